@@ -2,9 +2,14 @@ import { useRef } from "react";
 import "./App.css";
 import OrientationGetter from "./components/OrientationGetter.tsx";
 import ThreeScene from "./components/ThreeScene.tsx";
+import SpectatorView from "./components/SpectatorView.tsx";
 
 import { UpdateModelFunc, UpdateModelProps } from "./type/func.ts";
 
+// 大屏观战：/?view=spectator，观看房间时加上 &room=<房间码>
+const isSpectator =
+  new URLSearchParams(window.location.search).get("view") === "spectator";
+
 function App() {
   const updateModel = useRef<UpdateModelFunc | null>(null);
   const startUpdate = (updateModelProps: UpdateModelProps) => {
@@ -12,6 +17,9 @@ function App() {
       updateModel.current(updateModelProps);
     }
   };
+  if (isSpectator) {
+    return <SpectatorView />;
+  }
   return (
     <div className="flex items-center justify-center flex-col h-full overflow-hidden pt-10  pb-10 w-full box-border">
       <div>
@@ -13,6 +13,12 @@ interface OrientationPosition {
   gamma: number;
 }
 
+// 倾斜超过 NAV_TILT 度发送一次菜单导航，回到 NAV_RELEASE 度以内后才能再次触发
+const NAV_TILT = 25;
+const NAV_RELEASE = 10;
+
+type NavCommand = "up" | "down" | "left" | "right" | "select" | "back";
+
 export default function OrientationGetter({
   updateModel,
 }: OrientationGetterProps) {
@@ -20,6 +26,8 @@ export default function OrientationGetter({
   const [beta, setBeta] = useState(0);
   const [alpha, setAlpha] = useState(0);
   const ws = useRef<WebSocket | null>(null);
+  // 游戏分配的座位（1-4），双打时第 N 个座位控制第 N 名玩家的球拍
+  const [seat, setSeat] = useState<number | null>(null);
   const status = useRef<boolean>(false);
   let orientationPermission: boolean = false;
   const wsStartStatus = useRef<boolean>(false);
@@ -30,10 +38,40 @@ export default function OrientationGetter({
   const [heading, setHeading] = useState(0);
   const originHeading = useRef<number | null>(null);
 
+  // 菜单导航的中立姿态，第一次收到姿态时记录，set origin position 时更新
+  const navOrigin = useRef<{ beta: number; gamma: number } | null>(null);
+  const navArmed = useRef(true);
+
+  function sendNav(command: NavCommand) {
+    if (status.current && ws.current) ws.current.send(`nav:${command}`);
+  }
+
+  // 前后倾斜为上下，左右倾斜为左右
+  function handleTiltNav(beta: number, gamma: number) {
+    if (!navOrigin.current) {
+      navOrigin.current = { beta, gamma };
+      return;
+    }
+    const dBeta = beta - navOrigin.current.beta;
+    const dGamma = gamma - navOrigin.current.gamma;
+    const tilt = Math.max(Math.abs(dBeta), Math.abs(dGamma));
+    if (tilt < NAV_RELEASE) {
+      navArmed.current = true;
+    } else if (tilt > NAV_TILT && navArmed.current) {
+      navArmed.current = false;
+      if (Math.abs(dBeta) > Math.abs(dGamma)) {
+        sendNav(dBeta < 0 ? "up" : "down");
+      } else {
+        sendNav(dGamma < 0 ? "left" : "right");
+      }
+    }
+  }
+
   function handleOrientation(event: any) {
     const newAlpha = event.alpha;
     const newBeta = event.beta;
     const newGamma = event.gamma;
+    handleTiltNav(newBeta, newGamma);
     // 更新状态
     setAlpha(newAlpha.toFixed(0));
     setBeta(newBeta.toFixed(0));
@@ -120,16 +158,28 @@ export default function OrientationGetter({
       if (!orientationPermission) getPermission();
       if (!wsStartStatus.current) {
         wsStartStatus.current = true;
-        ws.current = new WebSocket("wss://192.168.1.105:8080");
+        // 网页与 WebSocket 由游戏端同一端口提供
+        const scheme = window.location.protocol === "https:" ? "wss" : "ws";
+        ws.current = new WebSocket(`${scheme}://${window.location.host}/ws`);
         // ws = new WebSocket("wss://dev.local:8080");
         ws.current.onopen = () => {
           if (ws.current?.OPEN) {
             ws.current.send("hello");
+            // 通过 ?room=<房间码> 加入指定房间
+            const room = new URLSearchParams(window.location.search).get("room");
+            if (room) ws.current.send(`join:${room}`);
+            // 通过 ?seat=<1-4> 选择座位，否则由游戏按连接顺序分配
+            const seatParam = new URLSearchParams(window.location.search).get("seat");
+            if (seatParam) ws.current.send(`seat:${seatParam}`);
             status.current = true;
           }
         };
 
-        ws.current.onmessage = (e) => console.log("收到:", e.data);
+        ws.current.onmessage = (e) => {
+          console.log("收到:", e.data);
+          const match = /^seat:(\d+)$/.exec(e.data);
+          if (match) setSeat(Number(match[1]));
+        };
         ws.current.onerror = (e) => {
           console.log("error:", e);
           // setWsStatusLogs(JSON.stringify(e));
@@ -244,6 +294,7 @@ export default function OrientationGetter({
       beta: beta,
       gamma: gamma,
     };
+    navOrigin.current = null;
   }
 
   useEffect(() => {
@@ -255,11 +306,20 @@ export default function OrientationGetter({
 
   return (
     <div className="flex flex-col items-center justify-center">
+      {seat && <div className="text-2xl">Player {seat}</div>}
       <div>showDelta:{showDelta}</div>
       <div>{alpha}</div>
       <button onClick={getPermission}>click to get permission</button>
       <button onClick={handleClick}>click to send message</button>
       <button onClick={setOriginPosition}>set origin position</button>
+      <div className="flex gap-4 mt-4">
+        <button className="px-8 py-6 text-xl" onClick={() => sendNav("back")}>
+          back
+        </button>
+        <button className="px-8 py-6 text-xl" onClick={() => sendNav("select")}>
+          select
+        </button>
+      </div>
     </div>
   );
 }
@@ -0,0 +1,208 @@
+import { useEffect, useRef, useState } from "react";
+import * as THREE from "three";
+import { GLTFLoader } from "three/addons/loaders/GlTFLoader.js";
+
+import { PoseData, SpectatorFrame, SpectatorMessage } from "../type/spectator";
+
+// 与游戏里的 Court 一致：ITTF 标准球桌 2.74 × 1.525 m，桌面高 0.76 m，球直径 40 mm
+const TABLE_HALF_EXTENTS = new THREE.Vector3(1.37, 0.38, 0.7625);
+const BALL_RADIUS = 0.02;
+
+interface ReceivedFrame {
+  frame: SpectatorFrame;
+  receivedAt: number;
+}
+
+function applyPose(
+  object: THREE.Object3D,
+  from: PoseData,
+  to: PoseData,
+  t: number
+) {
+  const a = new THREE.Vector3(...from.translation);
+  const b = new THREE.Vector3(...to.translation);
+  object.position.copy(a.lerp(b, t));
+  const qa = new THREE.Quaternion(...from.rotation);
+  const qb = new THREE.Quaternion(...to.rotation);
+  object.quaternion.copy(qa.slerp(qb, t));
+}
+
+export default function SpectatorView() {
+  const threeContainer = useRef<HTMLDivElement | null>(null);
+  const frames = useRef<ReceivedFrame[]>([]);
+  const interval = useRef<number>(50);
+  const [status, setStatus] = useState("Connecting...");
+  const [frame, setFrame] = useState<SpectatorFrame | null>(null);
+
+  useEffect(() => {
+    const container = threeContainer.current!;
+    const scene = new THREE.Scene();
+    scene.background = new THREE.Color(0x202020);
+
+    const camera = new THREE.PerspectiveCamera(
+      60,
+      container.clientWidth / container.clientHeight,
+      0.1,
+      100
+    );
+    // 与游戏观战画面相同的侧面视角
+    camera.position.set(0, 2, 2.5);
+    camera.lookAt(0, 0.76, 0);
+
+    const renderer = new THREE.WebGLRenderer({ antialias: true });
+    renderer.setSize(container.clientWidth, container.clientHeight);
+    container.appendChild(renderer.domElement);
+
+    scene.add(new THREE.AmbientLight(0xffffff, 1.5));
+    const light = new THREE.PointLight(0xffffff, 20);
+    light.position.set(0, 3, 0);
+    scene.add(light);
+
+    const table = new THREE.Mesh(
+      new THREE.BoxGeometry(
+        TABLE_HALF_EXTENTS.x * 2,
+        TABLE_HALF_EXTENTS.y * 2,
+        TABLE_HALF_EXTENTS.z * 2
+      ),
+      new THREE.MeshStandardMaterial({ color: 0x1d4f91 })
+    );
+    table.position.y = TABLE_HALF_EXTENTS.y;
+    scene.add(table);
+
+    const ball = new THREE.Mesh(
+      new THREE.SphereGeometry(BALL_RADIUS, 16, 16),
+      new THREE.MeshStandardMaterial({ color: 0xffffff })
+    );
+    ball.visible = false;
+    scene.add(ball);
+
+    const rackets: THREE.Object3D[] = [];
+    let racketModel: THREE.Group | null = null;
+    new GLTFLoader().load(
+      "/models/pong-racket.glb",
+      (gltf) => {
+        racketModel = gltf.scene;
+      },
+      undefined,
+      (error) => console.error("An error happened", error)
+    );
+
+    function syncRackets(count: number) {
+      while (racketModel && rackets.length < count) {
+        const racket = racketModel.clone();
+        scene.add(racket);
+        rackets.push(racket);
+      }
+      rackets.forEach((racket, i) => (racket.visible = i < count));
+    }
+
+    // 在最近两帧之间插值，画面比最新数据晚一个推送间隔
+    let animation = 0;
+    function animate() {
+      animation = requestAnimationFrame(animate);
+      const received = frames.current;
+      if (received.length > 0) {
+        const current = received[received.length - 1];
+        const previous = received.length > 1 ? received[0] : current;
+        const t = Math.min(
+          (performance.now() - current.receivedAt) / interval.current,
+          1
+        );
+        const { frame: from } = previous;
+        const { frame: to } = current;
+
+        if (from.ball && to.ball) {
+          ball.visible = true;
+          applyPose(ball, from.ball, to.ball, t);
+        } else {
+          ball.visible = false;
+        }
+        syncRackets(to.rackets.length);
+        rackets.forEach((racket, i) => {
+          if (i < to.rackets.length) {
+            applyPose(racket, from.rackets[i] ?? to.rackets[i], to.rackets[i], t);
+          }
+        });
+      }
+      renderer.render(scene, camera);
+    }
+    animate();
+
+    function handleResize() {
+      camera.aspect = container.clientWidth / container.clientHeight;
+      camera.updateProjectionMatrix();
+      renderer.setSize(container.clientWidth, container.clientHeight);
+    }
+    window.addEventListener("resize", handleResize);
+
+    const scheme = window.location.protocol === "https:" ? "wss" : "ws";
+    const room = new URLSearchParams(window.location.search).get("room");
+    const query = room ? `?room=${encodeURIComponent(room)}` : "";
+    const ws = new WebSocket(
+      `${scheme}://${window.location.host}/spectate${query}`
+    );
+    ws.onmessage = (event) => {
+      const message: SpectatorMessage = JSON.parse(event.data);
+      switch (message.type) {
+        case "welcome":
+          interval.current = 1000 / message.rate;
+          setStatus(room ? `Watching room ${room}` : "Watching");
+          break;
+        case "snapshot":
+          // 中途加入时先显示当前状态，不做插值
+          frames.current = [{ frame: message.frame, receivedAt: 0 }];
+          setFrame(message.frame);
+          break;
+        case "frame":
+          frames.current = [
+            frames.current[frames.current.length - 1],
+            { frame: message.frame, receivedAt: performance.now() },
+          ].filter(Boolean);
+          setFrame(message.frame);
+          break;
+        case "error":
+          setStatus(`Error: ${message.message}`);
+          break;
+      }
+    };
+    ws.onclose = () => setStatus("Disconnected");
+
+    return () => {
+      ws.close();
+      cancelAnimationFrame(animation);
+      window.removeEventListener("resize", handleResize);
+      renderer.dispose();
+      container.removeChild(renderer.domElement);
+    };
+  }, []);
+
+  return (
+    <div className="relative w-full h-full">
+      <div ref={threeContainer} className="w-full h-full"></div>
+      <div className="absolute top-2 left-2 text-left">
+        <div>{status}</div>
+        {frame && (
+          <>
+            <div>State: {frame.state}</div>
+            <div>
+              {frame.score.launched ? "Rally" : "Serving"} · Table bounces:{" "}
+              {frame.score.table_bounces}
+            </div>
+            {frame.score.points && frame.score.games && (
+              <div>
+                Games: {frame.score.games[0]} - {frame.score.games[1]} · Points:{" "}
+                {frame.score.points[0]} - {frame.score.points[1]} · Server: Player{" "}
+                {(frame.score.server ?? 0) + 1}
+              </div>
+            )}
+            {frame.score.hits !== undefined && (
+              <div>
+                Hits: {frame.score.hits} · Rallies: {frame.score.rallies}
+              </div>
+            )}
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
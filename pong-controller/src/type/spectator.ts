@@ -0,0 +1,40 @@
+// 观战协议，见 docs/spectator-protocol.md
+
+interface PoseData {
+    translation: [number, number, number];
+    rotation: [number, number, number, number];
+}
+
+interface SpectatorScore {
+    launched: boolean;
+    table_bounces: number;
+    hits?: number;
+    rallies?: number;
+    // 计分的比赛才有
+    points?: [number, number];
+    games?: [number, number];
+    server?: number;
+}
+
+interface SpectatorFrame {
+    seq: number;
+    time: number;
+    state: string;
+    room?: string;
+    ball: PoseData | null;
+    rackets: PoseData[];
+    score: SpectatorScore;
+}
+
+type SpectatorMessage =
+    | { type: "welcome"; protocol: number; rate: number; room?: string }
+    | { type: "snapshot"; frame: SpectatorFrame }
+    | { type: "frame"; frame: SpectatorFrame }
+    | { type: "error"; message: string };
+
+export type {
+    PoseData,
+    SpectatorScore,
+    SpectatorFrame,
+    SpectatorMessage,
+}
@@ -0,0 +1,182 @@
+# English UI text. Ids are shared with zh.ftl; missing ids fall back to this file.
+
+## Main menu
+menu-title = Pong Game
+menu-play = Play
+menu-settings = Settings
+menu-stats = Stats
+menu-credits = Credits
+menu-exit = Exit
+menu-back = Back
+
+## Quit dialog
+quit-title = Quit game?
+quit-message = Connected controllers will be disconnected.
+quit-confirm = Quit
+quit-cancel = Cancel
+
+## Mode select
+menu-mode-select = Select Mode
+menu-local-match = Local Match
+menu-versus-ai = Versus AI
+menu-doubles = Doubles
+doubles-team = { $first } & { $second }
+menu-practice = Practice Mode
+menu-host-online = Host Online
+menu-join-online = Join Online
+menu-rooms = Rooms
+menu-tournament = Tournament
+
+## Difficulty
+menu-difficulty = Difficulty
+difficulty-easy = Easy
+difficulty-normal = Normal
+difficulty-hard = Hard
+
+## Credits
+credits-body =
+    A 3D table tennis game played with your phone as the racket.
+    Built with Bevy and Rapier.
+    Phone controller built with React and three.js.
+    Thanks for playing!
+
+## Settings
+controls-player = Player { $number }
+controls-split = Split screen
+controls-volume = Volume
+controls-effects = Effects
+controls-language = Language
+volume-off = Off
+
+scheme-phone = Phone
+scheme-mouse = Mouse
+scheme-keyboard = Keyboard
+scheme-gamepad = Gamepad
+scheme-ai = AI
+scheme-replay = Replay
+
+split-side-by-side = Side by side
+split-stacked = Stacked
+split-windows = Windows
+
+effect-trail = Trail
+effect-bounce = Bounce
+effect-landing = Landing
+effect-flash = Flash
+
+## Stats
+stats-players = Players
+stats-recent-matches = Recent matches
+stats-practice = Practice
+stats-no-matches = No matches yet
+stats-no-practice = No practice sessions yet
+stats-profile = { $player }  { $won } W / { $lost } L  win rate { $rate }  · { $age }
+stats-match = [{ $mode }] { $left } vs { $right }  { $left-games }-{ $right-games } ({ $games })  { $result } · longest rally { $rally } · fastest { $speed } km/h · { $age }
+stats-match-won = { $player } won
+stats-match-unfinished = unfinished
+stats-mode-local = Local
+stats-mode-online = Online
+stats-device-online = Online
+stats-device-wall = Wall
+stats-practice-session = { $shots } shots in { $duration }s · avg { $average } km/h · best { $best } km/h · { $age }
+stats-practice-trend = Recent average { $recent } km/h ({ $change } km/h vs earlier)
+age-just-now = just now
+age-minutes = { $count }m ago
+age-hours = { $count }h ago
+age-days = { $count }d ago
+
+## In-game HUD
+hud-exit = Esc
+player-wall = Wall
+player-host = Host
+player-guest = Guest
+hud-move-speed = move speed:
+hud-driver-speed = { $driver } { $speed } m/s
+hud-rally = Rally { $rally } · Last shot { $speed } km/h
+hud-point =
+    Point { $player }
+    { $reason }
+hud-game =
+    Game { $player }
+    { $left } - { $right }
+reason-out = Out
+reason-own-side = Own side
+reason-double-bounce = Double bounce
+reason-out-of-turn = Out of turn
+reason-wrong-half = Serve not diagonal
+
+summary-winner = { $player } wins the match!
+summary-versus = { $left } vs { $right }
+summary-games = Games: { $games }
+summary-points = Points won: { $left } - { $right }
+summary-shots = Shots: { $left } - { $right }
+summary-longest-rally = Longest rally: { $rally }
+summary-fastest-shot = Fastest shot: { $speed } km/h
+summary-rematch = Rematch
+summary-waiting = Waiting for host to rematch...
+summary-main-menu = Main Menu
+summary-bracket = Back to Bracket
+
+## Tournament
+tournament-format = Format
+tournament-single-elimination = Single elimination
+tournament-round-robin = Round robin
+tournament-player-placeholder = Player { $number }
+tournament-add-player = Add player
+tournament-remove = Remove
+tournament-start = Start
+tournament-progress = { $played } / { $total } matches played
+tournament-round = Round { $number }
+tournament-semifinal = Semifinal
+tournament-final = Final
+tournament-bye = Bye
+tournament-tbd = TBD
+tournament-standings = Standings
+tournament-standing = { $rank }. { $player }  { $won } W / { $lost } L  ({ $diff })
+tournament-recent = Recent results
+tournament-no-results = No results yet
+tournament-result = { $left } { $left-games } - { $right-games } { $right }
+tournament-next-match = Next: { $left } vs { $right }
+tournament-play = Play match
+tournament-abandon = Abandon
+tournament-abandon-title = Abandon tournament?
+tournament-abandon-message = All results of this tournament will be lost.
+tournament-champion = { $player } is the champion!
+tournament-new = New tournament
+
+## Pause
+pause-title = Paused
+pause-resume = Resume
+pause-restart = Restart
+pause-settings = Settings
+pause-quit = Quit to Menu
+
+## Controller server and online play
+status-network-error = Network error: { $error }
+status-error-tls = TLS unavailable: { $reason }
+status-error-bind = Cannot bind port { $port }: { $detail }
+status-error-certificate = Cannot read certificate: { $detail }
+status-error-server = Server error: { $detail }
+status-error-reload = Certificate reload failed: { $detail }
+status-controller = Controller: { $url }
+status-controller-mdns = Controller: { $url }  ({ $fallback })
+toast-controller-connected = Controller connected ({ $count } online)
+toast-controller-disconnected = Controller disconnected ({ $count } online)
+toast-replay-missing = No racket recording: set PONG_REPLAY_PATH. The AI plays instead.
+toast-replay-unreadable = Cannot read racket recording { $path }. The AI plays instead.
+online-error = Online error: { $error }
+online-error-bind = Cannot bind online port { $addr }: { $detail }
+online-waiting = Waiting for opponent on UDP port { $port }
+online-opponent-left = Opponent left, waiting on UDP port { $port }
+online-hosting = Hosting - opponent connected
+online-connecting = Connecting to { $host }
+online-host-left = Host { $host } left
+online-connected = Connected to { $host }  (unacked inputs: { $pending })
+
+## Spectating rooms
+rooms-none = No rooms. Set PONG_ROOMS to host rooms.
+rooms-info =
+    Room { $code } ({ $index }/{ $count })
+    Controllers: { $controllers }  Hits: { $hits }  Rallies: { $rallies }
+    Join: { $url }
+    <- / -> or 1-9 to switch rooms
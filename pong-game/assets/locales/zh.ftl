@@ -0,0 +1,182 @@
+# 中文界面文本，id 与 en.ftl 相同，缺少的条目使用英文
+
+## 主菜单
+menu-title = 乒乓球
+menu-play = 开始游戏
+menu-settings = 设置
+menu-stats = 战绩
+menu-credits = 制作人员
+menu-exit = 退出
+menu-back = 返回
+
+## 退出对话框
+quit-title = 退出游戏？
+quit-message = 已连接的控制器会断开。
+quit-confirm = 退出
+quit-cancel = 取消
+
+## 模式选择
+menu-mode-select = 选择模式
+menu-local-match = 本地对战
+menu-versus-ai = 对战电脑
+menu-doubles = 双打
+doubles-team = { $first }、{ $second }
+menu-practice = 练习模式
+menu-host-online = 创建联机
+menu-join-online = 加入联机
+menu-rooms = 房间
+menu-tournament = 锦标赛
+
+## 难度
+menu-difficulty = 难度
+difficulty-easy = 简单
+difficulty-normal = 普通
+difficulty-hard = 困难
+
+## 制作人员
+credits-body =
+    用手机当球拍的 3D 乒乓球游戏。
+    基于 Bevy 和 Rapier 开发。
+    手机控制器使用 React 和 three.js。
+    感谢游玩！
+
+## 设置
+controls-player = 玩家 { $number }
+controls-split = 分屏
+controls-volume = 音量
+controls-effects = 效果
+controls-language = 语言
+volume-off = 关
+
+scheme-phone = 手机
+scheme-mouse = 鼠标
+scheme-keyboard = 键盘
+scheme-gamepad = 手柄
+scheme-ai = 电脑
+scheme-replay = 回放
+
+split-side-by-side = 左右
+split-stacked = 上下
+split-windows = 多窗口
+
+effect-trail = 拖尾
+effect-bounce = 落点
+effect-landing = 预测
+effect-flash = 闪光
+
+## 战绩
+stats-players = 玩家
+stats-recent-matches = 最近的比赛
+stats-practice = 练习
+stats-no-matches = 还没有比赛
+stats-no-practice = 还没有练习记录
+stats-profile = { $player }  { $won } 胜 / { $lost } 负  胜率 { $rate }  · { $age }
+stats-match = [{ $mode }] { $left } 对 { $right }  { $left-games }-{ $right-games }（{ $games }）  { $result } · 最长回合 { $rally } · 最快 { $speed } km/h · { $age }
+stats-match-won = { $player } 获胜
+stats-match-unfinished = 未完成
+stats-mode-local = 本地
+stats-mode-online = 联机
+stats-device-online = 联机
+stats-device-wall = 墙
+stats-practice-session = { $duration } 秒内击球 { $shots } 次 · 平均 { $average } km/h · 最快 { $best } km/h · { $age }
+stats-practice-trend = 最近平均 { $recent } km/h（比之前 { $change } km/h）
+age-just-now = 刚刚
+age-minutes = { $count } 分钟前
+age-hours = { $count } 小时前
+age-days = { $count } 天前
+
+## 比赛界面
+hud-exit = Esc 退出
+player-wall = 墙
+player-host = 主机
+player-guest = 客人
+hud-move-speed = 移动速度：
+hud-driver-speed = { $driver } { $speed } m/s
+hud-rally = 回合 { $rally } · 上一拍 { $speed } km/h
+hud-point =
+    { $player } 得分
+    { $reason }
+hud-game =
+    { $player } 赢下一局
+    { $left } - { $right }
+reason-out = 出界
+reason-own-side = 落在己方台面
+reason-double-bounce = 两次弹跳
+reason-out-of-turn = 击球顺序错误
+reason-wrong-half = 发球未落在对角半区
+
+summary-winner = { $player } 赢得比赛！
+summary-versus = { $left } 对 { $right }
+summary-games = 各局比分：{ $games }
+summary-points = 得分：{ $left } - { $right }
+summary-shots = 击球：{ $left } - { $right }
+summary-longest-rally = 最长回合：{ $rally }
+summary-fastest-shot = 最快击球：{ $speed } km/h
+summary-rematch = 再来一局
+summary-waiting = 等待主机重新开始……
+summary-main-menu = 主菜单
+summary-bracket = 返回对阵表
+
+## 锦标赛
+tournament-format = 赛制
+tournament-single-elimination = 单败淘汰
+tournament-round-robin = 循环赛
+tournament-player-placeholder = 玩家 { $number }
+tournament-add-player = 添加玩家
+tournament-remove = 移除
+tournament-start = 开始
+tournament-progress = 已完成 { $played } / { $total } 场
+tournament-round = 第 { $number } 轮
+tournament-semifinal = 半决赛
+tournament-final = 决赛
+tournament-bye = 轮空
+tournament-tbd = 待定
+tournament-standings = 积分榜
+tournament-standing = { $rank }. { $player }  { $won } 胜 / { $lost } 负  ({ $diff })
+tournament-recent = 最近结果
+tournament-no-results = 还没有结果
+tournament-result = { $left } { $left-games } - { $right-games } { $right }
+tournament-next-match = 下一场：{ $left } 对 { $right }
+tournament-play = 开始比赛
+tournament-abandon = 放弃
+tournament-abandon-title = 放弃锦标赛？
+tournament-abandon-message = 本次锦标赛的所有结果都会丢失。
+tournament-champion = { $player } 获得冠军！
+tournament-new = 新的锦标赛
+
+## 暂停
+pause-title = 已暂停
+pause-resume = 继续
+pause-restart = 重新开始
+pause-settings = 设置
+pause-quit = 回到主菜单
+
+## 控制器服务和联机
+status-network-error = 网络错误：{ $error }
+status-error-tls = TLS 不可用：{ $reason }
+status-error-bind = 无法绑定端口 { $port }：{ $detail }
+status-error-certificate = 无法读取证书：{ $detail }
+status-error-server = 服务器错误：{ $detail }
+status-error-reload = 证书重新加载失败：{ $detail }
+status-controller = 控制器：{ $url }
+status-controller-mdns = 控制器：{ $url }（{ $fallback }）
+toast-controller-connected = 控制器已连接（在线 { $count } 个）
+toast-controller-disconnected = 控制器已断开（在线 { $count } 个）
+toast-replay-missing = 没有球拍录制：请设置 PONG_REPLAY_PATH，改由 AI 控制
+toast-replay-unreadable = 无法读取球拍录制 { $path }，改由 AI 控制
+online-error = 联机错误：{ $error }
+online-error-bind = 无法绑定联机端口 { $addr }：{ $detail }
+online-waiting = 等待对手加入，UDP 端口 { $port }
+online-opponent-left = 对手已离开，等待中，UDP 端口 { $port }
+online-hosting = 主机 - 对手已连接
+online-connecting = 正在连接 { $host }
+online-host-left = 主机 { $host } 已离开
+online-connected = 已连接到 { $host }（未确认的输入：{ $pending }）
+
+## 观战
+rooms-none = 没有房间，设置 PONG_ROOMS 以创建房间。
+rooms-info =
+    房间 { $code }（{ $index }/{ $count }）
+    控制器：{ $controllers }  击球：{ $hits }  回合：{ $rallies }
+    加入：{ $url }
+    ← / → 或 1-9 切换房间
@@ -1,25 +1,110 @@
 use bevy::prelude::*;
 
+use crate::components::theme::{TextSize, UiTheme};
+
+/// 当前选中的选项，例如 Settings 页面中每名玩家的控制方式
 #[derive(Component)]
 pub struct SelectedOption;
 
-pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
-pub const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
-pub const HOVERED_PRESSED_BUTTON: Color = Color::srgb(0.25, 0.65, 0.25);
-pub const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
+/// 键盘、手柄或手机导航时的焦点，效果与鼠标悬停相同
+#[derive(Component)]
+pub struct Focused;
+
+/// 按钮的样式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonKind {
+    /// 菜单列表中的按钮
+    Menu,
+    /// 选项行中的按钮，字小一些、带内边距
+    Option,
+}
+
+/// 带文字的按钮，背景色由 [`button_system`] 按主题更新
+pub fn button(theme: &UiTheme, label: String, kind: ButtonKind) -> impl Bundle + use<> {
+    let (size, padding) = match kind {
+        ButtonKind::Menu => (TextSize::Button, UiRect::ZERO),
+        ButtonKind::Option => (TextSize::Option, UiRect::axes(Val::Px(10.0), Val::Px(5.0))),
+    };
+    (
+        theme.text(label, size),
+        Button,
+        Node {
+            margin: UiRect::all(Val::Px(10.0)),
+            padding,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(theme.button),
+    )
+}
+
+/// 比赛画面右上角的 Esc 按钮
+pub fn corner_button(theme: &UiTheme, label: String) -> impl Bundle + use<> {
+    (
+        theme.text(label, TextSize::Button),
+        Button,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            margin: UiRect::all(Val::Px(10.0)),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(theme.button),
+    )
+}
+
+impl UiTheme {
+    /// 按钮在各状态下的背景色
+    pub fn button_color(&self, interaction: Interaction, selected: bool, focused: bool) -> Color {
+        let hovered = interaction == Interaction::Hovered || focused;
+        match (interaction, selected, hovered) {
+            (Interaction::Pressed, _, _) => self.button_pressed,
+            (_, true, true) => self.button_selected_hovered,
+            (_, true, false) => self.button_pressed,
+            (_, false, true) => self.button_hovered,
+            (_, false, false) => self.button,
+        }
+    }
+}
+
+pub fn button_plugin(app: &mut App) {
+    app.add_systems(Update, button_system);
+}
+
+/// 本帧被按下或松开的按钮和它的动作，各页面的 `*_action` 系统共用
+pub type ButtonActions<'w, 's, A> =
+    Query<'w, 's, (&'static Interaction, &'static A), (Changed<Interaction>, With<Button>)>;
+
+/// 决定按钮背景色的状态
+type ButtonLook = (
+    Entity,
+    Ref<'static, Interaction>,
+    &'static mut BackgroundColor,
+    Option<Ref<'static, SelectedOption>>,
+    Option<Ref<'static, Focused>>,
+);
 
+/// 鼠标、导航焦点、选中状态或主题变化时更新背景色
 pub fn button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, Option<&SelectedOption>),
-        (Changed<Interaction>, With<Button>),
-    >,
+    theme: Res<UiTheme>,
+    mut removed_selected: RemovedComponents<SelectedOption>,
+    mut removed_focus: RemovedComponents<Focused>,
+    mut buttons: Query<ButtonLook, With<Button>>,
 ) {
-    for (interaction, mut background_color, selected) in &mut interaction_query {
-        *background_color = match (*interaction, selected) {
-            (Interaction::Pressed, _) | (Interaction::None, Some(_)) => PRESSED_BUTTON.into(),
-            (Interaction::Hovered, Some(_)) => HOVERED_PRESSED_BUTTON.into(),
-            (Interaction::Hovered, None) => HOVERED_BUTTON.into(),
-            (Interaction::None, None) => NORMAL_BUTTON.into(),
+    let removed: Vec<Entity> = removed_selected.read().chain(removed_focus.read()).collect();
+    for (entity, interaction, mut background_color, selected, focused) in &mut buttons {
+        let changed = theme.is_changed()
+            || interaction.is_changed()
+            || selected.as_ref().is_some_and(|selected| selected.is_added())
+            || focused.as_ref().is_some_and(|focused| focused.is_added())
+            || removed.contains(&entity);
+        if changed {
+            *background_color =
+                theme.button_color(*interaction, selected.is_some(), focused.is_some()).into();
         }
     }
-}
\ No newline at end of file
+}
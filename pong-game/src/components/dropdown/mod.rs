@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::components::Popup;
+use crate::components::button::{ButtonKind, Focused, SelectedOption, button};
+use crate::components::theme::{POPUP_LAYER, UiTheme};
+
+/// 下拉选择框：按下后在按钮下方展开选项列表。使用者通过 `Changed<Dropdown>` 读取选择
+#[derive(Component, Clone, Debug)]
+pub struct Dropdown {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+/// 展开的列表，盖住整个窗口，点击列表以外的地方关闭
+#[derive(Component)]
+struct DropdownPopup {
+    dropdown: Entity,
+}
+
+#[derive(Component)]
+struct DropdownItem {
+    dropdown: Entity,
+    index: usize,
+}
+
+pub fn dropdown(theme: &UiTheme, options: Vec<String>, selected: usize) -> impl Bundle + use<> {
+    let label = options.get(selected).cloned().unwrap_or_default();
+    (
+        button(theme, label, ButtonKind::Option),
+        Dropdown { options, selected },
+    )
+}
+
+pub fn dropdown_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            open_dropdown,
+            choose_dropdown_item,
+            close_dropdown,
+            update_dropdown_label,
+        )
+            .chain(),
+    );
+}
+
+fn open_dropdown(
+    mut commands: Commands,
+    theme: Res<UiTheme>,
+    dropdowns: Query<
+        (Entity, &Interaction, &Dropdown, &GlobalTransform, &ComputedNode),
+        Changed<Interaction>,
+    >,
+    popups: Query<&DropdownPopup>,
+) {
+    for (entity, interaction, dropdown, transform, node) in dropdowns.iter() {
+        if *interaction != Interaction::Pressed
+            || popups.iter().any(|popup| popup.dropdown == entity)
+        {
+            continue;
+        }
+        // 布局结果为物理像素，换算成逻辑像素后放在按钮正下方
+        let scale = node.inverse_scale_factor();
+        let size = node.size() * scale;
+        let center = transform.translation().truncate() * scale;
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                Interaction::default(),
+                FocusPolicy::Block,
+                GlobalZIndex(POPUP_LAYER),
+                Popup,
+                DropdownPopup { dropdown: entity },
+            ))
+            .with_children(|parent| {
+                parent
+                    .spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(center.x - size.x / 2.0),
+                            top: Val::Px(center.y + size.y / 2.0),
+                            min_width: Val::Px(size.x),
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        BackgroundColor(theme.panel),
+                    ))
+                    .with_children(|list| {
+                        for (index, option) in dropdown.options.iter().enumerate() {
+                            let mut item = list.spawn((
+                                button(&theme, option.clone(), ButtonKind::Option),
+                                DropdownItem {
+                                    dropdown: entity,
+                                    index,
+                                },
+                            ));
+                            // 导航直接从当前选项开始
+                            if index == dropdown.selected {
+                                item.insert((SelectedOption, Focused));
+                            }
+                        }
+                    });
+            });
+    }
+}
+
+fn choose_dropdown_item(
+    mut commands: Commands,
+    items: Query<(&Interaction, &DropdownItem), Changed<Interaction>>,
+    popups: Query<(Entity, &DropdownPopup)>,
+    mut dropdowns: Query<&mut Dropdown>,
+) {
+    for (interaction, item) in items.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Ok(mut dropdown) = dropdowns.get_mut(item.dropdown)
+            && dropdown.selected != item.index
+        {
+            dropdown.selected = item.index;
+        }
+        for (popup, DropdownPopup { dropdown }) in popups.iter() {
+            if *dropdown == item.dropdown {
+                commands.entity(popup).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// 点击列表以外的地方，或者下拉框已经随页面一起移除时关闭列表
+fn close_dropdown(
+    mut commands: Commands,
+    popups: Query<(Entity, &Interaction, &DropdownPopup), Without<Button>>,
+    dropdowns: Query<(), With<Dropdown>>,
+) {
+    for (entity, interaction, popup) in popups.iter() {
+        if *interaction == Interaction::Pressed || !dropdowns.contains(popup.dropdown) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn update_dropdown_label(mut dropdowns: Query<(&Dropdown, &mut Text), Changed<Dropdown>>) {
+    for (dropdown, mut text) in dropdowns.iter_mut() {
+        if let Some(label) = dropdown.options.get(dropdown.selected)
+            && text.0 != *label
+        {
+            text.0 = label.clone();
+        }
+    }
+}
@@ -1 +1,47 @@
-pub mod button;
\ No newline at end of file
+use bevy::prelude::*;
+
+use crate::menu::navigation::MenuInput;
+
+pub mod button;
+pub mod dropdown;
+pub mod modal;
+pub mod qr;
+pub mod slider;
+pub mod text_input;
+pub mod theme;
+pub mod toast;
+pub mod toggle;
+
+/// 盖在页面上面的下拉列表或对话框，打开时返回键先关闭它
+#[derive(Component)]
+pub struct Popup;
+
+/// 界面组件：主题、按钮、滑块、开关、下拉框、文本框、对话框、提示和二维码
+pub fn components_plugin(app: &mut App) {
+    app.init_resource::<theme::UiTheme>()
+        .add_plugins((
+            button::button_plugin,
+            toggle::toggle_plugin,
+            slider::slider_plugin,
+            dropdown::dropdown_plugin,
+            text_input::text_input_plugin,
+            modal::modal_plugin,
+            toast::toast_plugin,
+            qr::qr_plugin,
+        ))
+        .add_systems(Update, close_popup_on_back);
+}
+
+/// 返回键关闭最上层的弹出层
+fn close_popup_on_back(
+    mut commands: Commands,
+    mut inputs: EventReader<MenuInput>,
+    popups: Query<(Entity, &GlobalZIndex), With<Popup>>,
+) {
+    if !inputs.read().any(|input| *input == MenuInput::Back) {
+        return;
+    }
+    if let Some((entity, _)) = popups.iter().max_by_key(|(_, z)| z.0) {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::components::Popup;
+use crate::components::theme::{MODAL_LAYER, TextSize, UiTheme, overlay_column};
+
+/// 对话框的根节点，盖住整个窗口，挡住后面的按钮
+#[derive(Component)]
+pub struct Modal;
+
+/// 按下后关闭所在的对话框
+#[derive(Component)]
+pub struct CloseModal;
+
+/// 生成对话框：标题、说明和 `buttons` 生成的一行按钮。
+/// `extra` 加在根节点上，例如页面的标记组件，离开页面时一起移除
+pub fn spawn_modal(
+    commands: &mut Commands,
+    theme: &UiTheme,
+    title: String,
+    message: String,
+    extra: impl Bundle,
+    buttons: impl FnOnce(&mut ChildBuilder),
+) -> Entity {
+    commands
+        .spawn((
+            overlay_column(0.0),
+            BackgroundColor(theme.scrim),
+            Interaction::default(),
+            FocusPolicy::Block,
+            GlobalZIndex(MODAL_LAYER),
+            Modal,
+            Popup,
+            extra,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(12.0),
+                        padding: UiRect::all(Val::Px(24.0)),
+                        ..default()
+                    },
+                    BackgroundColor(theme.panel),
+                ))
+                .with_children(|panel| {
+                    panel.spawn(theme.text(title, TextSize::Title));
+                    panel.spawn((
+                        theme.text(message, TextSize::Body),
+                        TextLayout::new_with_justify(JustifyText::Center),
+                    ));
+                    panel
+                        .spawn(Node {
+                            flex_direction: FlexDirection::Row,
+                            ..default()
+                        })
+                        .with_children(buttons);
+                });
+        })
+        .id()
+}
+
+pub fn modal_plugin(app: &mut App) {
+    app.add_systems(Update, close_modal);
+}
+
+/// 本帧被按下或松开的关闭按钮
+type CloseButtons = (With<CloseModal>, Changed<Interaction>);
+
+fn close_modal(
+    mut commands: Commands,
+    buttons: Query<(Entity, &Interaction), CloseButtons>,
+    parents: Query<&Parent>,
+    modals: Query<(), With<Modal>>,
+) {
+    for (entity, interaction) in buttons.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(modal) = parents.iter_ancestors(entity).find(|ancestor| modals.contains(*ancestor)) {
+            commands.entity(modal).despawn_recursive();
+        }
+    }
+}
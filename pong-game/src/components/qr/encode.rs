@@ -0,0 +1,509 @@
+//! QR 码编码：字节模式、纠错等级 L、版本 1 ~ 10（最多 271 字节），足够放下控制器地址
+
+/// 各版本的每块纠错码字数，以及两组 (块数, 每块数据码字数)
+const BLOCKS: [(usize, [(usize, usize); 2]); 10] = [
+    (7, [(1, 19), (0, 0)]),
+    (10, [(1, 34), (0, 0)]),
+    (15, [(1, 55), (0, 0)]),
+    (20, [(1, 80), (0, 0)]),
+    (26, [(1, 108), (0, 0)]),
+    (18, [(2, 68), (0, 0)]),
+    (20, [(2, 78), (0, 0)]),
+    (24, [(2, 97), (0, 0)]),
+    (30, [(2, 116), (0, 0)]),
+    (18, [(2, 68), (2, 69)]),
+];
+
+/// 各版本校正图形中心所在的行列
+const ALIGNMENT: [&[usize]; 10] = [
+    &[],
+    &[6, 18],
+    &[6, 22],
+    &[6, 26],
+    &[6, 30],
+    &[6, 34],
+    &[6, 22, 38],
+    &[6, 24, 42],
+    &[6, 26, 46],
+    &[6, 28, 50],
+];
+
+/// 纠错等级 L 在格式信息中的编码
+const ECC_LOW: u32 = 0b01;
+
+/// 编码后的模块矩阵，`true` 为深色
+#[derive(Clone)]
+pub struct QrMatrix {
+    pub size: usize,
+    modules: Vec<bool>,
+    /// 定位、时序、格式等固定图形，不放数据也不加掩码
+    function: Vec<bool>,
+}
+
+impl QrMatrix {
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.set(x, y, dark);
+        self.function[y * self.size + x] = true;
+    }
+}
+
+/// 数据太长时返回 `None`
+pub fn encode(data: &[u8]) -> Option<QrMatrix> {
+    let version = (1..=BLOCKS.len()).find(|&version| {
+        let (_, groups) = BLOCKS[version - 1];
+        let capacity = groups.iter().map(|(count, len)| count * len).sum::<usize>() * 8;
+        4 + count_bits(version) + data.len() * 8 <= capacity
+    })?;
+    let codewords = add_error_correction(version, &data_codewords(version, data));
+
+    let mut matrix = function_patterns(version);
+    draw_codewords(&mut matrix, &codewords);
+    (0..8).map(|mask| masked(&matrix, mask)).min_by_key(penalty)
+}
+
+/// 加上掩码并写入对应的格式信息
+fn masked(matrix: &QrMatrix, mask: u32) -> QrMatrix {
+    let mut candidate = matrix.clone();
+    apply_mask(&mut candidate, mask);
+    draw_format(&mut candidate, mask);
+    candidate
+}
+
+/// 字符数的位数
+fn count_bits(version: usize) -> usize {
+    if version <= 9 { 8 } else { 16 }
+}
+
+/// 模式、长度、数据、终止符和填充字节
+fn data_codewords(version: usize, data: &[u8]) -> Vec<u8> {
+    let (_, groups) = BLOCKS[version - 1];
+    let capacity = groups.iter().map(|(count, len)| count * len).sum::<usize>();
+
+    let mut bits = Vec::new();
+    let mut push = |value: usize, len: usize| {
+        for i in (0..len).rev() {
+            bits.push((value >> i) & 1 == 1);
+        }
+    };
+    push(0b0100, 4);
+    push(data.len(), count_bits(version));
+    for byte in data {
+        push(*byte as usize, 8);
+    }
+    let terminator = (capacity * 8 - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    bits.resize(bits.len().div_ceil(8) * 8, false);
+
+    let mut codewords = bits
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0u8, |byte, bit| (byte << 1) | *bit as u8))
+        .collect::<Vec<_>>();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() >= capacity {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+/// 分块计算纠错码字，再按列交错
+fn add_error_correction(version: usize, data: &[u8]) -> Vec<u8> {
+    let (ec_len, groups) = BLOCKS[version - 1];
+    let divisor = rs_divisor(ec_len);
+    let mut blocks = Vec::new();
+    let mut rest = data;
+    for (count, len) in groups {
+        for _ in 0..count {
+            let (block, tail) = rest.split_at(len);
+            blocks.push((block, rs_remainder(block, &divisor)));
+            rest = tail;
+        }
+    }
+
+    let max_len = blocks.iter().map(|(block, _)| block.len()).max().unwrap_or(0);
+    let mut result = Vec::new();
+    for i in 0..max_len {
+        result.extend(blocks.iter().filter_map(|(block, _)| block.get(i)));
+    }
+    for i in 0..ec_len {
+        result.extend(blocks.iter().map(|(_, ec)| ec[i]));
+    }
+    result
+}
+
+/// GF(256) 乘法，本原多项式 0x11D
+fn gf_mul(x: u8, y: u8) -> u8 {
+    let mut z: u16 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u16 >> i) & 1) * x as u16;
+    }
+    z as u8
+}
+
+fn rs_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_mul(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_mul(root, 0x02);
+    }
+    result
+}
+
+fn rs_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (value, coefficient) in result.iter_mut().zip(divisor) {
+            *value ^= gf_mul(*coefficient, factor);
+        }
+    }
+    result
+}
+
+fn function_patterns(version: usize) -> QrMatrix {
+    let size = version * 4 + 17;
+    let mut matrix = QrMatrix {
+        size,
+        modules: vec![false; size * size],
+        function: vec![false; size * size],
+    };
+
+    for i in 0..size {
+        matrix.set_function(6, i, i % 2 == 0);
+        matrix.set_function(i, 6, i % 2 == 0);
+    }
+
+    // 三个角上的定位图形，外圈一格为空白分隔
+    for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+        for dy in -4i32..=4 {
+            for dx in -4i32..=4 {
+                let (x, y) = (cx as i32 + dx, cy as i32 + dy);
+                if (0..size as i32).contains(&x) && (0..size as i32).contains(&y) {
+                    let distance = dx.abs().max(dy.abs());
+                    matrix.set_function(x as usize, y as usize, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    // 校正图形，和定位图形重叠的三个位置跳过
+    let positions = ALIGNMENT[version - 1];
+    let last = positions.len().saturating_sub(1);
+    for (i, &cx) in positions.iter().enumerate() {
+        for (j, &cy) in positions.iter().enumerate() {
+            if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                continue;
+            }
+            for dy in -2i32..=2 {
+                for dx in -2i32..=2 {
+                    let x = (cx as i32 + dx) as usize;
+                    let y = (cy as i32 + dy) as usize;
+                    matrix.set_function(x, y, dx.abs().max(dy.abs()) != 1);
+                }
+            }
+        }
+    }
+
+    // 先占住格式信息的位置，选好掩码后再写入
+    draw_format(&mut matrix, 0);
+
+    if version >= 7 {
+        let bits = version_bits(version);
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 == 1;
+            let a = size - 11 + i % 3;
+            let b = i / 3;
+            matrix.set_function(a, b, dark);
+            matrix.set_function(b, a, dark);
+        }
+    }
+    matrix
+}
+
+/// 版本信息：版本号加 12 位 BCH 校验，版本 7 起写在右上和左下
+fn version_bits(version: usize) -> u32 {
+    let mut remainder = version as u32;
+    for _ in 0..12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+    }
+    (version as u32) << 12 | remainder
+}
+
+/// 格式信息：纠错等级和掩码，BCH 编码后再异或固定掩码
+fn format_bits(mask: u32) -> u32 {
+    let data = ECC_LOW << 3 | mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    (data << 10 | remainder) ^ 0x5412
+}
+
+/// 格式信息写在定位图形旁边两处
+fn draw_format(matrix: &mut QrMatrix, mask: u32) {
+    let bits = format_bits(mask);
+    let bit = |i: usize| (bits >> i) & 1 == 1;
+    let size = matrix.size;
+
+    for i in 0..=5 {
+        matrix.set_function(8, i, bit(i));
+    }
+    matrix.set_function(8, 7, bit(6));
+    matrix.set_function(8, 8, bit(7));
+    matrix.set_function(7, 8, bit(8));
+    for i in 9..15 {
+        matrix.set_function(14 - i, 8, bit(i));
+    }
+    for i in 0..8 {
+        matrix.set_function(size - 1 - i, 8, bit(i));
+    }
+    for i in 8..15 {
+        matrix.set_function(8, size - 15 + i, bit(i));
+    }
+    // 固定的深色模块
+    matrix.set_function(8, size - 8, true);
+}
+
+/// 从右下角开始，两列一组上下蛇形填入数据，跳过时序图形所在的第 6 列
+fn draw_codewords(matrix: &mut QrMatrix, codewords: &[u8]) {
+    let size = matrix.size;
+    let total = codewords.len() * 8;
+    let mut index = 0;
+    let mut right = size as i32 - 1;
+    while right >= 1 {
+        if right == 6 {
+            right = 5;
+        }
+        let upward = (right + 1) & 2 == 0;
+        for vertical in 0..size {
+            let y = if upward { size - 1 - vertical } else { vertical };
+            for j in 0..2 {
+                let x = (right - j) as usize;
+                if matrix.function[y * size + x] || index >= total {
+                    continue;
+                }
+                let dark = (codewords[index / 8] >> (7 - index % 8)) & 1 == 1;
+                matrix.set(x, y, dark);
+                index += 1;
+            }
+        }
+        right -= 2;
+    }
+}
+
+fn apply_mask(matrix: &mut QrMatrix, mask: u32) {
+    let size = matrix.size;
+    for y in 0..size {
+        for x in 0..size {
+            if matrix.function[y * size + x] {
+                continue;
+            }
+            let invert = match mask {
+                0 => (x + y) % 2 == 0,
+                1 => y % 2 == 0,
+                2 => x % 3 == 0,
+                3 => (x + y) % 3 == 0,
+                4 => (x / 3 + y / 2) % 2 == 0,
+                5 => x * y % 2 + x * y % 3 == 0,
+                6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+            };
+            if invert {
+                let dark = matrix.get(x, y);
+                matrix.set(x, y, !dark);
+            }
+        }
+    }
+}
+
+/// 掩码的评分，越低越容易识别
+fn penalty(matrix: &QrMatrix) -> usize {
+    let size = matrix.size;
+    let mut score = 0;
+    let lines = |horizontal: bool| {
+        (0..size).map(move |i| {
+            (0..size)
+                .map(|j| if horizontal { matrix.get(j, i) } else { matrix.get(i, j) })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // 连续五个以上同色，以及类似定位图形的 1:1:3:1:1
+    const FINDER_LIKE: [bool; 11] = [
+        true, false, true, true, true, false, true, false, false, false, false,
+    ];
+    for line in lines(true).chain(lines(false)) {
+        let mut run = 1;
+        for i in 1..=size {
+            if i < size && line[i] == line[i - 1] {
+                run += 1;
+                continue;
+            }
+            if run >= 5 {
+                score += run - 2;
+            }
+            run = 1;
+        }
+        for window in line.windows(FINDER_LIKE.len()) {
+            if window == FINDER_LIKE || window.iter().rev().eq(FINDER_LIKE.iter()) {
+                score += 40;
+            }
+        }
+    }
+
+    // 2x2 同色
+    for y in 0..size - 1 {
+        for x in 0..size - 1 {
+            let color = matrix.get(x, y);
+            if matrix.get(x + 1, y) == color
+                && matrix.get(x, y + 1) == color
+                && matrix.get(x + 1, y + 1) == color
+            {
+                score += 3;
+            }
+        }
+    }
+
+    // 深色比例偏离一半
+    let dark = matrix.modules.iter().filter(|dark| **dark).count();
+    let total = size * size;
+    let deviation = (dark * 20).abs_diff(total * 10);
+    score += (deviation.div_ceil(total)).saturating_sub(1) * 10;
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(matrix: &QrMatrix) -> Vec<String> {
+        (0..matrix.size)
+            .map(|y| {
+                (0..matrix.size)
+                    .map(|x| if matrix.get(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reed_solomon_matches_reference_codewords() {
+        // "HELLO WORLD" 1-M 的数据码字和纠错码字
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        let ec = rs_remainder(&data, &rs_divisor(10));
+        assert_eq!(ec, [196, 35, 39, 119, 235, 215, 231, 226, 93, 23]);
+    }
+
+    #[test]
+    fn format_bits_match_spec_table() {
+        let expected = [
+            0x77C4, 0x72F3, 0x7DAA, 0x789D, 0x662F, 0x6318, 0x6C41, 0x6976,
+        ];
+        for (mask, bits) in expected.into_iter().enumerate() {
+            assert_eq!(format_bits(mask as u32), bits, "mask {mask}");
+        }
+    }
+
+    #[test]
+    fn version_bits_match_spec_table() {
+        let expected = [0x07C94, 0x085BC, 0x09A99, 0x0A4D3];
+        for (version, bits) in (7..=10).zip(expected) {
+            assert_eq!(version_bits(version), bits, "version {version}");
+        }
+    }
+
+    #[test]
+    fn mask_patterns_follow_spec() {
+        // 规范中 i 为行、j 为列
+        let conditions: [fn(usize, usize) -> bool; 8] = [
+            |i, j| (i + j) % 2 == 0,
+            |i, _| i % 2 == 0,
+            |_, j| j % 3 == 0,
+            |i, j| (i + j) % 3 == 0,
+            |i, j| (i / 2 + j / 3) % 2 == 0,
+            |i, j| (i * j) % 2 + (i * j) % 3 == 0,
+            |i, j| ((i * j) % 2 + (i * j) % 3) % 2 == 0,
+            |i, j| ((i + j) % 2 + (i * j) % 3) % 2 == 0,
+        ];
+        for (mask, condition) in conditions.into_iter().enumerate() {
+            let mut matrix = QrMatrix {
+                size: 21,
+                modules: vec![false; 21 * 21],
+                function: vec![false; 21 * 21],
+            };
+            apply_mask(&mut matrix, mask as u32);
+            for i in 0..21 {
+                for j in 0..21 {
+                    assert_eq!(
+                        matrix.get(j, i),
+                        condition(i, j),
+                        "mask {mask} at ({i}, {j})"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn picks_smallest_version_that_fits() {
+        let size = |len: usize| encode(&vec![b'a'; len]).map(|matrix| matrix.size);
+        assert_eq!(size(17), Some(21));
+        assert_eq!(size(18), Some(25));
+        assert_eq!(size(271), Some(57));
+        assert_eq!(size(272), None);
+    }
+
+    #[test]
+    fn encodes_controller_url() {
+        // 另一个编码器用掩码 4 生成的参考矩阵
+        let expected = [
+            "#######.#.....##..#######",
+            "#.....#.#..##...#.#.....#",
+            "#.###.#.##..#..##.#.###.#",
+            "#.###.#.###.##.#..#.###.#",
+            "#.###.#..##.##.#..#.###.#",
+            "#.....#.###...#...#.....#",
+            "#######.#.#.#.#.#.#######",
+            "..........##.............",
+            "##..###...#.#.##...#.####",
+            "####.#..#....#.#.#.###.#.",
+            "..#.#.#####....#####.##..",
+            "#.#..#.#.#..#..##.....##.",
+            "##..###....##.###.#..####",
+            "##..##.#..#.#..#.#.##..#.",
+            "...#.####..###.###.####..",
+            "..#.##.#.##.#....####.##.",
+            "##..###.#####.#.#######..",
+            "........#####..##...#....",
+            "#######..#.##...#.#.#....",
+            "#.....#.##.....##...#####",
+            "#.###.#.##....#.#######..",
+            "#.###.#...####.#..##..###",
+            "#.###.#...##..#####..#.#.",
+            "#.....#.#####.....######.",
+            "#######.#.#...#.......###",
+        ];
+        let matrix = encode(b"https://192.168.1.20:8443/").unwrap();
+        assert_eq!(rows(&matrix), expected);
+    }
+}
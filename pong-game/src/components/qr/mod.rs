@@ -0,0 +1,85 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+pub mod encode;
+
+/// 二维码四周的空白（模块数），扫码需要
+const QUIET_ZONE: usize = 4;
+
+/// 显示 `0` 中文本的二维码，修改文本后图片自动更新，文本为空时不显示
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct QrCode(pub String);
+
+/// 边长为 `size` 像素的二维码。旧图片在句柄替换后自动释放
+pub fn qr_code(data: String, size: f32) -> impl Bundle {
+    (
+        ImageNode::default(),
+        Node {
+            width: Val::Px(size),
+            height: Val::Px(size),
+            ..default()
+        },
+        QrCode(data),
+    )
+}
+
+pub fn qr_plugin(app: &mut App) {
+    app.add_systems(PostUpdate, render_qr_codes);
+}
+
+fn render_qr_codes(
+    mut images: ResMut<Assets<Image>>,
+    mut codes: Query<(&QrCode, &mut ImageNode), Changed<QrCode>>,
+) {
+    for (code, mut image_node) in codes.iter_mut() {
+        let matrix = if code.0.is_empty() {
+            None
+        } else {
+            let matrix = encode::encode(code.0.as_bytes());
+            if matrix.is_none() {
+                warn!("⚠️ 文本太长，无法生成二维码（{} 字节）", code.0.len());
+            }
+            matrix
+        };
+        match matrix {
+            Some(matrix) => {
+                image_node.image = images.add(qr_image(&matrix));
+                image_node.color = Color::WHITE;
+            }
+            None => {
+                image_node.image = Handle::default();
+                image_node.color = Color::NONE;
+            }
+        }
+    }
+}
+
+/// 每个模块一个像素，显示时用最近邻采样放大
+fn qr_image(matrix: &encode::QrMatrix) -> Image {
+    let side = matrix.size + QUIET_ZONE * 2;
+    let mut data = Vec::with_capacity(side * side * 4);
+    for y in 0..side {
+        for x in 0..side {
+            let dark = (QUIET_ZONE..QUIET_ZONE + matrix.size).contains(&x)
+                && (QUIET_ZONE..QUIET_ZONE + matrix.size).contains(&y)
+                && matrix.get(x - QUIET_ZONE, y - QUIET_ZONE);
+            let value = if dark { 0 } else { 255 };
+            data.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    let mut image = Image::new(
+        Extent3d {
+            width: side as u32,
+            height: side as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::nearest();
+    image
+}
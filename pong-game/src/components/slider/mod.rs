@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+use crate::components::button::Focused;
+use crate::components::theme::UiTheme;
+use crate::menu::navigation::MenuInput;
+
+/// 滑块的长度
+const TRACK_WIDTH: f32 = 240.0;
+const TRACK_HEIGHT: f32 = 28.0;
+
+/// 滑块，拖动或在获得焦点时按左右键修改。使用者通过 `Changed<Slider>` 读取新值
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// 左右键每次移动的距离，拖动时也按该步长取整
+    pub step: f32,
+}
+
+impl Slider {
+    pub fn new(value: f32, min: f32, max: f32, step: f32) -> Self {
+        Slider {
+            value: value.clamp(min, max),
+            min,
+            max,
+            step,
+        }
+    }
+
+    fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn snapped(&self, value: f32) -> f32 {
+        let steps = ((value - self.min) / self.step).round();
+        (self.min + steps * self.step).clamp(self.min, self.max)
+    }
+}
+
+/// 滑块已填充的部分
+#[derive(Component)]
+struct SliderFill;
+
+/// 生成滑块，`extra` 为使用者的标记组件
+pub fn spawn_slider(
+    parent: &mut ChildBuilder,
+    theme: &UiTheme,
+    slider: Slider,
+    extra: impl Bundle,
+) -> Entity {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(TRACK_WIDTH),
+                height: Val::Px(TRACK_HEIGHT),
+                margin: UiRect::all(Val::Px(10.0)),
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            },
+            BackgroundColor(theme.button),
+            RelativeCursorPosition::default(),
+            slider,
+            extra,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Node {
+                    width: Val::Percent(slider.fraction() * 100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(theme.accent),
+                SliderFill,
+            ));
+        })
+        .id()
+}
+
+pub fn slider_plugin(app: &mut App) {
+    app.add_systems(Update, (drag_slider, nudge_slider, update_slider_fill).chain());
+}
+
+/// 按住鼠标时滑块跟随光标
+fn drag_slider(mut sliders: Query<(Ref<Interaction>, &RelativeCursorPosition, &mut Slider)>) {
+    for (interaction, cursor, mut slider) in sliders.iter_mut() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // 导航确认也会按下滑块，此时光标不一定在滑块上
+        if interaction.is_changed() && !cursor.mouse_over() {
+            continue;
+        }
+        let Some(position) = cursor.normalized else {
+            continue;
+        };
+        let value = slider.snapped(slider.min + position.x.clamp(0.0, 1.0) * (slider.max - slider.min));
+        if slider.value != value {
+            slider.value = value;
+        }
+    }
+}
+
+/// 获得焦点时左右键调整
+fn nudge_slider(mut inputs: EventReader<MenuInput>, mut sliders: Query<&mut Slider, With<Focused>>) {
+    for input in inputs.read() {
+        let direction = match input {
+            MenuInput::Left => -1.0,
+            MenuInput::Right => 1.0,
+            _ => continue,
+        };
+        for mut slider in sliders.iter_mut() {
+            let value = slider.snapped(slider.value + direction * slider.step);
+            if slider.value != value {
+                slider.value = value;
+            }
+        }
+    }
+}
+
+fn update_slider_fill(
+    sliders: Query<(&Slider, &Children), Changed<Slider>>,
+    mut fills: Query<&mut Node, With<SliderFill>>,
+) {
+    for (slider, children) in sliders.iter() {
+        for child in children.iter() {
+            if let Ok(mut node) = fills.get_mut(*child) {
+                node.width = Val::Percent(slider.fraction() * 100.0);
+            }
+        }
+    }
+}
+
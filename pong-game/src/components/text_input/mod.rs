@@ -0,0 +1,142 @@
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+use crate::components::theme::{TextSize, UiTheme};
+
+/// 编辑时文字后面显示的光标
+const CURSOR: &str = "_";
+
+/// 单行文本框，按下后开始编辑，Enter 或 Esc 结束。使用者通过 `Changed<TextInput>` 读取内容
+#[derive(Component, Clone, Debug)]
+pub struct TextInput {
+    pub value: String,
+    /// 内容为空时显示的提示
+    pub placeholder: String,
+    pub max_len: usize,
+}
+
+/// 正在编辑的文本框，同一时间最多一个。编辑时键盘不再用于菜单导航
+#[derive(Component)]
+pub struct Editing;
+
+pub fn text_input(
+    theme: &UiTheme,
+    value: String,
+    placeholder: String,
+    max_len: usize,
+) -> impl Bundle + use<> {
+    let label = if value.is_empty() { placeholder.clone() } else { value.clone() };
+    (
+        theme.text(label, TextSize::Option),
+        Button,
+        Node {
+            min_width: Val::Px(240.0),
+            margin: UiRect::all(Val::Px(10.0)),
+            padding: UiRect::axes(Val::Px(10.0), Val::Px(5.0)),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(theme.button),
+        TextInput {
+            value,
+            placeholder,
+            max_len,
+        },
+    )
+}
+
+pub fn text_input_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (toggle_editing, type_text, update_text_input_label).chain(),
+    );
+}
+
+/// 按钮是否为文本框、是否正在编辑
+type EditableButton = (Entity, &'static Interaction, Has<TextInput>, Has<Editing>);
+
+/// 按下文本框开始或结束编辑，按下其他按钮时结束编辑
+fn toggle_editing(
+    mut commands: Commands,
+    pressed: Query<EditableButton, Changed<Interaction>>,
+    editing: Query<Entity, With<Editing>>,
+) {
+    for (entity, interaction, is_input, is_editing) in pressed.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        for other in editing.iter() {
+            commands.entity(other).remove::<Editing>();
+        }
+        if is_input && !is_editing {
+            commands.entity(entity).insert(Editing);
+        }
+    }
+}
+
+/// 刚开始编辑的一帧忽略按键，用 Enter 按下文本框时不会马上结束编辑
+fn type_text(
+    mut commands: Commands,
+    mut keys: EventReader<KeyboardInput>,
+    mut inputs: Query<(Entity, &mut TextInput, Ref<Editing>)>,
+) {
+    let Ok((entity, mut input, editing)) = inputs.get_single_mut() else {
+        keys.clear();
+        return;
+    };
+    if editing.is_added() {
+        keys.clear();
+        return;
+    }
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Character(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if input.value.chars().count() < input.max_len {
+                        input.value.push(c);
+                    }
+                }
+            }
+            Key::Space if input.value.chars().count() < input.max_len => input.value.push(' '),
+            Key::Backspace => {
+                input.value.pop();
+            }
+            Key::Enter | Key::Escape => {
+                commands.entity(entity).remove::<Editing>();
+            }
+            _ => {}
+        }
+    }
+}
+
+type InputLabel = (
+    Entity,
+    Ref<'static, TextInput>,
+    Option<Ref<'static, Editing>>,
+    &'static mut Text,
+);
+
+/// 编辑时显示光标，内容为空且不在编辑时显示提示
+fn update_text_input_label(
+    mut removed: RemovedComponents<Editing>,
+    mut inputs: Query<InputLabel>,
+) {
+    let removed: Vec<Entity> = removed.read().collect();
+    for (entity, input, editing, mut text) in inputs.iter_mut() {
+        let changed = input.is_changed()
+            || editing.as_ref().is_some_and(|editing| editing.is_added())
+            || removed.contains(&entity);
+        if !changed {
+            continue;
+        }
+        text.0 = match (editing.is_some(), input.value.is_empty()) {
+            (true, _) => format!("{}{}", input.value, CURSOR),
+            (false, true) => input.placeholder.clone(),
+            (false, false) => input.value.clone(),
+        };
+    }
+}
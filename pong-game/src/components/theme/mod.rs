@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+/// 各个盖在画面上的界面所在的层，导航只在最上层的按钮之间移动焦点
+pub const SUMMARY_LAYER: i32 = 10;
+pub const PAUSE_LAYER: i32 = 20;
+pub const POPUP_LAYER: i32 = 30;
+pub const MODAL_LAYER: i32 = 40;
+pub const TOAST_LAYER: i32 = 60;
+
+/// 文本的用途，字号由 [`UiTheme`] 决定
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextSize {
+    /// 主菜单标题
+    Hero,
+    /// 比分横幅、暂停标题
+    Banner,
+    /// 页面标题
+    Title,
+    /// 记分牌的比分
+    Score,
+    /// 菜单按钮、小节标题
+    Button,
+    /// 选项按钮和选项名
+    Option,
+    /// 正文
+    Body,
+    /// 列表中的一行
+    Caption,
+    /// 角落里的状态文字
+    Small,
+}
+
+/// 界面的颜色和字号，所有控件从这里取样式
+#[derive(Resource, Clone, Debug)]
+pub struct UiTheme {
+    pub button: Color,
+    pub button_hovered: Color,
+    pub button_pressed: Color,
+    /// 选中的选项被悬停或获得焦点
+    pub button_selected_hovered: Color,
+    /// 滑块已填充的部分
+    pub accent: Color,
+    /// 记分牌、下拉列表和对话框的底色
+    pub panel: Color,
+    /// 暂停菜单盖住比赛画面的遮罩
+    pub overlay: Color,
+    /// 比赛总结和对话框后面更深的遮罩
+    pub scrim: Color,
+    pub text: Color,
+    /// 所有字号的倍数
+    pub text_scale: f32,
+}
+
+impl Default for UiTheme {
+    fn default() -> Self {
+        UiTheme {
+            button: Color::srgb(0.15, 0.15, 0.15),
+            button_hovered: Color::srgb(0.25, 0.25, 0.25),
+            button_pressed: Color::srgb(0.35, 0.75, 0.35),
+            button_selected_hovered: Color::srgb(0.25, 0.65, 0.25),
+            accent: Color::srgb(0.35, 0.75, 0.35),
+            panel: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            overlay: Color::srgba(0.0, 0.0, 0.0, 0.7),
+            scrim: Color::srgba(0.0, 0.0, 0.0, 0.8),
+            text: Color::WHITE,
+            text_scale: 1.0,
+        }
+    }
+}
+
+impl UiTheme {
+    pub fn font_size(&self, size: TextSize) -> f32 {
+        let base = match size {
+            TextSize::Hero => 67.0,
+            TextSize::Banner => 56.0,
+            TextSize::Title => 48.0,
+            TextSize::Score => 40.0,
+            TextSize::Button => 32.0,
+            TextSize::Option => 28.0,
+            TextSize::Body => 24.0,
+            TextSize::Caption => 20.0,
+            TextSize::Small => 16.0,
+        };
+        base * self.text_scale
+    }
+
+    pub fn font(&self, size: TextSize) -> TextFont {
+        TextFont {
+            font_size: self.font_size(size),
+            ..default()
+        }
+    }
+
+    /// 一段文本
+    pub fn text(&self, content: String, size: TextSize) -> impl Bundle + use<> {
+        (Text::new(content), self.font(size), TextColor(self.text))
+    }
+}
+
+/// 占满窗口、内容居中的纵向布局
+pub fn screen_column(row_gap: f32) -> Node {
+    Node {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        flex_direction: FlexDirection::Column,
+        row_gap: Val::Px(row_gap),
+        ..default()
+    }
+}
+
+/// 盖在其他界面上面的全屏纵向布局，和 `GlobalZIndex` 一起使用
+pub fn overlay_column(row_gap: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        ..screen_column(row_gap)
+    }
+}
+
+/// 选项行：左边是名字，右边是控件
+pub fn option_row() -> Node {
+    Node {
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+/// 选项行左边的名字
+pub fn row_label(theme: &UiTheme, content: String) -> impl Bundle + use<> {
+    (
+        theme.text(content, TextSize::Option),
+        Node {
+            margin: UiRect::right(Val::Px(20.0)),
+            ..default()
+        },
+    )
+}
@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+use crate::components::theme::{TOAST_LAYER, TextSize, UiTheme};
+
+/// 提示显示的时间（秒），最后 `TOAST_FADE` 秒淡出
+const TOAST_DURATION: f32 = 3.0;
+const TOAST_FADE: f32 = 0.5;
+/// 同时显示的提示数量，超出时移除最早的
+const MAX_TOASTS: usize = 4;
+
+/// 在窗口底部显示一条短暂的提示，任何状态下都可以发送
+#[derive(Event, Clone, Debug)]
+pub struct Toast(pub String);
+
+#[derive(Component)]
+struct ToastStack;
+
+#[derive(Component)]
+struct ToastItem {
+    age: f32,
+}
+
+pub fn toast_plugin(app: &mut App) {
+    app.add_event::<Toast>()
+        .add_systems(Startup, spawn_toast_stack)
+        .add_systems(Update, (show_toasts, fade_toasts).chain());
+}
+
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(60.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        GlobalZIndex(TOAST_LAYER),
+        ToastStack,
+    ));
+}
+
+fn show_toasts(
+    mut commands: Commands,
+    theme: Res<UiTheme>,
+    mut toasts: EventReader<Toast>,
+    stack: Query<(Entity, Option<&Children>), With<ToastStack>>,
+) {
+    let Ok((stack, children)) = stack.get_single() else {
+        return;
+    };
+    let mut shown = children.map_or(0, |children| children.len());
+    let mut oldest = children.into_iter().flatten();
+    for Toast(message) in toasts.read() {
+        if shown >= MAX_TOASTS {
+            if let Some(entity) = oldest.next() {
+                commands.entity(*entity).despawn_recursive();
+            }
+        } else {
+            shown += 1;
+        }
+        let toast = commands
+            .spawn((
+                theme.text(message.clone(), TextSize::Body),
+                Node {
+                    padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                    ..default()
+                },
+                BackgroundColor(theme.panel),
+                ToastItem { age: 0.0 },
+            ))
+            .id();
+        commands.entity(stack).add_child(toast);
+    }
+}
+
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    theme: Res<UiTheme>,
+    mut toasts: Query<(Entity, &mut ToastItem, &mut TextColor, &mut BackgroundColor)>,
+) {
+    for (entity, mut toast, mut text_color, mut background) in toasts.iter_mut() {
+        toast.age += time.delta_secs();
+        if toast.age >= TOAST_DURATION {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        let alpha = ((TOAST_DURATION - toast.age) / TOAST_FADE).min(1.0);
+        text_color.0 = theme.text.with_alpha(theme.text.alpha() * alpha);
+        background.0 = theme.panel.with_alpha(theme.panel.alpha() * alpha);
+    }
+}
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+use crate::components::button::{ButtonKind, SelectedOption, button};
+use crate::components::theme::UiTheme;
+
+/// 开关按钮，按下时切换。开启时带有 [`SelectedOption`]，和选中的选项显示相同
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Toggle(pub bool);
+
+pub fn toggle(theme: &UiTheme, label: String, on: bool) -> impl Bundle + use<> {
+    (button(theme, label, ButtonKind::Option), Toggle(on))
+}
+
+pub fn toggle_plugin(app: &mut App) {
+    app.add_systems(Update, (press_toggle, sync_toggle_selection).chain());
+}
+
+fn press_toggle(mut toggles: Query<(&Interaction, &mut Toggle), Changed<Interaction>>) {
+    for (interaction, mut toggle) in toggles.iter_mut() {
+        if *interaction == Interaction::Pressed {
+            toggle.0 = !toggle.0;
+        }
+    }
+}
+
+fn sync_toggle_selection(mut commands: Commands, toggles: Query<(Entity, &Toggle), Changed<Toggle>>) {
+    for (entity, toggle) in toggles.iter() {
+        if toggle.0 {
+            commands.entity(entity).insert(SelectedOption);
+        } else {
+            commands.entity(entity).remove::<SelectedOption>();
+        }
+    }
+}
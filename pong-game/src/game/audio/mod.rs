@@ -0,0 +1,288 @@
+use std::env;
+
+use bevy::audio::{AddAudioSource, GlobalVolume, PlaybackMode, Volume};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::GameState;
+use crate::game::camera::CameraRig;
+use crate::game::camera::viewport::ViewportSlot;
+use crate::game::scoring::{MatchPhase, MatchScore, PointScored};
+use crate::game::utils::court::Court;
+use crate::game::utils::racket_physics::RacketHitEvent;
+use crate::game::utils::{Ball, Net, Table};
+use crate::locale::Locale;
+
+pub mod synth;
+
+use synth::Synth;
+
+/// 达到该速度（m/s）的撞击音量最大
+const LOUD_IMPACT_SPEED: f32 = 10.0;
+/// 太轻的接触（球停在桌面上滚动等）不发声
+const MIN_IMPACT_SPEED: f32 = 0.3;
+/// 听者两耳之间的距离
+const EAR_GAP: f32 = 0.3;
+
+/// 整体音量 0~1，默认值来自 `PONG_VOLUME`，可在 Settings 页面中修改
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct SoundSettings {
+    pub volume: f32,
+}
+
+impl SoundSettings {
+    pub fn from_env() -> Self {
+        let volume = env::var("PONG_VOLUME")
+            .ok()
+            .and_then(|value| value.parse::<f32>().ok())
+            .unwrap_or(0.75)
+            .clamp(0.0, 1.0);
+        SoundSettings { volume }
+    }
+
+    pub fn label(volume: f32, locale: &Locale) -> String {
+        if volume == 0.0 {
+            locale.text("volume-off")
+        } else {
+            format!("{:.0}%", volume * 100.0)
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SoundBank {
+    racket: Handle<Synth>,
+    table: Handle<Synth>,
+    net: Handle<Synth>,
+    floor: Handle<Synth>,
+    crowd: Handle<Synth>,
+    click: Handle<Synth>,
+}
+
+pub fn audio_plugin(app: &mut App) {
+    app.add_audio_source::<Synth>()
+        .insert_resource(SoundSettings::from_env())
+        .add_systems(Startup, load_sound_bank)
+        .add_systems(
+            Update,
+            apply_sound_settings.run_if(resource_changed::<SoundSettings>),
+        )
+        .add_systems(Update, (attach_listener, button_click_sounds))
+        .add_systems(
+            Update,
+            (racket_sounds, contact_sounds, floor_sounds, crowd_sounds)
+                .run_if(not(in_state(GameState::Menu))),
+        );
+}
+
+fn load_sound_bank(mut commands: Commands, mut synths: ResMut<Assets<Synth>>) {
+    commands.insert_resource(SoundBank {
+        racket: synths.add(synth::racket_hit()),
+        table: synths.add(synth::table_bounce()),
+        net: synths.add(synth::net_hit()),
+        floor: synths.add(synth::floor_hit()),
+        crowd: synths.add(synth::crowd()),
+        click: synths.add(synth::ui_click()),
+    });
+}
+
+/// 音效都是一次性播放的，修改全局音量即可影响之后的所有声音
+fn apply_sound_settings(settings: Res<SoundSettings>, mut global: ResMut<GlobalVolume>) {
+    global.volume = Volume::new(settings.volume);
+}
+
+/// Bevy 只支持一个听者，挂在第一名玩家的相机上；分屏时由 [`PlayerEars`] 把声音换算到该相机下
+fn attach_listener(
+    mut commands: Commands,
+    listeners: Query<(), With<SpatialListener>>,
+    rigs: Query<(Entity, &ViewportSlot), With<CameraRig>>,
+) {
+    if !listeners.is_empty() {
+        return;
+    }
+    if let Some((camera, _)) = rigs.iter().min_by_key(|(_, slot)| slot.index) {
+        commands.entity(camera).insert(SpatialListener::new(EAR_GAP));
+    }
+}
+
+/// 分屏时每名玩家都按自己的视角听到声音：撞击声相对离它最近的玩家相机定位，
+/// 再放到听者相机下的同一相对位置播放
+#[derive(SystemParam)]
+struct PlayerEars<'w, 's> {
+    listener: Query<'w, 's, &'static GlobalTransform, With<SpatialListener>>,
+    cameras: Query<'w, 's, &'static GlobalTransform, With<CameraRig>>,
+}
+
+impl PlayerEars<'_, '_> {
+    fn emitter_position(&self, position: Vec3) -> Vec3 {
+        let Ok(listener) = self.listener.get_single() else {
+            return position;
+        };
+        let nearest = self.cameras.iter().min_by(|a, b| {
+            let a = a.translation().distance_squared(position);
+            let b = b.translation().distance_squared(position);
+            a.total_cmp(&b)
+        });
+        let Some(camera) = nearest else {
+            return position;
+        };
+        let relative = camera.affine().inverse().transform_point3(position);
+        listener.transform_point(relative)
+    }
+}
+
+/// 在 `position` 处播放一次撞击声，音量和音调随速度变化
+fn play_impact(
+    commands: &mut Commands,
+    ears: &PlayerEars,
+    sound: &Handle<Synth>,
+    position: Vec3,
+    speed: f32,
+) {
+    if speed < MIN_IMPACT_SPEED {
+        return;
+    }
+    let strength = (speed / LOUD_IMPACT_SPEED).clamp(0.1, 1.0);
+    let pitch = 0.9 + strength * 0.25 + rand::rng().random_range(-0.04..0.04);
+    commands.spawn((
+        AudioPlayer(sound.clone()),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::new(strength),
+            speed: pitch,
+            spatial: true,
+            ..default()
+        },
+        Transform::from_translation(ears.emitter_position(position)),
+    ));
+}
+
+fn racket_sounds(
+    mut commands: Commands,
+    ears: PlayerEars,
+    bank: Res<SoundBank>,
+    mut hits: EventReader<RacketHitEvent>,
+    ball_query: Query<&Transform, With<Ball>>,
+) {
+    for hit in hits.read() {
+        if let Ok(ball) = ball_query.get(hit.ball) {
+            let (position, speed) = (ball.translation, hit.speed);
+            play_impact(&mut commands, &ears, &bank.racket, position, speed);
+        }
+    }
+}
+
+/// 球与桌面、球网接触，按接触时的球速决定音量
+fn contact_sounds(
+    mut commands: Commands,
+    ears: PlayerEars,
+    bank: Res<SoundBank>,
+    mut collision_events: EventReader<CollisionEvent>,
+    ball_query: Query<(&Transform, &Velocity), With<Ball>>,
+    table_q: Query<(), With<Table>>,
+    net_q: Query<(), With<Net>>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+        let (ball, other) = if ball_query.contains(*e1) {
+            (*e1, *e2)
+        } else {
+            (*e2, *e1)
+        };
+        let Ok((transform, velocity)) = ball_query.get(ball) else {
+            continue;
+        };
+        let sound = if table_q.contains(other) {
+            &bank.table
+        } else if net_q.contains(other) {
+            &bank.net
+        } else {
+            continue;
+        };
+        play_impact(
+            &mut commands,
+            &ears,
+            sound,
+            transform.translation,
+            velocity.linvel.length(),
+        );
+    }
+}
+
+/// 场地没有地板碰撞体，球穿过地面高度时播放落地声
+fn floor_sounds(
+    mut commands: Commands,
+    ears: PlayerEars,
+    bank: Res<SoundBank>,
+    court: Res<Court>,
+    ball_query: Query<(&Transform, &Velocity), With<Ball>>,
+    mut last_height: Local<Option<f32>>,
+) {
+    let Some((transform, velocity)) = ball_query.iter().next() else {
+        *last_height = None;
+        return;
+    };
+    let floor = court.ball_radius();
+    let height = transform.translation.y;
+    if last_height.is_some_and(|last| last > floor) && height <= floor {
+        play_impact(
+            &mut commands,
+            &ears,
+            &bank.floor,
+            transform.translation.with_y(0.0),
+            velocity.linvel.y.abs(),
+        );
+    }
+    *last_height = Some(height);
+}
+
+/// 得分时观众欢呼，整场比赛结束时更响
+fn crowd_sounds(
+    mut commands: Commands,
+    bank: Res<SoundBank>,
+    score: Res<MatchScore>,
+    mut points: EventReader<PointScored>,
+    mut last_phase: Local<MatchPhase>,
+) {
+    let mut volume = if points.read().count() > 0 { 0.35 } else { 0.0 };
+    let match_over = matches!(score.phase, MatchPhase::MatchOver { .. });
+    if match_over && !matches!(*last_phase, MatchPhase::MatchOver { .. }) {
+        volume = 0.8;
+    }
+    // 联机客户端没有得分事件，根据同步来的比分判断
+    if matches!(score.phase, MatchPhase::PointOver { .. } | MatchPhase::GameOver { .. })
+        && *last_phase == MatchPhase::Playing
+    {
+        volume = f32::max(volume, 0.35);
+    }
+    *last_phase = score.phase;
+    if volume > 0.0 {
+        commands.spawn((
+            AudioPlayer(bank.crowd.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(volume)),
+        ));
+    }
+}
+
+fn button_click_sounds(
+    mut commands: Commands,
+    bank: Option<Res<SoundBank>>,
+    interactions: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+) {
+    let Some(bank) = bank else {
+        return;
+    };
+    if interactions
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        commands.spawn((
+            AudioPlayer(bank.click.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(0.5)),
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::audio::{Decodable, Source};
+use bevy::prelude::*;
+
+const SAMPLE_RATE: u32 = 44_100;
+
+/// 启动时合成好的一段单声道音效，不依赖音频文件
+#[derive(Asset, TypePath, Clone)]
+pub struct Synth {
+    samples: Arc<[f32]>,
+}
+
+pub struct SynthDecoder {
+    samples: Arc<[f32]>,
+    position: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.position).copied();
+        self.position += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.position))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            position: 0,
+        }
+    }
+}
+
+/// 一段衰减的声音：几个正弦分量加上噪声，`attack` 秒内淡入，之后按 `decay` 秒指数衰减
+struct Voice {
+    duration: f32,
+    attack: f32,
+    decay: f32,
+    /// （频率，振幅）
+    partials: &'static [(f32, f32)],
+    noise: f32,
+    /// 噪声的平滑程度，越接近 1 声音越闷
+    noise_smoothing: f32,
+}
+
+impl Voice {
+    fn render(&self) -> Synth {
+        let count = (self.duration * SAMPLE_RATE as f32) as usize;
+        // 固定种子的噪声，每次启动听起来一样
+        let mut seed: u32 = 0x9e37_79b9;
+        let mut filtered = 0.0;
+        let samples = (0..count)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let envelope = (t / self.attack.max(1e-4)).min(1.0) * (-t / self.decay).exp();
+                let tone: f32 = self
+                    .partials
+                    .iter()
+                    .map(|(frequency, amplitude)| (TAU * frequency * t).sin() * amplitude)
+                    .sum();
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let white = seed as f32 / u32::MAX as f32 * 2.0 - 1.0;
+                filtered = filtered * self.noise_smoothing + white * (1.0 - self.noise_smoothing);
+                ((tone + filtered * self.noise) * envelope).clamp(-1.0, 1.0)
+            })
+            .collect::<Vec<_>>();
+        Synth {
+            samples: samples.into(),
+        }
+    }
+}
+
+/// 球拍击球：清脆的“啪”
+pub fn racket_hit() -> Synth {
+    Voice {
+        duration: 0.12,
+        attack: 0.001,
+        decay: 0.02,
+        partials: &[(1250.0, 0.5), (2900.0, 0.2)],
+        noise: 0.6,
+        noise_smoothing: 0.2,
+    }
+    .render()
+}
+
+/// 球落在桌面：比击球低、带一点空腔的回响
+pub fn table_bounce() -> Synth {
+    Voice {
+        duration: 0.18,
+        attack: 0.001,
+        decay: 0.035,
+        partials: &[(820.0, 0.6), (1650.0, 0.15)],
+        noise: 0.35,
+        noise_smoothing: 0.4,
+    }
+    .render()
+}
+
+/// 球打在网上：闷响
+pub fn net_hit() -> Synth {
+    Voice {
+        duration: 0.25,
+        attack: 0.003,
+        decay: 0.07,
+        partials: &[(180.0, 0.4), (260.0, 0.2)],
+        noise: 0.7,
+        noise_smoothing: 0.85,
+    }
+    .render()
+}
+
+/// 球落地
+pub fn floor_hit() -> Synth {
+    Voice {
+        duration: 0.2,
+        attack: 0.001,
+        decay: 0.045,
+        partials: &[(420.0, 0.5), (950.0, 0.1)],
+        noise: 0.3,
+        noise_smoothing: 0.6,
+    }
+    .render()
+}
+
+/// 观众的欢呼声，用缓慢起伏的噪声模拟
+pub fn crowd() -> Synth {
+    Voice {
+        duration: 1.8,
+        attack: 0.3,
+        decay: 0.7,
+        partials: &[],
+        noise: 0.8,
+        noise_smoothing: 0.92,
+    }
+    .render()
+}
+
+/// 菜单按钮
+pub fn ui_click() -> Synth {
+    Voice {
+        duration: 0.05,
+        attack: 0.001,
+        decay: 0.012,
+        partials: &[(2000.0, 0.4)],
+        noise: 0.0,
+        noise_smoothing: 0.0,
+    }
+    .render()
+}
@@ -0,0 +1,155 @@
+use bevy::input::gamepad::{Gamepad, GamepadButton};
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_rapier3d::prelude::PhysicsSet;
+
+use crate::game::input::PlayerRacket;
+use crate::game::scoring::side_of;
+use crate::game::utils::court::Court;
+use crate::game::utils::{Ball, Racket};
+
+pub mod viewport;
+
+/// 相机追踪的平滑程度，越大跟得越紧
+const SMOOTHING: f32 = 6.0;
+/// 球跟随视角在球后方、上方的距离
+const FOLLOW_OFFSET: Vec3 = Vec3::new(1.2, 0.45, 0.6);
+
+/// 比赛相机的视角，按 C 键或手柄 Select 键切换
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraMode {
+    /// 站在自己球拍后方，左右跟随球拍
+    #[default]
+    PlayerEye,
+    /// 电视转播的侧面视角，跟随球的位置
+    Broadcast,
+    /// 俯视整张球桌
+    Overhead,
+    /// 跟在球后面的电影视角
+    BallFollow,
+}
+
+impl CameraMode {
+    pub const ALL: [CameraMode; 4] = [
+        CameraMode::PlayerEye,
+        CameraMode::Broadcast,
+        CameraMode::Overhead,
+        CameraMode::BallFollow,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CameraMode::PlayerEye => "Player's eye",
+            CameraMode::Broadcast => "Broadcast",
+            CameraMode::Overhead => "Overhead",
+            CameraMode::BallFollow => "Ball follow",
+        }
+    }
+
+    fn next(self) -> Self {
+        let index = CameraMode::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        CameraMode::ALL[(index + 1) % CameraMode::ALL.len()]
+    }
+}
+
+/// 由相机系统控制的比赛相机，`side` 为所属玩家的一侧（1.0 为 +x，-1.0 为 -x），
+/// `player` 为跟随的球拍（[`PlayerRacket::player`]）
+#[derive(Component, Clone, Copy)]
+pub struct CameraRig {
+    pub side: f32,
+    pub player: usize,
+}
+
+pub fn camera_rig_plugin(app: &mut App) {
+    app.init_resource::<CameraMode>()
+        .add_plugins(viewport::viewport_plugin)
+        .add_systems(Update, switch_camera_mode)
+        .add_systems(
+            PostUpdate,
+            follow_camera_rigs
+                .after(PhysicsSet::Writeback)
+                .before(TransformSystem::TransformPropagate),
+        );
+}
+
+fn switch_camera_mode(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    rigs: Query<(), With<CameraRig>>,
+    mut mode: ResMut<CameraMode>,
+) {
+    if rigs.is_empty() {
+        return;
+    }
+    let pressed = keys.just_pressed(KeyCode::KeyC)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::Select));
+    if pressed {
+        *mode = mode.next();
+        info!("🎥 切换视角: {}", mode.label());
+    }
+}
+
+/// 视角跟随的球拍，双打时按座位区分同一侧的两支
+type FollowedRacket = (&'static Transform, Option<&'static PlayerRacket>);
+
+/// 计算各视角的目标位姿，然后平滑地移动过去
+fn follow_camera_rigs(
+    time: Res<Time>,
+    court: Res<Court>,
+    mode: Res<CameraMode>,
+    ball_query: Query<&Transform, (With<Ball>, Without<CameraRig>)>,
+    rackets: Query<FollowedRacket, (With<Racket>, Without<CameraRig>)>,
+    mut cameras: Query<(&CameraRig, &mut Transform)>,
+) {
+    let ball = ball_query.iter().next().map(|t| t.translation);
+    let blend = 1.0 - (-SMOOTHING * time.delta_secs()).exp();
+    let table_top = Vec3::Y * court.table_height;
+
+    for (rig, mut transform) in cameras.iter_mut() {
+        let side = rig.side;
+        let own_side = if side > 0.0 { 0 } else { 1 };
+        // 双打时同一侧有两支球拍，跟随自己的那一支
+        let racket = rackets
+            .iter()
+            .filter(|(t, _)| side_of(t.translation) == own_side)
+            .max_by_key(|(_, racket)| racket.is_some_and(|racket| racket.player == rig.player))
+            .map(|(t, _)| t.translation);
+
+        let target = match *mode {
+            CameraMode::PlayerEye => {
+                let mut target = court.player_camera(side);
+                if let Some(racket) = racket {
+                    // 只跟随球拍的左右位置，避免画面随挥拍上下晃动
+                    target.translation.z = racket.z * 0.6;
+                    target.look_at(table_top + Vec3::new(0.0, 0.24, racket.z * 0.3), Vec3::Y);
+                }
+                target
+            }
+            CameraMode::Broadcast => {
+                let focus = ball.unwrap_or(table_top);
+                let look = table_top + Vec3::new(focus.x * 0.4, 0.1, 0.0);
+                let mut target = court.side_camera();
+                target.translation.x = focus.x * 0.3;
+                target.look_at(look, Vec3::Y);
+                target
+            }
+            CameraMode::Overhead => {
+                // 对手一侧在画面上方
+                Transform::from_translation(table_top + Vec3::new(side * 0.3, court.table_length, 0.0))
+                    .looking_at(table_top, Vec3::NEG_X * side)
+            }
+            CameraMode::BallFollow => match ball {
+                Some(ball) => Transform::from_translation(
+                    ball + Vec3::new(side * FOLLOW_OFFSET.x, FOLLOW_OFFSET.y, FOLLOW_OFFSET.z),
+                )
+                .looking_at(ball.lerp(table_top, 0.3), Vec3::Y),
+                None => court.player_camera(side),
+            },
+        };
+
+        transform.translation = transform.translation.lerp(target.translation, blend);
+        transform.rotation = transform.rotation.slerp(target.rotation, blend);
+    }
+}
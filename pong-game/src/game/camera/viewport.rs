@@ -0,0 +1,268 @@
+use std::env;
+
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, Viewport};
+use bevy::window::{
+    PrimaryWindow, WindowClosed, WindowRef, WindowResized, WindowScaleFactorChanged,
+};
+
+/// 多名玩家共用一台电脑时各自画面的排列方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplitLayout {
+    /// 左右分屏
+    #[default]
+    SideBySide,
+    /// 上下分屏
+    Stacked,
+    /// 每名玩家一个系统窗口，可以拖到不同的显示器上
+    Windows,
+}
+
+impl SplitLayout {
+    pub const ALL: [SplitLayout; 3] = [
+        SplitLayout::SideBySide,
+        SplitLayout::Stacked,
+        SplitLayout::Windows,
+    ];
+
+    /// 界面上显示的文本 id
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            SplitLayout::SideBySide => "split-side-by-side",
+            SplitLayout::Stacked => "split-stacked",
+            SplitLayout::Windows => "split-windows",
+        }
+    }
+}
+
+/// 分屏设置，默认值来自 `PONG_SPLIT`（side / stacked / windows），可在 Settings 页面中修改
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct SplitScreen {
+    pub layout: SplitLayout,
+}
+
+impl SplitScreen {
+    pub fn from_env() -> Self {
+        let layout = match env::var("PONG_SPLIT").as_deref() {
+            Ok("stacked") => SplitLayout::Stacked,
+            Ok("windows") => SplitLayout::Windows,
+            _ => SplitLayout::SideBySide,
+        };
+        SplitScreen { layout }
+    }
+}
+
+/// 分屏中的位置：第 `index` 个画面，共 `count` 个。
+/// 窗口大小或缩放比例变化时按物理像素重新计算视口。
+#[derive(Component, Clone, Copy)]
+pub struct ViewportSlot {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl ViewportSlot {
+    pub fn full() -> Self {
+        ViewportSlot { index: 0, count: 1 }
+    }
+
+    pub fn split(index: u32, count: u32) -> Self {
+        ViewportSlot { index, count }
+    }
+
+    /// 画面在网格中的（列数，行数）和（列，行）。两个以上的画面排成两列或两行，
+    /// 双打时同一方的两名玩家（序号奇偶相同）在同一列（左右分屏）或同一行（上下分屏）
+    fn grid(&self, layout: SplitLayout) -> (UVec2, UVec2) {
+        let count = self.count.max(1);
+        let (team, member) = (self.index % 2, self.index / 2);
+        match layout {
+            SplitLayout::SideBySide if count > 2 => {
+                (UVec2::new(2, count.div_ceil(2)), UVec2::new(team, member))
+            }
+            SplitLayout::Stacked if count > 2 => {
+                (UVec2::new(count.div_ceil(2), 2), UVec2::new(member, team))
+            }
+            SplitLayout::SideBySide => (UVec2::new(count, 1), UVec2::new(self.index, 0)),
+            SplitLayout::Stacked => (UVec2::new(1, count), UVec2::new(0, self.index)),
+            SplitLayout::Windows => (UVec2::ONE, UVec2::ZERO),
+        }
+    }
+
+    fn viewport(&self, layout: SplitLayout, window_size: UVec2) -> Viewport {
+        let (cells, cell) = self.grid(layout);
+        let size = window_size / cells;
+        let position = cell * size;
+        Viewport {
+            physical_position: position,
+            physical_size: size.max(UVec2::ONE),
+            ..default()
+        }
+    }
+}
+
+/// 独立窗口模式下为某个相机打开的窗口，相机消失后关闭
+#[derive(Component)]
+struct PlayerWindow {
+    camera: Entity,
+}
+
+pub fn viewport_plugin(app: &mut App) {
+    app.insert_resource(SplitScreen::from_env()).add_systems(
+        Update,
+        (
+            release_closed_windows,
+            assign_player_windows,
+            layout_viewports,
+            close_player_windows,
+        )
+            .chain(),
+    );
+}
+
+/// 玩家关掉自己的窗口后，相机改回主窗口并停止渲染，不再指向已经移除的窗口
+fn release_closed_windows(
+    mut closed: EventReader<WindowClosed>,
+    mut cameras: Query<(&mut Camera, &ViewportSlot)>,
+) {
+    for event in closed.read() {
+        for (mut camera, slot) in cameras.iter_mut() {
+            let RenderTarget::Window(WindowRef::Entity(window)) = camera.target else {
+                continue;
+            };
+            if window == event.window {
+                camera.target = RenderTarget::Window(WindowRef::Primary);
+                camera.is_active = false;
+                info!("🪟 玩家 {} 的窗口已关闭", slot.index + 1);
+            }
+        }
+    }
+}
+
+/// 独立窗口模式下，除第一个画面外每个画面打开自己的窗口；切回分屏时画面回到主窗口。
+/// 窗口被关掉的画面在重新选择分屏方式后恢复
+fn assign_player_windows(
+    mut commands: Commands,
+    split: Res<SplitScreen>,
+    mut cameras: Query<(Entity, &mut Camera, &ViewportSlot)>,
+    added: Query<(), Added<ViewportSlot>>,
+    player_windows: Query<(Entity, &PlayerWindow)>,
+) {
+    if !split.is_changed() && added.is_empty() {
+        return;
+    }
+    for (entity, mut camera, slot) in cameras.iter_mut() {
+        let own_window = player_windows
+            .iter()
+            .find(|(_, window)| window.camera == entity)
+            .map(|(window, _)| window);
+        let wants_window = split.layout == SplitLayout::Windows && slot.index > 0;
+        match (wants_window, own_window) {
+            (true, None) => {
+                let window = commands
+                    .spawn((
+                        Window {
+                            title: format!("Pong - Player {}", slot.index + 1),
+                            ..default()
+                        },
+                        PlayerWindow { camera: entity },
+                    ))
+                    .id();
+                camera.target = RenderTarget::Window(WindowRef::Entity(window));
+                // 新窗口还没有尺寸，先渲染整个窗口
+                camera.viewport = None;
+                camera.is_active = true;
+            }
+            (false, Some(window)) => {
+                camera.target = RenderTarget::Window(WindowRef::Primary);
+                commands.entity(window).despawn();
+            }
+            (false, None) if !camera.is_active => {
+                camera.is_active = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 新相机生成、设置变化、窗口大小或缩放比例变化时重新划分视口
+fn layout_viewports(
+    split: Res<SplitScreen>,
+    mut resized: EventReader<WindowResized>,
+    mut rescaled: EventReader<WindowScaleFactorChanged>,
+    primary: Query<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Camera, &ViewportSlot)>,
+    added: Query<(), Added<ViewportSlot>>,
+) {
+    let window_changed = resized.read().count() + rescaled.read().count() > 0;
+    if !window_changed && !split.is_changed() && added.is_empty() {
+        return;
+    }
+    let Ok(window) = primary.get_single() else {
+        return;
+    };
+    let size = window.physical_size();
+    for (mut camera, slot) in cameras.iter_mut() {
+        // 独立窗口中的画面占满自己的窗口
+        if !matches!(camera.target, RenderTarget::Window(WindowRef::Primary)) {
+            camera.viewport = None;
+            continue;
+        }
+        camera.viewport = Some(slot.viewport(split.layout, size));
+    }
+}
+
+/// 相机随比赛结束消失后关闭对应的窗口
+fn close_player_windows(
+    mut commands: Commands,
+    cameras: Query<(), With<ViewportSlot>>,
+    player_windows: Query<(Entity, &PlayerWindow)>,
+) {
+    for (window, player_window) in player_windows.iter() {
+        if !cameras.contains(player_window.camera) {
+            commands.entity(window).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player_window(app: &mut App) -> Option<Entity> {
+        let world = app.world_mut();
+        let mut windows = world.query_filtered::<Entity, With<PlayerWindow>>();
+        windows.iter(world).next()
+    }
+
+    #[test]
+    fn closing_player_window_releases_camera() {
+        let mut app = App::new();
+        app.add_event::<WindowClosed>()
+            .add_event::<WindowResized>()
+            .add_event::<WindowScaleFactorChanged>()
+            .add_plugins(viewport_plugin)
+            .insert_resource(SplitScreen {
+                layout: SplitLayout::Windows,
+            });
+        app.world_mut()
+            .spawn((Camera::default(), ViewportSlot::split(0, 2)));
+        let camera = app
+            .world_mut()
+            .spawn((Camera::default(), ViewportSlot::split(1, 2)))
+            .id();
+        app.update();
+
+        let window = player_window(&mut app).expect("第二名玩家应该有自己的窗口");
+        app.world_mut().despawn(window);
+        app.world_mut().send_event(WindowClosed { window });
+        app.update();
+
+        let target = &app.world().get::<Camera>(camera).unwrap().target;
+        assert!(matches!(target, RenderTarget::Window(WindowRef::Primary)));
+        assert!(!app.world().get::<Camera>(camera).unwrap().is_active);
+        assert!(player_window(&mut app).is_none());
+
+        app.world_mut().resource_mut::<SplitScreen>().layout = SplitLayout::SideBySide;
+        app.update();
+        assert!(app.world().get::<Camera>(camera).unwrap().is_active);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::VecDeque;
+use std::env;
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy::render::primitives::Aabb;
+use bevy_rapier3d::plugin::TimestepMode;
+use bevy_rapier3d::prelude::*;
+use bevy_rapier3d::render::DebugRenderContext;
+
+use crate::game::utils::Ball;
+use crate::game::utils::racket_physics::physics_dt;
+
+/// 接触点标记保留的时间（秒）
+const CONTACT_DURATION: f32 = 1.0;
+/// 速度箭头的长度：每 m/s 画多长
+const VELOCITY_SCALE: f32 = 0.08;
+
+const BOUNDS_COLOR: Color = Color::srgb(1.0, 0.8, 0.0);
+const VELOCITY_COLOR: Color = Color::srgb(0.2, 1.0, 0.4);
+const CONTACT_COLOR: Color = Color::srgb(1.0, 0.2, 0.8);
+
+/// 调试视图的开关，F1 ~ F4 切换，启动时的值来自 `PONG_DEBUG`
+/// （逗号分隔的 colliders / velocity / contacts / stats）
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct DebugOverlay {
+    /// F1：帧率和物理步进面板
+    pub stats: bool,
+    /// F2：模型包围盒与 rapier 碰撞体线框对比
+    pub colliders: bool,
+    /// F3：刚体的速度箭头
+    pub velocities: bool,
+    /// F4：球的碰撞位置
+    pub contacts: bool,
+}
+
+impl DebugOverlay {
+    fn from_env() -> Self {
+        let value = env::var("PONG_DEBUG").unwrap_or_default();
+        let enabled = |name: &str| value.split(',').any(|item| item.trim() == name);
+        DebugOverlay {
+            stats: enabled("stats"),
+            colliders: enabled("colliders"),
+            velocities: enabled("velocity"),
+            contacts: enabled("contacts"),
+        }
+    }
+}
+
+/// 最近的接触点和产生时间
+#[derive(Resource, Default)]
+struct ContactMarkers(VecDeque<(Vec3, f32)>);
+
+#[derive(Component)]
+struct DebugPanel;
+
+/// 只在启用 `debug-overlay` feature 时编译，正式版本不包含任何调试绘制
+pub fn debug_plugin(app: &mut App) {
+    app.insert_resource(DebugOverlay::from_env())
+        .add_plugins(RapierDebugRenderPlugin::default().disabled())
+        .init_resource::<ContactMarkers>()
+        .add_plugins(FrameTimeDiagnosticsPlugin)
+        .add_systems(Startup, spawn_debug_panel)
+        .add_systems(
+            Update,
+            (
+                toggle_debug_overlay,
+                sync_collider_wireframes.run_if(resource_changed::<DebugOverlay>),
+                draw_visual_bounds.run_if(|overlay: Res<DebugOverlay>| overlay.colliders),
+                draw_velocities.run_if(|overlay: Res<DebugOverlay>| overlay.velocities),
+                (record_contacts, draw_contacts)
+                    .chain()
+                    .run_if(|overlay: Res<DebugOverlay>| overlay.contacts),
+                update_debug_panel,
+            )
+                .chain(),
+        );
+}
+
+fn toggle_debug_overlay(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<DebugOverlay>) {
+    let (flag, name) = if keys.just_pressed(KeyCode::F1) {
+        (&mut overlay.stats, "帧率面板")
+    } else if keys.just_pressed(KeyCode::F2) {
+        (&mut overlay.colliders, "碰撞体对比视图")
+    } else if keys.just_pressed(KeyCode::F3) {
+        (&mut overlay.velocities, "速度箭头")
+    } else if keys.just_pressed(KeyCode::F4) {
+        (&mut overlay.contacts, "接触点")
+    } else {
+        return;
+    };
+    *flag = !*flag;
+    info!("🐞 {}: {}", name, if *flag { "开" } else { "关" });
+}
+
+/// rapier 的碰撞体线框跟随 F2 开关
+fn sync_collider_wireframes(overlay: Res<DebugOverlay>, mut debug_render: ResMut<DebugRenderContext>) {
+    debug_render.enabled = overlay.colliders;
+}
+
+/// 黄色为模型网格的包围盒，rapier 的碰撞体线框由 RapierDebugRenderPlugin 绘制
+fn draw_visual_bounds(mut gizmos: Gizmos, meshes: Query<(&GlobalTransform, &Aabb, &ViewVisibility), With<Mesh3d>>) {
+    for (transform, aabb, visibility) in meshes.iter() {
+        if !visibility.get() {
+            continue;
+        }
+        let local = Transform::from_translation(aabb.center.into())
+            .with_scale(Vec3::from(aabb.half_extents) * 2.0);
+        gizmos.cuboid(transform.mul_transform(local), BOUNDS_COLOR);
+    }
+}
+
+fn draw_velocities(mut gizmos: Gizmos, bodies: Query<(&GlobalTransform, &Velocity)>) {
+    for (transform, velocity) in bodies.iter() {
+        if velocity.linvel.length_squared() < 1e-4 {
+            continue;
+        }
+        let start = transform.translation();
+        gizmos.arrow(start, start + velocity.linvel * VELOCITY_SCALE, VELOCITY_COLOR);
+    }
+}
+
+/// 碰撞开始时记下球的位置，作为近似的接触点
+fn record_contacts(
+    time: Res<Time>,
+    mut markers: ResMut<ContactMarkers>,
+    mut collision_events: EventReader<CollisionEvent>,
+    ball_query: Query<&GlobalTransform, With<Ball>>,
+) {
+    let now = time.elapsed_secs();
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+        if let Ok(ball) = ball_query.get(*e1).or_else(|_| ball_query.get(*e2)) {
+            markers.0.push_back((ball.translation(), now));
+        }
+    }
+    while markers.0.front().is_some_and(|(_, t)| now - t > CONTACT_DURATION) {
+        markers.0.pop_front();
+    }
+}
+
+fn draw_contacts(time: Res<Time>, markers: Res<ContactMarkers>, mut gizmos: Gizmos) {
+    let now = time.elapsed_secs();
+    for (position, t) in markers.0.iter() {
+        let alpha = 1.0 - (now - t) / CONTACT_DURATION;
+        let color = CONTACT_COLOR.with_alpha(alpha.clamp(0.0, 1.0));
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            gizmos.line(*position - axis * 0.03, *position + axis * 0.03, color);
+        }
+    }
+}
+
+fn spawn_debug_panel(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        GlobalZIndex(100),
+        Visibility::Hidden,
+        DebugPanel,
+    ));
+}
+
+/// 帧率、帧时间、物理步长、刚体数量和球速
+fn update_debug_panel(
+    time: Res<Time>,
+    overlay: Res<DebugOverlay>,
+    diagnostics: Res<DiagnosticsStore>,
+    timestep_mode: Option<Res<TimestepMode>>,
+    bodies: Query<(), With<RigidBody>>,
+    colliders: Query<(), With<Collider>>,
+    ball_query: Query<&Velocity, With<Ball>>,
+    mut panel: Query<(&mut Text, &mut Visibility), With<DebugPanel>>,
+) {
+    let Ok((mut text, mut visibility)) = panel.get_single_mut() else {
+        return;
+    };
+    if !overlay.stats {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.0);
+    let frame_time = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .and_then(|frame_time| frame_time.smoothed())
+        .unwrap_or(0.0);
+    let step = match timestep_mode.as_deref() {
+        Some(mode) => {
+            let kind = match mode {
+                TimestepMode::Fixed { .. } => "fixed",
+                TimestepMode::Variable { .. } => "variable",
+                TimestepMode::Interpolated { .. } => "interpolated",
+            };
+            format!("{} {:.2} ms", kind, physics_dt(mode, time.delta_secs()) * 1000.0)
+        }
+        None => "-".to_string(),
+    };
+    let ball_speed = ball_query
+        .iter()
+        .next()
+        .map_or("-".to_string(), |velocity| format!("{:.2} m/s", velocity.linvel.length()));
+
+    text.0 = format!(
+        "FPS {:.0} ({:.1} ms)\nPhysics step {}\nBodies {}  Colliders {}\nBall {}",
+        fps,
+        frame_time,
+        step,
+        bodies.iter().count(),
+        colliders.iter().count(),
+        ball_speed,
+    );
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+use crate::game::input::{PlayerRacket, RacketInputSet, RacketOffset};
+use crate::game::scoring::{MatchScore, ScoringSet};
+
+/// 不该击球的球员退到的位置（玩家自己一侧的坐标）：向后退，并让到同伴的侧面
+const STANDBY_OFFSET: Vec3 = Vec3::new(0.5, 0.0, 0.45);
+/// 让位移动的平滑程度，越大越快
+const SWAP_SMOOTHING: f32 = 8.0;
+
+pub fn doubles_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        rotate_doubles_positions
+            .after(ScoringSet)
+            .before(RacketInputSet)
+            .run_if(in_state(GameState::GameRunning)),
+    );
+}
+
+/// 双打时每方下一个该击球的球员站到中间，同伴退到身后一侧；
+/// 对方击球时由本方下一个击球员站到中间准备接球
+fn rotate_doubles_positions(
+    time: Res<Time>,
+    score: Res<MatchScore>,
+    mut rackets: Query<(&PlayerRacket, &mut RacketOffset)>,
+) {
+    let Some(order) = score.doubles else {
+        return;
+    };
+    let blend = 1.0 - (-SWAP_SMOOTHING * time.delta_secs()).exp();
+    for (racket, mut offset) in rackets.iter_mut() {
+        let side = racket.player % 2;
+        let next = (0..2)
+            .map(|shot| order.striker(score.rally + shot))
+            .find(|seat| seat % 2 == side);
+        let target = if next == Some(racket.player) {
+            Vec3::ZERO
+        } else if racket.player < 2 {
+            STANDBY_OFFSET
+        } else {
+            STANDBY_OFFSET * Vec3::new(1.0, 1.0, -1.0)
+        };
+        offset.0 = offset.0.lerp(target, blend);
+    }
+}
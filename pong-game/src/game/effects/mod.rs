@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::GameState;
+use crate::game::utils::court::Court;
+use crate::game::utils::racket_physics::RacketHitEvent;
+use crate::game::utils::{Ball, Table};
+
+/// 拖尾保留的时间（秒）
+const TRAIL_DURATION: f32 = 0.35;
+/// 落点标记保留的时间
+const MARKER_DURATION: f32 = 1.5;
+/// 击球闪光的时间
+const FLASH_DURATION: f32 = 0.15;
+const GRAVITY: f32 = 9.81;
+
+const TRAIL_COLOR: Color = Color::srgb(1.0, 0.85, 0.3);
+const MARKER_COLOR: Color = Color::srgb(1.0, 0.3, 0.2);
+const LANDING_COLOR: Color = Color::srgb(0.3, 0.9, 1.0);
+const FLASH_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+
+/// 各个视觉效果的开关，在 Settings 页面中设置
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct EffectSettings {
+    pub trail: bool,
+    pub bounce_marker: bool,
+    pub landing_prediction: bool,
+    pub racket_flash: bool,
+}
+
+impl Default for EffectSettings {
+    fn default() -> Self {
+        EffectSettings {
+            trail: true,
+            bounce_marker: true,
+            landing_prediction: true,
+            racket_flash: true,
+        }
+    }
+}
+
+/// 可以单独开关的效果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    Trail,
+    BounceMarker,
+    LandingPrediction,
+    RacketFlash,
+}
+
+impl Effect {
+    pub const ALL: [Effect; 4] = [
+        Effect::Trail,
+        Effect::BounceMarker,
+        Effect::LandingPrediction,
+        Effect::RacketFlash,
+    ];
+
+    /// 界面上显示的文本 id
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            Effect::Trail => "effect-trail",
+            Effect::BounceMarker => "effect-bounce",
+            Effect::LandingPrediction => "effect-landing",
+            Effect::RacketFlash => "effect-flash",
+        }
+    }
+}
+
+impl EffectSettings {
+    pub fn enabled(&self, effect: Effect) -> bool {
+        match effect {
+            Effect::Trail => self.trail,
+            Effect::BounceMarker => self.bounce_marker,
+            Effect::LandingPrediction => self.landing_prediction,
+            Effect::RacketFlash => self.racket_flash,
+        }
+    }
+
+    pub fn set(&mut self, effect: Effect, on: bool) {
+        let flag = match effect {
+            Effect::Trail => &mut self.trail,
+            Effect::BounceMarker => &mut self.bounce_marker,
+            Effect::LandingPrediction => &mut self.landing_prediction,
+            Effect::RacketFlash => &mut self.racket_flash,
+        };
+        *flag = on;
+    }
+}
+
+/// 效果使用单独的 gizmo 分组，线条更粗，也不受碰撞体调试视图的影响
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct EffectGizmos;
+
+/// 效果的状态，`f32` 为产生时的时间
+#[derive(Resource, Default)]
+struct EffectState {
+    trail: VecDeque<(Vec3, f32)>,
+    bounces: VecDeque<(Vec3, f32)>,
+    flashes: Vec<(Entity, f32)>,
+}
+
+pub fn effects_plugin(app: &mut App) {
+    app.init_resource::<EffectSettings>()
+        .init_resource::<EffectState>()
+        .init_gizmo_group::<EffectGizmos>()
+        .add_systems(Startup, configure_effect_gizmos)
+        .add_systems(
+            Update,
+            (record_effects, draw_effects)
+                .chain()
+                .run_if(not(in_state(GameState::Menu))),
+        )
+        .add_systems(OnEnter(GameState::Menu), clear_effects);
+}
+
+fn configure_effect_gizmos(mut config_store: ResMut<GizmoConfigStore>) {
+    let (config, _) = config_store.config_mut::<EffectGizmos>();
+    config.line_width = 4.0;
+}
+
+fn clear_effects(mut state: ResMut<EffectState>) {
+    *state = EffectState::default();
+}
+
+/// 记录球的轨迹、落台位置和击球
+fn record_effects(
+    time: Res<Time>,
+    mut state: ResMut<EffectState>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut hits: EventReader<RacketHitEvent>,
+    ball_query: Query<(Entity, &Transform), With<Ball>>,
+    table_q: Query<(), With<Table>>,
+) {
+    let now = time.elapsed_secs();
+    let ball = ball_query.iter().next();
+
+    if let Some((_, transform)) = ball {
+        // 发球重置时球会瞬移，断开拖尾
+        if state
+            .trail
+            .back()
+            .is_some_and(|(last, _)| last.distance(transform.translation) > 0.5)
+        {
+            state.trail.clear();
+        }
+        state.trail.push_back((transform.translation, now));
+    }
+    while state.trail.front().is_some_and(|(_, t)| now - t > TRAIL_DURATION) {
+        state.trail.pop_front();
+    }
+
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+        let Some((entity, transform)) = ball else {
+            continue;
+        };
+        let hit_table =
+            (*e1 == entity && table_q.contains(*e2)) || (*e2 == entity && table_q.contains(*e1));
+        if hit_table {
+            state.bounces.push_back((transform.translation, now));
+        }
+    }
+    while state.bounces.front().is_some_and(|(_, t)| now - t > MARKER_DURATION) {
+        state.bounces.pop_front();
+    }
+
+    for hit in hits.read() {
+        state.flashes.push((hit.racket, now));
+    }
+    state.flashes.retain(|(_, t)| now - t < FLASH_DURATION);
+}
+
+/// 按当前速度推算球落在桌面上的位置，只在球飞向的一侧显示
+fn predict_landing(court: &Court, position: Vec3, velocity: Vec3, gravity_scale: f32) -> Option<Vec3> {
+    let g = GRAVITY * gravity_scale;
+    let surface = court.table_height + court.ball_radius();
+    let height = position.y - surface;
+    if g <= 0.0 || height < 0.0 || velocity.x.abs() < 0.1 {
+        return None;
+    }
+    // height + vy * t - g * t² / 2 = 0 的正根
+    let t = (velocity.y + (velocity.y * velocity.y + 2.0 * g * height).sqrt()) / g;
+    let landing = position + velocity * t;
+    let on_table = landing.x.abs() <= court.half_length() && landing.z.abs() <= court.half_width();
+    let towards_receiver = landing.x.signum() == velocity.x.signum();
+    (on_table && towards_receiver).then(|| landing.with_y(court.table_height))
+}
+
+/// 桌面上平放的圆
+fn flat_circle(position: Vec3) -> Isometry3d {
+    Isometry3d::new(position + Vec3::Y * 0.002, Quat::from_rotation_x(FRAC_PI_2))
+}
+
+fn draw_effects(
+    time: Res<Time>,
+    court: Res<Court>,
+    settings: Res<EffectSettings>,
+    state: Res<EffectState>,
+    mut gizmos: Gizmos<EffectGizmos>,
+    ball_query: Query<(&Transform, &Velocity, Option<&GravityScale>), With<Ball>>,
+    rackets: Query<&GlobalTransform>,
+) {
+    let now = time.elapsed_secs();
+
+    if settings.trail {
+        gizmos.linestrip_gradient(state.trail.iter().map(|(position, t)| {
+            let alpha = 1.0 - (now - t) / TRAIL_DURATION;
+            (*position, TRAIL_COLOR.with_alpha(alpha.clamp(0.0, 1.0)))
+        }));
+    }
+
+    if settings.bounce_marker {
+        for (position, t) in state.bounces.iter() {
+            let age = (now - t) / MARKER_DURATION;
+            let color = MARKER_COLOR.with_alpha(1.0 - age);
+            let surface = position.with_y(court.table_height);
+            gizmos.circle(flat_circle(surface), court.ball_radius() * 1.5, color);
+            gizmos.circle(flat_circle(surface), court.ball_radius() * (1.5 + age * 2.0), color);
+        }
+    }
+
+    if settings.landing_prediction
+        && let Some((transform, velocity, gravity)) = ball_query.iter().next()
+    {
+        let gravity_scale = gravity.map_or(1.0, |gravity| gravity.0);
+        if let Some(landing) = predict_landing(
+            &court,
+            transform.translation,
+            velocity.linvel,
+            gravity_scale,
+        ) {
+            let radius = court.ball_radius() * 2.0;
+            gizmos.circle(flat_circle(landing), radius, LANDING_COLOR);
+            gizmos.line(
+                landing - Vec3::X * radius * 1.5 + Vec3::Y * 0.002,
+                landing + Vec3::X * radius * 1.5 + Vec3::Y * 0.002,
+                LANDING_COLOR,
+            );
+            gizmos.line(
+                landing - Vec3::Z * radius * 1.5 + Vec3::Y * 0.002,
+                landing + Vec3::Z * radius * 1.5 + Vec3::Y * 0.002,
+                LANDING_COLOR,
+            );
+        }
+    }
+
+    if settings.racket_flash {
+        for (racket, t) in state.flashes.iter() {
+            let Ok(transform) = rackets.get(*racket) else {
+                continue;
+            };
+            let alpha = 1.0 - (now - t) / FLASH_DURATION;
+            let size = court.racket_half_extents.max_element() * (1.4 + (1.0 - alpha) * 0.6);
+            gizmos.sphere(
+                Isometry3d::from_translation(transform.translation()),
+                size,
+                FLASH_COLOR.with_alpha(alpha),
+            );
+        }
+    }
+}
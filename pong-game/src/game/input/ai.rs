@@ -0,0 +1,86 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+use crate::game::input::{InputContext, RacketInput};
+
+/// 球拍跟随的高度范围（桌面以上）
+const MIN_HEIGHT: f32 = 0.04;
+const MAX_HEIGHT: f32 = 0.64;
+/// 球拍可以伸出边线的距离
+const REACH: f32 = 0.04;
+
+/// 电脑对手的难度，在菜单的 Difficulty 页面中选择
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AiDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl AiDifficulty {
+    pub const ALL: [AiDifficulty; 3] = [AiDifficulty::Easy, AiDifficulty::Normal, AiDifficulty::Hard];
+
+    /// 界面上显示的文本 id
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            AiDifficulty::Easy => "difficulty-easy",
+            AiDifficulty::Normal => "difficulty-normal",
+            AiDifficulty::Hard => "difficulty-hard",
+        }
+    }
+
+    /// 球拍每秒最多移动的距离
+    fn max_speed(&self) -> f32 {
+        match self {
+            AiDifficulty::Easy => 0.9,
+            AiDifficulty::Normal => 1.5,
+            AiDifficulty::Hard => 2.4,
+        }
+    }
+}
+
+/// 简单的电脑对手：在击球位置上平移，跟随球的高度和左右位置
+pub struct AiInput {
+    position: Option<Vec3>,
+    max_speed: f32,
+}
+
+impl AiInput {
+    pub fn new(difficulty: AiDifficulty) -> Self {
+        AiInput {
+            position: None,
+            max_speed: difficulty.max_speed(),
+        }
+    }
+}
+
+impl RacketInput for AiInput {
+    fn locale_key(&self) -> &'static str {
+        "scheme-ai"
+    }
+
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform> {
+        let court = ctx.court;
+        let max_width = court.half_width() + REACH;
+        let goal = Vec3::new(
+            court.hit_depth(),
+            ctx.ball.y.clamp(
+                court.table_height + MIN_HEIGHT,
+                court.table_height + MAX_HEIGHT,
+            ),
+            ctx.ball.z.clamp(-max_width, max_width),
+        );
+        let current = self.position.unwrap_or(goal);
+        let step = goal - current;
+        let max_step = self.max_speed * ctx.delta;
+        let position = current + step.clamp_length_max(max_step);
+        self.position = Some(position);
+
+        Some(
+            Transform::from_translation(position)
+                .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.0, -PI / 2.0, 0.0)),
+        )
+    }
+}
@@ -0,0 +1,86 @@
+use std::f32::consts::PI;
+
+use bevy::input::gamepad::GamepadAxis;
+use bevy::prelude::*;
+
+use crate::game::input::{InputContext, RacketInput};
+use crate::game::utils::command_handler;
+
+/// 挥拍角度范围，与手机控制器发送的航向角一致
+const MAX_SWING: f32 = PI / 2.0;
+/// 拍面倾斜范围，与手机控制器发送的 gamma 一致
+const MAX_TILT: f32 = PI / 3.0;
+/// 键盘每秒转动的角度
+const KEYBOARD_SPEED: f32 = PI;
+/// 摇杆死区
+const STICK_DEADZONE: f32 = 0.1;
+
+/// 与手机发送的 `rotation:heading,alpha,0,gamma` 对应，x 为挥拍角度，w 为拍面倾斜
+fn swing_pose(swing: f32, tilt: f32, ctx: &InputContext) -> Transform {
+    let rotation = Quat::from_xyzw(swing, 0.0, 0.0, tilt);
+    command_handler::racket_pose(ctx.court, rotation, ctx.ball, ctx.launched)
+}
+
+/// 鼠标在窗口中的位置决定挥拍角度和拍面倾斜，窗口中心为 0
+pub struct MouseInput;
+
+impl RacketInput for MouseInput {
+    fn locale_key(&self) -> &'static str {
+        "scheme-mouse"
+    }
+
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform> {
+        let cursor = ctx.cursor?.clamp(Vec2::NEG_ONE, Vec2::ONE);
+        Some(swing_pose(cursor.x * MAX_SWING, cursor.y * MAX_TILT, ctx))
+    }
+}
+
+/// 按住方向键持续转动，松开后保持当前角度
+#[derive(Default)]
+pub struct KeyboardInput {
+    swing: f32,
+    tilt: f32,
+}
+
+impl RacketInput for KeyboardInput {
+    fn locale_key(&self) -> &'static str {
+        "scheme-keyboard"
+    }
+
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform> {
+        let [left, right, up, down] = match ctx.player {
+            0 => [KeyCode::KeyA, KeyCode::KeyD, KeyCode::KeyW, KeyCode::KeyS],
+            _ => [
+                KeyCode::ArrowLeft,
+                KeyCode::ArrowRight,
+                KeyCode::ArrowUp,
+                KeyCode::ArrowDown,
+            ],
+        };
+        let axis = |negative: KeyCode, positive: KeyCode| {
+            ctx.keys.pressed(positive) as i32 as f32 - ctx.keys.pressed(negative) as i32 as f32
+        };
+        self.swing = (self.swing + axis(left, right) * KEYBOARD_SPEED * ctx.delta)
+            .clamp(-MAX_SWING, MAX_SWING);
+        self.tilt =
+            (self.tilt + axis(down, up) * KEYBOARD_SPEED * ctx.delta).clamp(-MAX_TILT, MAX_TILT);
+        Some(swing_pose(self.swing, self.tilt, ctx))
+    }
+}
+
+/// 左摇杆挥拍、右摇杆倾斜，第 N 名玩家使用第 N 个连接的手柄
+pub struct GamepadInput;
+
+impl RacketInput for GamepadInput {
+    fn locale_key(&self) -> &'static str {
+        "scheme-gamepad"
+    }
+
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform> {
+        let gamepad = ctx.gamepads.get(ctx.player)?;
+        let deadzone = |value: f32| if value.abs() < STICK_DEADZONE { 0.0 } else { value };
+        let swing = deadzone(gamepad.get(GamepadAxis::LeftStickX).unwrap_or(0.0));
+        let tilt = deadzone(gamepad.get(GamepadAxis::RightStickY).unwrap_or(0.0));
+        Some(swing_pose(swing * MAX_SWING, tilt * MAX_TILT, ctx))
+    }
+}
@@ -0,0 +1,353 @@
+use std::f32::consts::PI;
+
+use bevy::ecs::system::SystemParam;
+use bevy::input::gamepad::Gamepad;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::GameState;
+use crate::components::toast::Toast;
+use crate::game::LocalVersus;
+use crate::game::utils::court::Court;
+use crate::game::utils::racket_physics::KinematicTarget;
+use crate::game::utils::{Ball, LaunchState, MoveSpeedText, Racket};
+use crate::locale::{Locale, LocaleMessage};
+use crate::menu::navigation::MenuNavigationSet;
+
+pub mod ai;
+pub mod local;
+pub mod phone;
+pub mod replay;
+
+/// 玩家控制球拍的方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlScheme {
+    /// 手机控制器（WebSocket）
+    #[default]
+    Phone,
+    /// 鼠标在窗口中的位置决定挥拍角度和拍面倾斜
+    Mouse,
+    /// 玩家 1：A/D 挥拍、W/S 倾斜；玩家 2：方向键
+    Keyboard,
+    /// 左摇杆挥拍、右摇杆倾斜，按连接顺序分配手柄
+    Gamepad,
+    /// 电脑控制，跟随球的位置
+    Ai,
+    /// 回放 `PONG_REPLAY_PATH` 中录制的球拍动作
+    Replay,
+}
+
+impl ControlScheme {
+    pub const ALL: [ControlScheme; 6] = [
+        ControlScheme::Phone,
+        ControlScheme::Mouse,
+        ControlScheme::Keyboard,
+        ControlScheme::Gamepad,
+        ControlScheme::Ai,
+        ControlScheme::Replay,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlScheme::Phone => "Phone",
+            ControlScheme::Mouse => "Mouse",
+            ControlScheme::Keyboard => "Keyboard",
+            ControlScheme::Gamepad => "Gamepad",
+            ControlScheme::Ai => "AI",
+            ControlScheme::Replay => "Replay",
+        }
+    }
+
+    /// 界面上显示的文本 id
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            ControlScheme::Phone => "scheme-phone",
+            ControlScheme::Mouse => "scheme-mouse",
+            ControlScheme::Keyboard => "scheme-keyboard",
+            ControlScheme::Gamepad => "scheme-gamepad",
+            ControlScheme::Ai => "scheme-ai",
+            ControlScheme::Replay => "scheme-replay",
+        }
+    }
+}
+
+/// 本机最多的玩家数，双打时每方两人
+pub const MAX_PLAYERS: usize = 4;
+
+/// 每名玩家选择的控制方式，在菜单的 Settings 页面中设置
+#[derive(Resource, Clone, Debug, Default)]
+pub struct PlayerControls {
+    pub schemes: [ControlScheme; MAX_PLAYERS],
+    /// 电脑控制的球拍使用的难度
+    pub difficulty: ai::AiDifficulty,
+}
+
+impl PlayerControls {
+    /// 玩家在本场比赛中使用的控制方式和电脑难度，对战电脑时玩家 2 由
+    /// [`LocalVersus::ai_opponent`] 替换
+    pub fn match_scheme(
+        &self,
+        versus: Option<&LocalVersus>,
+        player: usize,
+    ) -> (ControlScheme, ai::AiDifficulty) {
+        match versus.and_then(|versus| versus.ai_opponent) {
+            Some(difficulty) if player == 1 => (ControlScheme::Ai, difficulty),
+            _ => (
+                self.schemes.get(player).copied().unwrap_or_default(),
+                self.difficulty,
+            ),
+        }
+    }
+}
+
+/// 每帧提供给输入源的信息，坐标已换算到玩家自己一侧（+x）
+pub struct InputContext<'a> {
+    pub player: usize,
+    pub court: &'a Court,
+    pub delta: f32,
+    pub elapsed: f32,
+    pub ball: Vec3,
+    pub launched: bool,
+    pub keys: &'a ButtonInput<KeyCode>,
+    /// 鼠标在主窗口中的位置，窗口中心为 0，边缘为 ±1
+    pub cursor: Option<Vec2>,
+    pub gamepads: &'a [&'a Gamepad],
+    /// 该玩家座位的手机本帧发来的姿态
+    pub phone: Option<Quat>,
+    /// 该玩家座位的手机累加的左右偏移
+    pub phone_shift: f32,
+}
+
+/// 球拍输入源。每帧根据上下文给出球拍的目标位姿，没有新输入时返回 `None` 保持不动。
+/// 新增输入方式只需实现该 trait 并在 [`new_racket_input`] 中注册。
+pub trait RacketInput: Send + Sync + 'static {
+    /// 界面上显示的输入方式名称（文本 id）
+    fn locale_key(&self) -> &'static str;
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform>;
+}
+
+/// 由本机玩家控制的球拍，`mirrored` 表示球拍位于 -x 一侧。
+/// 双打时玩家 1、3 在 +x 一侧，玩家 2、4 在 -x 一侧
+#[derive(Component, Clone, Copy)]
+pub struct PlayerRacket {
+    pub player: usize,
+    pub mirrored: bool,
+}
+
+impl PlayerRacket {
+    pub fn new(player: usize) -> Self {
+        PlayerRacket {
+            player,
+            mirrored: false,
+        }
+    }
+
+    pub fn mirrored(player: usize) -> Self {
+        PlayerRacket {
+            player,
+            mirrored: true,
+        }
+    }
+}
+
+/// 球拍当前使用的输入源，进入比赛时根据 [`PlayerControls`] 自动添加
+#[derive(Component)]
+pub struct RacketDriver(pub Box<dyn RacketInput>);
+
+/// 双打时球拍相对输入源位姿的偏移（玩家自己一侧的坐标），用来给同伴让出位置
+#[derive(Component, Default)]
+pub struct RacketOffset(pub Vec3);
+
+/// 输入源给出的最新目标位姿（世界坐标），由各模式的系统取走并应用
+#[derive(Component, Default)]
+pub struct RacketTarget(pub Option<Transform>);
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RacketInputSet;
+
+pub fn racket_input_plugin(app: &mut App) {
+    app.init_resource::<PlayerControls>()
+        .init_resource::<phone::PhonePoses>()
+        .add_plugins(replay::racket_recording_plugin)
+        .add_systems(
+            PreUpdate,
+            phone::dispatch_controller_commands.before(MenuNavigationSet),
+        )
+        .add_systems(
+            Update,
+            (attach_racket_drivers, drive_rackets, apply_racket_targets)
+                .chain()
+                .in_set(RacketInputSet)
+                .run_if(not(in_state(GameState::Menu))),
+        )
+        .add_systems(
+            Update,
+            racket_hud_system
+                .after(RacketInputSet)
+                .run_if(not(in_state(GameState::Menu))),
+        );
+}
+
+/// 根据玩家选择创建输入源，录制不可用时返回提示玩家的文本
+pub fn new_racket_input(
+    scheme: ControlScheme,
+    difficulty: ai::AiDifficulty,
+) -> Result<Box<dyn RacketInput>, LocaleMessage> {
+    Ok(match scheme {
+        // 第 N 名玩家读取第 N 个座位的手机发来的姿态，见 InputContext::phone
+        ControlScheme::Phone => Box::new(phone::PhoneInput),
+        ControlScheme::Mouse => Box::new(local::MouseInput),
+        ControlScheme::Keyboard => Box::new(local::KeyboardInput::default()),
+        ControlScheme::Gamepad => Box::new(local::GamepadInput),
+        ControlScheme::Ai => Box::new(ai::AiInput::new(difficulty)),
+        ControlScheme::Replay => Box::new(replay::ReplayInput::from_env()?),
+    })
+}
+
+/// 绕 Y 轴旋转 180°，在 +x 和 -x 两侧之间换算
+pub fn mirror(transform: Transform) -> Transform {
+    Transform::from_rotation(Quat::from_rotation_y(PI)).mul_transform(transform)
+}
+
+fn attach_racket_drivers(
+    mut commands: Commands,
+    controls: Res<PlayerControls>,
+    versus: Option<Res<LocalVersus>>,
+    locale: Res<Locale>,
+    query: Query<(Entity, &PlayerRacket), Without<RacketDriver>>,
+    mut toasts: EventWriter<Toast>,
+) {
+    for (entity, racket) in query.iter() {
+        let (scheme, difficulty) = controls.match_scheme(versus.as_deref(), racket.player);
+        // 选了回放却没有可用的录制时改由 AI 控制，并提示玩家原因
+        let input = new_racket_input(scheme, difficulty).unwrap_or_else(|reason| {
+            toasts.send(Toast(locale.message(&reason)));
+            Box::new(ai::AiInput::new(difficulty))
+        });
+        commands.entity(entity).insert((
+            RacketDriver(input),
+            RacketTarget::default(),
+        ));
+    }
+}
+
+/// 各输入方式读取的设备状态
+#[derive(SystemParam)]
+struct InputDevices<'w, 's> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    phone_poses: Res<'w, phone::PhonePoses>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+fn drive_rackets(
+    time: Res<Time>,
+    court: Res<Court>,
+    launch_state: Res<LaunchState>,
+    devices: InputDevices,
+    ball_query: Query<&Transform, With<Ball>>,
+    mut rackets: Query<(&PlayerRacket, &mut RacketDriver, &mut RacketTarget, Option<&RacketOffset>)>,
+) {
+    let ball = ball_query.iter().next().map(|t| t.translation).unwrap_or_default();
+    let cursor = devices.windows.get_single().ok().and_then(|window| {
+        let cursor = window.cursor_position()?;
+        Some(Vec2::new(
+            cursor.x / window.width() * 2.0 - 1.0,
+            cursor.y / window.height() * 2.0 - 1.0,
+        ))
+    });
+    let gamepads: Vec<&Gamepad> = devices.gamepads.iter().collect();
+
+    for (racket, mut driver, mut target, offset) in rackets.iter_mut() {
+        let ctx = InputContext {
+            player: racket.player,
+            court: &court,
+            delta: time.delta_secs(),
+            elapsed: time.elapsed_secs(),
+            ball: if racket.mirrored {
+                mirror(Transform::from_translation(ball)).translation
+            } else {
+                ball
+            },
+            launched: launch_state.launched,
+            keys: &devices.keys,
+            cursor,
+            gamepads: &gamepads,
+            phone: devices.phone_poses.rotations.get(racket.player).copied().flatten(),
+            phone_shift: devices.phone_poses.shifts.get(racket.player).copied().unwrap_or_default(),
+        };
+        if let Some(mut pose) = driver.0.target_pose(&ctx) {
+            pose.translation += offset.map_or(Vec3::ZERO, |offset| offset.0);
+            target.0 = Some(if racket.mirrored { mirror(pose) } else { pose });
+        }
+    }
+}
+
+/// 由物理引擎驱动的球拍交给 racket_physics，按速度移动到目标位姿
+fn apply_racket_targets(
+    mut query: Query<(&mut RacketTarget, &mut KinematicTarget), With<Racket>>,
+) {
+    for (mut target, mut kinematic) in query.iter_mut() {
+        if let Some(pose) = target.0.take() {
+            kinematic.0 = Some(pose);
+        }
+    }
+}
+
+/// 左上角显示玩家 1 的输入方式和球拍移动速度
+fn racket_hud_system(
+    time: Res<Time>,
+    locale: Res<Locale>,
+    rackets: Query<(&PlayerRacket, &RacketDriver, &Transform)>,
+    mut text_query: Query<&mut Text, With<MoveSpeedText>>,
+    mut last_position: Local<Option<Vec3>>,
+) {
+    let Some((_, driver, transform)) = rackets.iter().find(|(racket, _, _)| racket.player == 0)
+    else {
+        return;
+    };
+    let position = transform.translation;
+    let speed = match *last_position {
+        Some(last) if time.delta_secs() > 0.0 => (position - last).length() / time.delta_secs(),
+        _ => 0.0,
+    };
+    *last_position = Some(position);
+
+    for mut text in text_query.iter_mut() {
+        text.0 = locale.format(
+            "hud-driver-speed",
+            &[
+                ("driver", &locale.text(driver.0.locale_key())),
+                ("speed", &format!("{:.2}", speed)),
+            ],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ai_opponent_only_overrides_player_two_for_the_match() {
+        let controls = PlayerControls {
+            schemes: [ControlScheme::Keyboard; MAX_PLAYERS],
+            difficulty: ai::AiDifficulty::Easy,
+        };
+        let mut versus = LocalVersus {
+            names: [String::new(), String::new()],
+            tournament: None,
+            doubles: false,
+            ai_opponent: Some(ai::AiDifficulty::Hard),
+        };
+
+        let opponent = controls.match_scheme(Some(&versus), 1);
+        assert_eq!(opponent, (ControlScheme::Ai, ai::AiDifficulty::Hard));
+        let player = controls.match_scheme(Some(&versus), 0);
+        assert_eq!(player, (ControlScheme::Keyboard, ai::AiDifficulty::Easy));
+
+        versus.ai_opponent = None;
+        assert_eq!(controls.match_scheme(Some(&versus), 1).0, ControlScheme::Keyboard);
+        assert_eq!(controls.match_scheme(None, 1).0, ControlScheme::Keyboard);
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+use crate::game::LocalVersus;
+use crate::game::input::{ControlScheme, InputContext, MAX_PLAYERS, PlayerControls, RacketInput};
+use crate::game::utils::court::Court;
+use crate::game::utils::{CommandDataType, RacketCommandQueue, command_handler};
+use crate::menu::navigation::MenuInput;
+
+/// 各座位手机发来的输入，由 [`dispatch_controller_commands`] 填写
+#[derive(Resource, Default)]
+pub struct PhonePoses {
+    /// 本帧各座位的最新姿态，每帧重新填写
+    pub rotations: [Option<Quat>; MAX_PLAYERS],
+    /// `position` 指令累加出的左右偏移，离开比赛时清零
+    pub shifts: [f32; MAX_PLAYERS],
+}
+
+/// 手机控制器，读取自己座位在 [`PhonePoses`] 中的姿态
+pub struct PhoneInput;
+
+impl RacketInput for PhoneInput {
+    fn locale_key(&self) -> &'static str {
+        "scheme-phone"
+    }
+
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform> {
+        let rotation = ctx.phone?;
+        let mut pose = command_handler::racket_pose(ctx.court, rotation, ctx.ball, ctx.launched);
+        pose.translation.z += ctx.phone_shift;
+        Some(pose)
+    }
+}
+
+/// 每帧取空 ws_handler 写入的指令队列：`nav:` 指令转成 [`MenuInput`]，
+/// 比赛中使用手机控制的座位保留最新的姿态并累加 `position` 位移，其余指令直接丢弃
+pub fn dispatch_controller_commands(
+    game_state: Res<State<GameState>>,
+    court: Res<Court>,
+    controls: Res<PlayerControls>,
+    versus: Option<Res<LocalVersus>>,
+    command_queue: Res<RacketCommandQueue>,
+    mut poses: ResMut<PhonePoses>,
+    mut inputs: EventWriter<MenuInput>,
+) {
+    let in_match = *game_state.get() != GameState::Menu;
+    poses.rotations = [None; MAX_PLAYERS];
+    if !in_match {
+        poses.shifts = [0.0; MAX_PLAYERS];
+    }
+    for command in command_queue.0.lock().unwrap().drain(..) {
+        let phone_seat = in_match
+            && controls.match_scheme(versus.as_deref(), command.seat).0 == ControlScheme::Phone;
+        match command.command {
+            CommandDataType::Menu(input) => {
+                inputs.send(input);
+            }
+            CommandDataType::Rotation(rotation) if phone_seat => {
+                poses.rotations[command.seat] = Some(rotation);
+            }
+            CommandDataType::Position(delta) if phone_seat => {
+                let shift = &mut poses.shifts[command.seat];
+                *shift = command_handler::shift_racket(&court, *shift, delta);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::game::utils::RacketTransformCommand;
+
+    fn send(app: &mut App, seat: usize, command: CommandDataType) {
+        let queue = app.world().resource::<RacketCommandQueue>().0.clone();
+        queue.lock().unwrap().push(RacketTransformCommand { command, seat });
+    }
+
+    #[test]
+    fn position_commands_shift_only_phone_seats() {
+        let mut app = App::new();
+        app.add_plugins(StatesPlugin)
+            .insert_state(GameState::GameRunning)
+            .add_event::<MenuInput>()
+            .init_resource::<Court>()
+            .init_resource::<PhonePoses>()
+            .insert_resource(RacketCommandQueue(Arc::new(Mutex::new(Vec::new()))))
+            .insert_resource(PlayerControls {
+                schemes: [
+                    ControlScheme::Phone,
+                    ControlScheme::Keyboard,
+                    ControlScheme::Phone,
+                    ControlScheme::Phone,
+                ],
+                ..default()
+            })
+            .add_systems(Update, dispatch_controller_commands);
+
+        send(&mut app, 0, CommandDataType::Position(Vec3::new(0.1, 9.8, 0.0)));
+        send(&mut app, 0, CommandDataType::Position(Vec3::new(0.1, 9.8, 0.0)));
+        send(&mut app, 1, CommandDataType::Position(Vec3::new(0.1, 9.8, 0.0)));
+        send(&mut app, 2, CommandDataType::Position(Vec3::new(100.0, 0.0, 0.0)));
+        app.update();
+
+        let half_width = app.world().resource::<Court>().half_width();
+        let shifts = app.world().resource::<PhonePoses>().shifts;
+        assert!((shifts[0] - 0.2).abs() < 1e-5, "位移按 x 分量累加: {}", shifts[0]);
+        assert_eq!(shifts[1], 0.0, "不是手机控制的座位忽略位移");
+        assert_eq!(shifts[2], half_width, "不超出球台两侧");
+
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Menu);
+        app.update();
+        app.update();
+        assert_eq!(app.world().resource::<PhonePoses>().shifts, [0.0; MAX_PLAYERS]);
+    }
+}
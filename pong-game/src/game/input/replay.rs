@@ -0,0 +1,141 @@
+use std::env;
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+use crate::game::input::{InputContext, PlayerRacket, RacketInput, RacketInputSet, mirror};
+use crate::game::online::protocol::PoseData;
+use crate::locale::LocaleMessage;
+
+/// 录制下来的一帧球拍位姿，`time` 为相对录制开始的秒数
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ReplayFrame {
+    pub time: f32,
+    pub pose: PoseData,
+}
+
+/// 按时间循环回放录制的球拍动作
+pub struct ReplayInput {
+    frames: Vec<ReplayFrame>,
+    start: Option<f32>,
+}
+
+impl ReplayInput {
+    pub fn new(frames: Vec<ReplayFrame>) -> Option<Self> {
+        if frames.is_empty() {
+            return None;
+        }
+        Some(ReplayInput {
+            frames,
+            start: None,
+        })
+    }
+
+    /// 读取 `PONG_REPLAY_PATH` 指定的录制文件，失败时返回提示玩家的文本
+    pub fn from_env() -> Result<Self, LocaleMessage> {
+        let Ok(path) = env::var("PONG_REPLAY_PATH") else {
+            warn!("⚠️ 未设置 PONG_REPLAY_PATH，没有可回放的录制");
+            return Err(LocaleMessage::new("toast-replay-missing"));
+        };
+        let frames = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Vec<ReplayFrame>>(&bytes).ok());
+        match frames.and_then(ReplayInput::new) {
+            Some(replay) => {
+                info!("📼 回放球拍录制: {}", path);
+                Ok(replay)
+            }
+            None => {
+                error!("❌ 无法读取球拍录制: {}", path);
+                Err(LocaleMessage::new("toast-replay-unreadable").arg("path", path))
+            }
+        }
+    }
+}
+
+impl RacketInput for ReplayInput {
+    fn locale_key(&self) -> &'static str {
+        "scheme-replay"
+    }
+
+    fn target_pose(&mut self, ctx: &InputContext) -> Option<Transform> {
+        let start = *self.start.get_or_insert(ctx.elapsed);
+        let duration = self.frames.last()?.time.max(f32::EPSILON);
+        let t = (ctx.elapsed - start) % duration;
+
+        let index = self.frames.partition_point(|frame| frame.time <= t);
+        let from = &self.frames[index.saturating_sub(1)];
+        let to = self.frames.get(index).unwrap_or(from);
+        let span = (to.time - from.time).max(f32::EPSILON);
+        let pose = from.pose.lerp(&to.pose, ((t - from.time) / span).clamp(0.0, 1.0));
+
+        let mut transform = Transform::default();
+        pose.apply_to(&mut transform);
+        Some(transform)
+    }
+}
+
+/// 设置 `PONG_RECORD_PATH` 后录制玩家 1 的球拍，回到菜单时写入文件
+#[derive(Resource)]
+struct RacketRecording {
+    path: String,
+    start: Option<f32>,
+    frames: Vec<ReplayFrame>,
+}
+
+pub fn racket_recording_plugin(app: &mut App) {
+    let Ok(path) = env::var("PONG_RECORD_PATH") else {
+        return;
+    };
+    app.insert_resource(RacketRecording {
+        path,
+        start: None,
+        frames: Vec::new(),
+    })
+    .add_systems(
+        Update,
+        record_racket_system
+            .after(RacketInputSet)
+            .run_if(not(in_state(GameState::Menu))),
+    )
+    .add_systems(OnEnter(GameState::Menu), save_racket_recording);
+}
+
+fn record_racket_system(
+    time: Res<Time>,
+    mut recording: ResMut<RacketRecording>,
+    rackets: Query<(&PlayerRacket, &Transform)>,
+) {
+    let Some((racket, transform)) = rackets.iter().find(|(racket, _)| racket.player == 0) else {
+        return;
+    };
+    // 统一按 +x 一侧录制，回放时再按需要镜像
+    let transform = if racket.mirrored {
+        mirror(*transform)
+    } else {
+        *transform
+    };
+    let start = *recording.start.get_or_insert(time.elapsed_secs());
+    let frame = ReplayFrame {
+        time: time.elapsed_secs() - start,
+        pose: PoseData::from(&transform),
+    };
+    recording.frames.push(frame);
+}
+
+fn save_racket_recording(mut recording: ResMut<RacketRecording>) {
+    if recording.frames.is_empty() {
+        return;
+    }
+    let frames = std::mem::take(&mut recording.frames);
+    recording.start = None;
+    match serde_json::to_vec(&frames) {
+        Ok(bytes) => match fs::write(&recording.path, bytes) {
+            Ok(_) => info!("📼 已保存球拍录制: {}（{} 帧）", recording.path, frames.len()),
+            Err(e) => error!("❌ 无法保存球拍录制 {}: {}", recording.path, e),
+        },
+        Err(e) => error!("❌ 序列化球拍录制失败: {}", e),
+    }
+}
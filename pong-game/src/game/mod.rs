@@ -1,23 +1,44 @@
 use std::f32::consts::PI;
 
-use bevy::{prelude::*, render::camera};
+use bevy::prelude::*;
 use bevy_rapier3d::plugin::RapierPhysicsPlugin;
 use bevy_rapier3d::plugin::{RapierConfiguration, TimestepMode};
 use bevy_rapier3d::prelude::*;
 
 use crate::GameState;
-
-use crate::components::button::button_system;
-
+use crate::components::button::{ButtonActions, corner_button};
+use crate::components::theme::{TextSize, UiTheme};
+use crate::locale::Locale;
+
+
+pub mod audio;
+pub mod camera;
+#[cfg(feature = "debug-overlay")]
+pub mod debug;
+pub mod doubles;
+pub mod effects;
+pub mod input;
+pub mod online;
+pub mod pause;
 pub mod practice;
+pub mod rooms;
+pub mod scoring;
+pub mod stats;
+pub mod tournament;
 pub mod utils;
 
-use utils::{command_handler, controller_server, init_resources, ws_handler};
+use camera::{CameraRig, viewport::ViewportSlot};
+use input::{MAX_PLAYERS, PlayerRacket, RacketOffset, mirror};
+use pause::{PauseState, not_paused};
+use scoring::{MatchConfig, ScoringSet, ShotEvent};
+use utils::court::Court;
+use utils::racket_physics::{KinematicTarget, RacketMotion};
+use utils::scene_collider::{self, SceneCollider, SceneColliderShape};
+use utils::{controller_server, init_resources, racket_physics, rally, spectator, sync_game_status};
 
 use utils::{
-    Ball, BallTableCollisionCount, CameraComponent, CommandDataType, LaunchState, LeftCamera,
-    ModelComponent, MoveSpeedText, Racket, RacketCommandQueue, RacketTransformCommand, RightCamera,
-    Table,
+    Ball, LeftCamera, ModelComponent, MoveSpeedText, Net, Racket, RightCamera, ServerStatusText,
+    Table, toast_controller_changes, update_controller_qr_code, update_server_status_text,
 };
 
 use super::despawn_screen;
@@ -25,19 +46,34 @@ use super::despawn_screen;
 #[derive(Component)]
 pub struct OnNormalGameScreen;
 
+/// 本机双人对战，玩家 2 的球拍在 -x 一侧；没有该资源时本地比赛为玩家 1 对墙。
+/// 由菜单在进入比赛前插入，回到菜单时移除
+#[derive(Resource, Clone, Debug)]
+pub struct LocalVersus {
+    /// 双方的名字，双打时为队名
+    pub names: [String; 2],
+    /// 锦标赛中的对阵（轮次，场次），比赛结束后自动记录结果
+    pub tournament: Option<(usize, usize)>,
+    /// 双打：四支球拍、四个画面，玩家 3、4 分别是玩家 1、2 的同伴
+    pub doubles: bool,
+    /// 对战电脑时玩家 2 的难度，只在本场比赛中替换玩家 2 的控制方式
+    pub ai_opponent: Option<input::ai::AiDifficulty>,
+}
+
 pub fn game_plugin(app: &mut App) {
     app.add_plugins(init_resources)
         .add_systems(OnEnter(GameState::GameEntering), game_init)
         .add_plugins((
             RapierPhysicsPlugin::<NoUserData>::default(),
-            RapierDebugRenderPlugin::default(),
+            racket_physics::racket_physics_plugin,
+            scene_collider::scene_collider_plugin,
+            rally::rally_plugin,
+            doubles::doubles_plugin,
         ))
         .add_systems(
             OnEnter(GameState::GameIniting),
             (
                 setup,
-                ws_handler::start_websocket_server,
-                controller_server::start_controller_server,
                 setup_physics_config,
                 over_init,
             ),
@@ -45,21 +81,35 @@ pub fn game_plugin(app: &mut App) {
         .add_systems(
             Update,
             (
-                command_handler::apply_racket_commands,
-                collision_event_system.in_set(PhysicsSet::SyncBackend),
-                contact_force_system.in_set(PhysicsSet::SyncBackend),
-                bounce_system.in_set(PhysicsSet::SyncBackend),
-                control_ball_system,
+                rally::count_ball_contacts.in_set(PhysicsSet::SyncBackend),
+                bounce_system
+                    .in_set(PhysicsSet::SyncBackend)
+                    .run_if(not(resource_exists::<LocalVersus>)),
+                (rally::end_rally_system, scoring::serve_after_rally)
+                    .chain()
+                    .after(ScoringSet)
+                    .run_if(not_paused),
             )
                 .run_if(in_state(GameState::GameRunning)),
         )
         .add_systems(
             OnEnter(GameState::Menu),
-            despawn_screen::<OnNormalGameScreen>,
+            (despawn_screen::<OnNormalGameScreen>, end_local_versus),
         )
+        .add_systems(Startup, controller_server::start_controller_server)
         .add_systems(
             Update,
-            (button_system, menu_action).run_if(not(in_state(GameState::Menu))),
+            (
+                sync_game_status,
+                update_server_status_text,
+                update_controller_qr_code,
+                toast_controller_changes,
+                spectator::publish_spectator_state,
+            ),
+        )
+        .add_systems(
+            Update,
+            menu_action.run_if(not(in_state(GameState::Menu))),
         );
 }
 
@@ -69,15 +119,14 @@ enum ButtonAction {
 }
 
 pub fn menu_action(
-    interaction_query: Query<(&Interaction, &ButtonAction), (Changed<Interaction>, With<Button>)>,
-    mut app_exit_events: EventWriter<AppExit>,
-    mut game_state: ResMut<NextState<GameState>>,
+    interaction_query: ButtonActions<ButtonAction>,
+    mut pause_state: ResMut<NextState<PauseState>>,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
                 ButtonAction::Esc => {
-                    game_state.set(GameState::Menu);
+                    pause_state.set(PauseState::Paused);
                 }
             }
         }
@@ -99,6 +148,29 @@ fn setup_physics_config(mut commands: Commands, mut timestep_mode: ResMut<Timest
     }
 }
 
+fn end_local_versus(mut commands: Commands) {
+    commands.remove_resource::<LocalVersus>();
+}
+
+/// 球拍的物理组件，由速度驱动的运动学刚体
+fn racket_body(court: &Court) -> impl Bundle + use<> {
+    (
+        Racket,
+        RigidBody::KinematicVelocityBased,
+        Velocity::zero(),
+        KinematicTarget::default(),
+        RacketMotion::default(),
+        ActiveEvents::COLLISION_EVENTS,
+        court.racket_collider(),
+        SceneCollider::new(SceneColliderShape::Cuboid),
+        Ccd { enabled: true },
+        Restitution {
+            coefficient: 0.,
+            combine_rule: CoefficientCombineRule::Max,
+        },
+    )
+}
+
 fn game_init(mut commands: Commands, mut game_state: ResMut<NextState<GameState>>) {
     game_state.set(GameState::GameIniting);
 }
@@ -107,16 +179,29 @@ fn over_init(mut game_state: ResMut<NextState<GameState>>) {
     game_state.set(GameState::GameRunning);
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<&Window>) {
-    let window = windows.single();
-    let width = window.width();
-    let height = window.height();
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    court: Res<Court>,
+    versus: Option<Res<LocalVersus>>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let doubles = versus.as_ref().is_some_and(|versus| versus.doubles);
+    commands.insert_resource(match &versus {
+        Some(versus) if versus.doubles => MatchConfig::doubles(versus.names.clone()),
+        Some(versus) => MatchConfig::local(versus.names.clone()),
+        None => MatchConfig::solo([
+            locale.format("controls-player", &[("number", &1)]),
+            locale.text("player-wall"),
+        ]),
+    });
 
     let model_names = vec!["tennis_table.glb", "pong-racket.glb", "ball.glb"];
     let pos = vec![
-        Vec3::new(0.0, 0.0, 0.0),
-        Vec3::new(1.0, 1.0, 0.0),
-        Vec3::new(0.95, 1.05, 0.0),
+        Vec3::ZERO,
+        court.racket_position(),
+        court.serve_position(),
     ];
     let rotation = vec![
         Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, 0.0),
@@ -133,16 +218,18 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         let model_path = format!("models/{}", model_name);
         let gltf_handle = asset_server.load(GltfAssetLabel::Scene(0).from_asset(model_path));
 
-        let scale_num = match *model_name {
-            "ball.glb" => 2.0,
-            _ => 1.0,
+        // 模型按标准尺寸缩放
+        let scale = match *model_name {
+            "tennis_table.glb" => court.table_scale(),
+            "ball.glb" => Vec3::splat(court.ball_scale()),
+            _ => Vec3::ONE,
         };
 
         let mut entity = commands.spawn((
             SceneRoot(gltf_handle),
             Transform::from_xyz(pos[i].x, pos[i].y, pos[i].z)
                 .with_rotation(rotation[i])
-                .with_scale(Vec3::splat(scale_num)),
+                .with_scale(scale),
             OnNormalGameScreen,
         ));
 
@@ -153,26 +240,33 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
                     Table,
                     RigidBody::Fixed,
                     ActiveEvents::COLLISION_EVENTS,
-                    Collider::cuboid(1.2, 0.75, 1.0),
+                    court.table_collider(),
+                    SceneCollider::new(SceneColliderShape::Cuboid).node("TT_Board"),
                     Ccd { enabled: true },
                     Restitution {
-                        coefficient: 0.9, // 让桌子也有高弹性
+                        coefficient: court.table_restitution,
                         combine_rule: CoefficientCombineRule::Max,
                     },
                 ));
+                // 球网挂在球桌下面，模型加载后从 TT_Net 节点生成碰撞体
+                entity.with_children(|parent| {
+                    parent.spawn((
+                        court.net_transform(),
+                        Net,
+                        OnNormalGameScreen,
+                        RigidBody::Fixed,
+                        ActiveEvents::COLLISION_EVENTS,
+                        court.net_collider(),
+                        SceneCollider::new(SceneColliderShape::Cuboid).node("TT_Net"),
+                        Ccd { enabled: true },
+                    ));
+                });
             }
             Some(ModelComponent::Rkt) => {
-                entity.insert((
-                    Racket,
-                    RigidBody::KinematicPositionBased,
-                    ActiveEvents::COLLISION_EVENTS,
-                    Collider::cuboid(0.07, 0.01, 0.12),
-                    Ccd { enabled: true },
-                    Restitution {
-                        coefficient: 0.,
-                        combine_rule: CoefficientCombineRule::Max,
-                    },
-                ));
+                entity.insert((PlayerRacket::new(0), racket_body(&court)));
+                if doubles {
+                    entity.insert(RacketOffset::default());
+                }
             }
             Some(ModelComponent::Bal) => {
                 entity.insert((
@@ -181,7 +275,9 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
                     Velocity::zero(),
                     GravityScale(0.0),
                     ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS,
-                    Collider::ball(0.01),
+                    court.ball_collider(),
+                    court.ball_mass(),
+                    SceneCollider::new(SceneColliderShape::Ball),
                     Ccd { enabled: true },
                     Restitution {
                         coefficient: 0.4, // 从 0.8 降到 0.4
@@ -201,13 +297,28 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         };
     }
 
-    commands.spawn((
-        Transform::from_xyz(0.0, 0.75, 0.0),
-        RigidBody::Fixed,
-        ActiveEvents::COLLISION_EVENTS,
-        Collider::cuboid(0.1, 0.1, 1.0),
-        Ccd { enabled: true },
-    ));
+    // 双人对战时玩家 2 的球拍在球桌另一侧；双打时玩家 3、4 分别和玩家 1、2 同侧
+    let players = match &versus {
+        Some(_) if doubles => MAX_PLAYERS,
+        Some(_) => 2,
+        None => 1,
+    };
+    for player in 1..players {
+        let transform = Transform::from_translation(pos[1]).with_rotation(rotation[1]);
+        let mut racket = commands.spawn((
+            SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/pong-racket.glb"))),
+            racket_body(&court),
+            OnNormalGameScreen,
+        ));
+        if player % 2 == 0 {
+            racket.insert((transform, PlayerRacket::new(player)));
+        } else {
+            racket.insert((mirror(transform), PlayerRacket::mirrored(player)));
+        }
+        if doubles {
+            racket.insert(RacketOffset::default());
+        }
+    }
 
     // light
     commands.spawn((
@@ -218,33 +329,34 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         Transform::from_xyz(0.0, 3.0, 0.0),
         OnNormalGameScreen,
     ));
-    // let positions = [UVec2::new(0, 0), UVec2::new(width as u32 / 2, 0)];
-    let size = UVec2::new(width as u32 / 2, height as u32);
-    let components = [CameraComponent::LC, CameraComponent::RC];
-    for (i, component) in components.iter().enumerate() {
+    // 左右分屏，双打时每名玩家一个画面，视口由 camera_rig_plugin 按窗口大小划分
+    let cameras = if doubles { MAX_PLAYERS } else { 2 };
+    for i in 0..cameras {
+        let side = if i % 2 == 0 { 1.0 } else { -1.0 };
         let mut cmd = commands.spawn((
             Camera3d { ..default() },
             Camera {
-                viewport: Some(camera::Viewport {
-                    physical_position: UVec2::new(i as u32 * width as u32 / 2, 0),
-                    physical_size: size,
-                    ..default()
-                }),
                 order: i as isize,
                 ..default()
             },
-            Transform::from_xyz(-2.5 * (i as f32 * 2.0 - 1.0), 1.50, 0.0)
-                .looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+            court.player_camera(side),
+            CameraRig { side, player: i },
+            ViewportSlot::split(i as u32, cameras as u32),
             OnNormalGameScreen,
         ));
-        match component {
-            CameraComponent::LC => cmd.insert(LeftCamera),
-            CameraComponent::RC => cmd.insert(RightCamera),
-        };
+        match i {
+            0 => {
+                cmd.insert(LeftCamera);
+            }
+            1 => {
+                cmd.insert(RightCamera);
+            }
+            _ => {}
+        }
     }
 
     commands.spawn((
-        Text::new("move speed:"),
+        Text::new(locale.text("hud-move-speed")),
         Node {
             position_type: PositionType::Absolute,
             top: Val::Px(10.0),
@@ -264,178 +376,45 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         MoveSpeedText,
         OnNormalGameScreen,
     ));
-    let button_node = Node {
-        // width: Val::Px(100.0),
-        // height: Val::Px(100.0),
-        position_type: PositionType::Absolute,
-        top: Val::Px(10.0),
-        right: Val::Px(10.0),
-        margin: UiRect::all(Val::Px(10.0)),
-        align_items: AlignItems::Center,
-        justify_content: JustifyContent::Center,
-
-        ..default()
-    };
-    let button_text = TextFont {
-        font_size: 32.0,
-        ..default()
-    };
     commands.spawn((
-        Text::new("Esc"),
-        button_text.clone(),
-        Button,
-        button_node.clone(),
+        theme.text(String::new(), TextSize::Small),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        ServerStatusText,
+        OnNormalGameScreen,
+    ));
+    commands.spawn((
+        corner_button(&theme, locale.text("hud-exit")),
         ButtonAction::Esc,
         OnNormalGameScreen
     ));
 }
 
-fn control_ball_system(
-    mut commands: Commands,
-    mut query: Query<
-        (
-            Entity,
-            &mut Transform,
-            Option<&RigidBody>,
-            Option<&GravityScale>,
-        ),
-        (With<Ball>, Without<Racket>),
-    >,
-    mut launch_state: ResMut<LaunchState>,
-    mut counter: ResMut<BallTableCollisionCount>,
-    racket_query: Query<&Transform, With<Racket>>,
-) {
-    let racket_transform = match racket_query.get_single() {
-        Ok(t) => t,
-        Err(_) => return, // 没有找到 Racket，跳过
-    };
-    for (entity, mut transform, rb, gs) in query.iter_mut() {
-        if launch_state.launched {
-            // 发射，设置为 Dynamic，由物理引擎接管
-            if gs.unwrap().0 == 0.0 {
-                commands.entity(entity).insert(GravityScale(1.0));
-            }
-        } /* else {
-        // transform.translation.z = racket_transform.translation.z + transform.rotation.y * 0.05;
-        // 未发射，保持固定高度，但允许参与碰撞
-        // transform.translation.y = 1.04;
-        // 如果不是 Kinematic，设置为 Kinematic
-        if !matches!(rb, Some(RigidBody::KinematicPositionBased)) {
-        commands
-        .entity(entity)
-        .insert(GravityScale(0.0))
-        .insert(Velocity::zero());
-        }
-        } */
-        if transform.translation.x > 2.0
-            || transform.translation.y > 2.0
-            || transform.translation.y < 0.0
-            || transform.translation.z > 2.0
-            || transform.translation.z < -2.0
-            || counter.count > 2
-        {
-            // 球超出边界，重置位置
-            transform.translation = Vec3::new(0.9, 1.0, 0.0);
-            commands
-                .entity(entity)
-                .insert(GravityScale(0.0))
-                .insert(Velocity::zero());
-            launch_state.launched = false;
-            counter.count = 0;
-        }
-    }
-}
-
 fn bounce_system(
     mut commands: Commands,
     query: Query<(Entity, &Transform), With<Ball>>,
     mut velocities: Query<&mut Velocity, With<Ball>>,
+    court: Res<Court>,
+    mut shots: EventWriter<ShotEvent>,
 ) {
     for (entity, tf) in query.iter() {
-        if tf.translation.x < -1.5 {
+        if tf.translation.x < -court.back_wall() {
             // 直接改 Velocity 组件
             if let Ok(mut vel) = velocities.get_mut(entity) {
+                if vel.linvel.x < 0.0 {
+                    // 墙把球打回来，记作玩家 2 击球
+                    shots.send(ShotEvent {
+                        player: 1,
+                        seat: None,
+                        speed: vel.linvel.length() * 0.8,
+                    });
+                }
                 vel.linvel.x = vel.linvel.x.abs() * 0.8;
             }
         }
     }
 }
-
-fn collision_event_system(
-    mut collision_events: EventReader<CollisionEvent>,
-    colliders: Query<(Entity, &Collider)>,
-    racket_q: Query<&Racket>,
-    ball_q: Query<&Ball>,
-    mut ball_vel_q: Query<&mut Velocity>,
-    table_q: Query<(), With<Table>>,
-    mut launch_state: ResMut<LaunchState>,
-    mut counter: ResMut<BallTableCollisionCount>,
-) {
-    for event in collision_events.read() {
-        if let CollisionEvent::Started(e1, e2, _) = event {
-            /* let hit = (racket_q.get(*e1).is_ok() && ball_q.get(*e2).is_ok())
-                           || (racket_q.get(*e2).is_ok() && ball_q.get(*e1).is_ok());
-                       if hit {
-                           launch_state.launched = true;
-                           counter.count = 0;
-                       }
-            */
-            let e1_is_ball = ball_q.get(*e1).is_ok();
-            let e2_is_ball = ball_q.get(*e2).is_ok();
-            let e1_is_racket = racket_q.get(*e1).is_ok();
-            let e2_is_racket = racket_q.get(*e2).is_ok();
-
-            let hit_racket = (e1_is_ball && e2_is_racket) || (e2_is_ball && e1_is_racket);
-            if hit_racket {
-                launch_state.launched = true;
-                counter.count = 0;
-                println!("Ball <-> Racket 碰撞触发！");
-            }
-
-            // 球-桌子碰撞计数
-            let hit_table = (e1_is_ball && table_q.get(*e2).is_ok())
-                || (e2_is_ball && table_q.get(*e1).is_ok());
-            if hit_table {
-                counter.count += 1;
-                println!("Ball <-> Table 碰撞，累计：{}", counter.count);
-            }
-        }
-    }
-}
-
-fn contact_force_system(
-    mut force_events: EventReader<ContactForceEvent>,
-    ball_q: Query<&Ball>,
-    racket_q: Query<&Racket>,
-    mut ball_vel_q: Query<&mut Velocity, With<Ball>>,
-) {
-    for event in force_events.read() {
-        let e1 = event.collider1;
-        let e2 = event.collider2;
-
-        let e1_is_ball = ball_q.get(e1).is_ok();
-        let e2_is_ball = ball_q.get(e2).is_ok();
-        let e1_is_racket = racket_q.get(e1).is_ok();
-        let e2_is_racket = racket_q.get(e2).is_ok();
-
-        let (ball_entity, hit) = match (e1_is_ball, e2_is_racket, e2_is_ball, e1_is_racket) {
-            (true, true, _, _) => (e1, true),
-            (_, _, true, true) => (e2, true),
-            _ => (e1, false),
-        };
-
-        if hit {
-            // 归一化方向，避免 NaN
-            let force_dir = event.total_force.normalize_or_zero();
-            let speed = event.total_force_magnitude * 0.2; // 自定义系数
-
-            if let Ok(mut vel) = ball_vel_q.get_mut(ball_entity) {
-                vel.linvel = force_dir * 3.0;
-                println!(
-                    "设置球 {:?} 速度为：方向 {:?}, 强度 {:.2}, 最终速度 {:?}",
-                    ball_entity, force_dir, event.total_force_magnitude, vel.linvel
-                );
-            }
-        }
-    }
-}
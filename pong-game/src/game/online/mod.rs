@@ -0,0 +1,616 @@
+use std::f32::consts::PI;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::plugin::{RapierConfiguration, TimestepMode};
+use bevy_rapier3d::prelude::*;
+
+use crate::GameState;
+use crate::components::button::{ButtonActions, corner_button};
+use crate::components::theme::{TextSize, UiTheme};
+use crate::locale::Locale;
+use crate::game::{
+    camera::{CameraRig, viewport::ViewportSlot},
+    input::{PlayerRacket, RacketInputSet, RacketTarget, mirror},
+    pause::PauseState,
+    scoring::{self, MatchConfig, MatchScore, ScoringSet},
+    utils::{
+        Ball, BallTableCollisionCount, LaunchState, Net, Racket, ServerStatusText, Table, WsRuntime,
+        court::Court,
+        racket_physics::{KinematicTarget, RacketMotion},
+        rally,
+        scene_collider::{SceneCollider, SceneColliderShape},
+    },
+};
+
+use super::despawn_screen;
+
+pub mod net;
+pub mod protocol;
+pub mod sync;
+
+use net::{OnlineLink, OnlineRole};
+use protocol::{BallState, NetMessage, ONLINE_PROTOCOL_VERSION, PoseData};
+use sync::{PredictionState, SnapshotBuffer, TimedSnapshot};
+
+/// 主机发送快照的频率
+const SNAPSHOT_RATE: f32 = 30.0;
+/// 客户端握手重试间隔
+const HELLO_INTERVAL: f32 = 0.5;
+
+#[derive(Component)]
+pub struct OnOnlineGameScreen;
+
+/// 主机一侧（+x）的球拍
+#[derive(Component)]
+struct HostRacket;
+
+/// 客户端一侧（-x）的球拍
+#[derive(Component)]
+struct ClientRacket;
+
+/// 客户端本地预测出的球拍位姿，显示时再叠加修正量
+#[derive(Component, Default)]
+struct PredictedPose(PoseData);
+
+#[derive(Component)]
+struct OnlineStatusText;
+
+/// 双方的连接状态
+#[derive(Resource, Default)]
+struct OnlineSession {
+    connected: bool,
+    left: bool,
+    /// 主机最近处理的客户端输入序号
+    last_input_seq: u32,
+    client_pose: Option<PoseData>,
+    tick: u32,
+    snapshot_timer: Timer,
+    hello_timer: Timer,
+}
+
+impl OnlineSession {
+    fn new() -> Self {
+        OnlineSession {
+            snapshot_timer: Timer::from_seconds(1.0 / SNAPSHOT_RATE, TimerMode::Repeating),
+            hello_timer: Timer::from_seconds(HELLO_INTERVAL, TimerMode::Repeating),
+            ..default()
+        }
+    }
+}
+
+fn is_host(role: Option<Res<OnlineRole>>) -> bool {
+    role.is_some_and(|role| role.is_host())
+}
+
+fn is_client(role: Option<Res<OnlineRole>>) -> bool {
+    role.is_some_and(|role| !role.is_host())
+}
+
+/// 两台机器联机对战。
+/// 主机负责球的物理模拟并定时广播快照；客户端本地预测自己的球拍，
+/// 对主机的球拍和球做快照插值，并根据主机确认的输入序号修正预测。
+pub fn game_online_plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameState::GameOnlineEntering), game_init)
+        .add_systems(
+            OnEnter(GameState::GameOnlineIniting),
+            (
+                setup,
+                setup_physics_config.run_if(is_host),
+                over_init,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                host_receive_system,
+                apply_client_racket_input,
+                rally::count_ball_contacts.in_set(PhysicsSet::SyncBackend),
+                rally::end_rally_system,
+                scoring::serve_after_rally,
+                broadcast_snapshot_system,
+            )
+                .chain()
+                .after(RacketInputSet)
+                .after(ScoringSet)
+                .run_if(in_state(GameState::GameOnlineRunning).and(is_host)),
+        )
+        .add_systems(
+            Update,
+            (
+                client_handshake_system,
+                client_receive_system,
+                predict_local_racket,
+                interpolate_remote_system,
+            )
+                .chain()
+                .after(RacketInputSet)
+                .run_if(in_state(GameState::GameOnlineRunning).and(is_client)),
+        )
+        .add_systems(
+            Update,
+            (update_online_status_text, menu_action)
+                .run_if(in_state(GameState::GameOnlineRunning)),
+        )
+        .add_systems(
+            OnEnter(GameState::Menu),
+            (despawn_screen::<OnOnlineGameScreen>, leave_online),
+        );
+}
+
+#[derive(Component)]
+enum ButtonAction {
+    Esc,
+}
+
+fn menu_action(
+    interaction_query: ButtonActions<ButtonAction>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+) {
+    for (interaction, menu_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match menu_button_action {
+                ButtonAction::Esc => {
+                    pause_state.set(PauseState::Paused);
+                }
+            }
+        }
+    }
+}
+
+fn game_init(
+    mut commands: Commands,
+    rt: Res<WsRuntime>,
+    role: Option<Res<OnlineRole>>,
+    mut launch_state: ResMut<LaunchState>,
+    mut counter: ResMut<BallTableCollisionCount>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let Some(role) = role else {
+        game_state.set(GameState::Menu);
+        return;
+    };
+    commands.insert_resource(net::spawn_link(&rt, role.clone()));
+    commands.insert_resource(OnlineSession::new());
+    commands.insert_resource(SnapshotBuffer::default());
+    commands.insert_resource(PredictionState::default());
+    launch_state.launched = false;
+    counter.count = 0;
+    game_state.set(GameState::GameOnlineIniting);
+}
+
+fn over_init(mut game_state: ResMut<NextState<GameState>>) {
+    game_state.set(GameState::GameOnlineRunning);
+}
+
+/// 离开联机时关闭网络任务，drop 发送端后任务会通知对端并退出
+fn leave_online(mut commands: Commands) {
+    commands.remove_resource::<OnlineLink>();
+    commands.remove_resource::<OnlineRole>();
+    commands.remove_resource::<OnlineSession>();
+    commands.remove_resource::<SnapshotBuffer>();
+    commands.remove_resource::<PredictionState>();
+}
+
+fn setup_physics_config(mut commands: Commands, mut timestep_mode: ResMut<TimestepMode>) {
+    commands.spawn(RapierConfiguration {
+        gravity: Vec3::new(0.0, -9.81, 0.0),
+        physics_pipeline_active: true,
+        query_pipeline_active: true,
+        scaled_shape_subdivision: 1,
+        force_update_from_transform_changes: true,
+    });
+    *timestep_mode = TimestepMode::Variable {
+        max_dt: 1. / 120.,
+        time_scale: 0.5,
+        substeps: 1,
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    role: Res<OnlineRole>,
+    court: Res<Court>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let host = role.is_host();
+    commands.insert_resource(MatchConfig::versus(
+        host,
+        [locale.text("player-host"), locale.text("player-guest")],
+    ));
+
+    let model = |name: &str| {
+        SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset(format!("models/{}", name))))
+    };
+
+    let mut table = commands.spawn((
+        model("tennis_table.glb"),
+        Transform::from_scale(court.table_scale()),
+        Table,
+        OnOnlineGameScreen,
+    ));
+    if host {
+        // 球网挂在球桌下面，模型加载后从 TT_Net 节点生成碰撞体
+        table.with_children(|parent| {
+            parent.spawn((
+                court.net_transform(),
+                Net,
+                OnOnlineGameScreen,
+                RigidBody::Fixed,
+                ActiveEvents::COLLISION_EVENTS,
+                court.net_collider(),
+                SceneCollider::new(SceneColliderShape::Cuboid).node("TT_Net"),
+                Ccd { enabled: true },
+            ));
+        });
+        table.insert((
+            RigidBody::Fixed,
+            ActiveEvents::COLLISION_EVENTS,
+            court.table_collider(),
+            SceneCollider::new(SceneColliderShape::Cuboid).node("TT_Board"),
+            Ccd { enabled: true },
+            Restitution {
+                coefficient: court.table_restitution,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+        ));
+    }
+
+    let host_racket_transform = Transform::from_translation(court.racket_position())
+        .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.0, -PI / 2.0, 0.0));
+    let client_racket_transform = mirror(host_racket_transform);
+    for (i, transform) in [host_racket_transform, client_racket_transform].into_iter().enumerate() {
+        let mut racket = commands.spawn((model("pong-racket.glb"), transform, OnOnlineGameScreen));
+        // 本机玩家的球拍由输入源驱动，客户端一侧需要镜像
+        match (i, host) {
+            (0, true) => racket.insert((HostRacket, PlayerRacket::new(0))),
+            (0, false) => racket.insert(HostRacket),
+            (_, true) => racket.insert(ClientRacket),
+            (_, false) => racket.insert((
+                ClientRacket,
+                PlayerRacket::mirrored(0),
+                PredictedPose(PoseData::from(&transform)),
+            )),
+        };
+        if host {
+            racket.insert((
+                Racket,
+                RigidBody::KinematicVelocityBased,
+                Velocity::zero(),
+                KinematicTarget::default(),
+                RacketMotion::default(),
+                ActiveEvents::COLLISION_EVENTS,
+                court.racket_collider(),
+                SceneCollider::new(SceneColliderShape::Cuboid),
+                Ccd { enabled: true },
+                Restitution {
+                    coefficient: 0.,
+                    combine_rule: CoefficientCombineRule::Max,
+                },
+            ));
+        }
+    }
+
+    let mut ball = commands.spawn((
+        model("ball.glb"),
+        Transform::from_translation(court.serve_position()).with_scale(Vec3::splat(court.ball_scale())),
+        Ball,
+        OnOnlineGameScreen,
+    ));
+    if host {
+        ball.insert((
+            RigidBody::Dynamic,
+            Velocity::zero(),
+            GravityScale(0.0),
+            ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS,
+            court.ball_collider(),
+            court.ball_mass(),
+            SceneCollider::new(SceneColliderShape::Ball),
+            Ccd { enabled: true },
+            Restitution {
+                coefficient: 1.0,
+                combine_rule: CoefficientCombineRule::Average,
+            },
+            Friction {
+                coefficient: 0.6,
+                combine_rule: CoefficientCombineRule::Average,
+            },
+            Damping {
+                linear_damping: 0.3,
+                angular_damping: 0.1,
+            },
+        ));
+    }
+
+    // light
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 3.0, 0.0),
+        OnOnlineGameScreen,
+    ));
+
+    // 各自从自己一侧看向球桌
+    let side = if host { 1.0 } else { -1.0 };
+    commands.spawn((
+        Camera3d { ..default() },
+        Camera {
+            order: 3,
+            ..default()
+        },
+        court.player_camera(side),
+        CameraRig { side, player: 0 },
+        ViewportSlot::full(),
+        OnOnlineGameScreen,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        OnlineStatusText,
+        OnOnlineGameScreen,
+    ));
+    commands.spawn((
+        theme.text(String::new(), TextSize::Small),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        ServerStatusText,
+        OnOnlineGameScreen,
+    ));
+    commands.spawn((
+        corner_button(&theme, locale.text("hud-exit")),
+        ButtonAction::Esc,
+        OnOnlineGameScreen,
+    ));
+}
+
+// ---------- 主机 ----------
+
+fn host_receive_system(link: Res<OnlineLink>, mut session: ResMut<OnlineSession>) {
+    for message in link.incoming.try_iter() {
+        match message {
+            NetMessage::Hello { protocol } => {
+                if protocol != ONLINE_PROTOCOL_VERSION {
+                    error!("❌ 客户端协议版本不一致: {}", protocol);
+                    continue;
+                }
+                if !session.connected {
+                    info!("🤝 对手已加入");
+                }
+                session.connected = true;
+                session.left = false;
+                link.send(NetMessage::Welcome {
+                    protocol: ONLINE_PROTOCOL_VERSION,
+                });
+            }
+            NetMessage::Input { seq, pose } => {
+                // UDP 可能乱序，只接受更新的输入
+                if seq > session.last_input_seq {
+                    session.last_input_seq = seq;
+                    session.client_pose = Some(pose);
+                }
+            }
+            NetMessage::Bye => {
+                session.connected = false;
+                session.left = true;
+                session.last_input_seq = 0;
+                session.client_pose = None;
+            }
+            NetMessage::Welcome { .. } | NetMessage::Snapshot { .. } => {}
+        }
+    }
+}
+
+/// 客户端上报的球拍位姿，限制在客户端自己的半场内
+fn apply_client_racket_input(
+    session: Res<OnlineSession>,
+    mut racket_query: Query<&mut KinematicTarget, With<ClientRacket>>,
+) {
+    let Some(pose) = session.client_pose else {
+        return;
+    };
+    let Ok(mut target) = racket_query.get_single_mut() else {
+        return;
+    };
+    let mut transform = Transform::default();
+    pose.apply_to(&mut transform);
+    transform.translation.x = transform.translation.x.min(0.0);
+    target.0 = Some(transform);
+}
+
+/// 快照中同步的球和两支球拍
+#[derive(SystemParam)]
+struct SnapshotBodies<'w, 's> {
+    ball: Query<'w, 's, (&'static Transform, &'static Velocity), With<Ball>>,
+    host_racket: Query<'w, 's, &'static Transform, With<HostRacket>>,
+    client_racket: Query<'w, 's, &'static Transform, With<ClientRacket>>,
+}
+
+fn broadcast_snapshot_system(
+    time: Res<Time>,
+    link: Res<OnlineLink>,
+    launch_state: Res<LaunchState>,
+    score: Res<MatchScore>,
+    mut session: ResMut<OnlineSession>,
+    bodies: SnapshotBodies,
+) {
+    if !session.connected || !session.snapshot_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let (Ok((ball, velocity)), Ok(host_racket), Ok(client_racket)) = (
+        bodies.ball.get_single(),
+        bodies.host_racket.get_single(),
+        bodies.client_racket.get_single(),
+    ) else {
+        return;
+    };
+    session.tick = session.tick.wrapping_add(1);
+    link.send(NetMessage::Snapshot {
+        tick: session.tick,
+        time: time.elapsed_secs(),
+        ack_seq: session.last_input_seq,
+        ball: BallState {
+            pose: PoseData::from(ball),
+            linvel: velocity.linvel.to_array(),
+        },
+        host_racket: PoseData::from(host_racket),
+        client_racket: PoseData::from(client_racket),
+        launched: launch_state.launched,
+        score: Box::new(score.clone()),
+    });
+}
+
+// ---------- 客户端 ----------
+
+fn client_handshake_system(
+    time: Res<Time>,
+    link: Res<OnlineLink>,
+    mut session: ResMut<OnlineSession>,
+) {
+    if session.connected || !session.hello_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    link.send(NetMessage::Hello {
+        protocol: ONLINE_PROTOCOL_VERSION,
+    });
+}
+
+fn client_receive_system(
+    time: Res<Time>,
+    link: Res<OnlineLink>,
+    mut session: ResMut<OnlineSession>,
+    mut buffer: ResMut<SnapshotBuffer>,
+    mut prediction: ResMut<PredictionState>,
+    mut launch_state: ResMut<LaunchState>,
+    mut score: ResMut<MatchScore>,
+) {
+    for message in link.incoming.try_iter() {
+        match message {
+            NetMessage::Welcome { protocol } => {
+                if protocol != ONLINE_PROTOCOL_VERSION {
+                    error!("❌ 主机协议版本不一致: {}", protocol);
+                    continue;
+                }
+                info!("🤝 已连接到主机");
+                session.connected = true;
+                session.left = false;
+            }
+            NetMessage::Snapshot {
+                tick,
+                time: host_time,
+                ack_seq,
+                ball,
+                host_racket,
+                client_racket,
+                launched,
+                score: host_score,
+            } => {
+                let snapshot = TimedSnapshot {
+                    time: host_time,
+                    ball,
+                    host_racket,
+                };
+                if buffer.push(tick, time.elapsed_secs(), snapshot) {
+                    prediction.reconcile(ack_seq, &client_racket);
+                    launch_state.launched = launched;
+                    *score = *host_score;
+                }
+            }
+            NetMessage::Bye => {
+                session.connected = false;
+                session.left = true;
+            }
+            NetMessage::Hello { .. } | NetMessage::Input { .. } => {}
+        }
+    }
+}
+
+/// 本地输入立即作用到自己的球拍上，同时带序号发给主机
+fn predict_local_racket(
+    time: Res<Time>,
+    link: Res<OnlineLink>,
+    session: Res<OnlineSession>,
+    mut prediction: ResMut<PredictionState>,
+    mut racket_query: Query<
+        (&mut Transform, &mut PredictedPose, &mut RacketTarget),
+        With<ClientRacket>,
+    >,
+) {
+    let Ok((mut transform, mut predicted, mut target)) = racket_query.get_single_mut() else {
+        return;
+    };
+    // 输入源已经按镜像后的球位置算好了客户端一侧的位姿
+    if let Some(pose) = target.0.take() {
+        predicted.0 = PoseData::from(&pose);
+        if session.connected {
+            let seq = prediction.record(predicted.0);
+            link.send(NetMessage::Input {
+                seq,
+                pose: predicted.0,
+            });
+        }
+    }
+
+    predicted.0.apply_to(&mut transform);
+    transform.translation += prediction.correction(time.delta_secs());
+}
+
+/// 主机的球拍和球在快照之间插值显示
+fn interpolate_remote_system(
+    time: Res<Time>,
+    buffer: Res<SnapshotBuffer>,
+    mut ball_query: Query<&mut Transform, (With<Ball>, Without<HostRacket>)>,
+    mut racket_query: Query<&mut Transform, (With<HostRacket>, Without<Ball>)>,
+) {
+    let Some(snapshot) = buffer.sample(time.elapsed_secs()) else {
+        return;
+    };
+    for mut transform in ball_query.iter_mut() {
+        snapshot.ball.pose.apply_to(&mut transform);
+    }
+    for mut transform in racket_query.iter_mut() {
+        snapshot.host_racket.apply_to(&mut transform);
+    }
+}
+
+fn update_online_status_text(
+    role: Res<OnlineRole>,
+    link: Res<OnlineLink>,
+    session: Res<OnlineSession>,
+    prediction: Res<PredictionState>,
+    locale: Res<Locale>,
+    mut text: Single<&mut Text, With<OnlineStatusText>>,
+) {
+    let content = match (link.error(), role.as_ref()) {
+        (Some(error), _) => locale.format("online-error", &[("error", &locale.message(&error))]),
+        (None, OnlineRole::Host { port }) if !session.connected => {
+            let waiting = if session.left { "online-opponent-left" } else { "online-waiting" };
+            locale.format(waiting, &[("port", port)])
+        }
+        (None, OnlineRole::Host { .. }) => locale.text("online-hosting"),
+        (None, OnlineRole::Client { host }) if !session.connected => {
+            let waiting = if session.left { "online-host-left" } else { "online-connecting" };
+            locale.format(waiting, &[("host", host)])
+        }
+        (None, OnlineRole::Client { host }) => locale.format(
+            "online-connected",
+            &[("host", host), ("pending", &prediction.pending_inputs())],
+        ),
+    };
+    if text.0 != content {
+        text.0 = content;
+    }
+}
@@ -0,0 +1,162 @@
+use std::env;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+use crate::game::online::protocol::{DEFAULT_ONLINE_PORT, NetMessage};
+use crate::game::utils::WsRuntime;
+use crate::locale::LocaleMessage;
+
+/// UDP 包的最大长度，快照远小于这个值
+const MAX_PACKET_SIZE: usize = 2048;
+
+#[derive(Resource, Clone, Debug)]
+pub enum OnlineRole {
+    /// 作为主机，负责球的物理模拟
+    Host { port: u16 },
+    /// 连接到主机
+    Client { host: SocketAddr },
+}
+
+impl OnlineRole {
+    /// 主机端口由 `PONG_ONLINE_PORT` 决定
+    pub fn host_from_env() -> Self {
+        let port = env::var("PONG_ONLINE_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(DEFAULT_ONLINE_PORT);
+        OnlineRole::Host { port }
+    }
+
+    /// 主机地址由 `PONG_PEER_ADDR` 决定，默认连接本机，方便在同一台电脑上开两个进程测试
+    pub fn client_from_env() -> Self {
+        let host = env::var("PONG_PEER_ADDR")
+            .ok()
+            .and_then(|addr| addr.parse().ok())
+            .unwrap_or_else(|| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), DEFAULT_ONLINE_PORT));
+        OnlineRole::Client { host }
+    }
+
+    pub fn is_host(&self) -> bool {
+        matches!(self, OnlineRole::Host { .. })
+    }
+}
+
+/// Bevy 与网络任务之间的通道，资源被移除后网络任务自动退出
+#[derive(Resource)]
+pub struct OnlineLink {
+    pub outgoing: UnboundedSender<NetMessage>,
+    pub incoming: Receiver<NetMessage>,
+    /// 网络任务出错时写入，显示在联机界面上
+    pub error: Arc<Mutex<Option<LocaleMessage>>>,
+}
+
+impl OnlineLink {
+    pub fn send(&self, message: NetMessage) {
+        let _ = self.outgoing.send(message);
+    }
+
+    pub fn error(&self) -> Option<LocaleMessage> {
+        self.error.lock().unwrap().clone()
+    }
+}
+
+pub fn spawn_link(rt: &WsRuntime, role: OnlineRole) -> OnlineLink {
+    let (outgoing, outgoing_rx) = unbounded_channel();
+    let (incoming_tx, incoming) = crossbeam_channel::unbounded();
+    let error = Arc::new(Mutex::new(None));
+    let task_error = error.clone();
+
+    rt.0.spawn(async move {
+        let (bind_addr, peer) = match role {
+            OnlineRole::Host { port } => (SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port), None),
+            OnlineRole::Client { host } => {
+                (SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0), Some(host))
+            }
+        };
+        let socket = match UdpSocket::bind(bind_addr).await {
+            Ok(socket) => socket,
+            Err(e) => {
+                error!("❌ 联机端口绑定失败 {}: {}", bind_addr, e);
+                *task_error.lock().unwrap() = Some(
+                    LocaleMessage::new("online-error-bind")
+                        .arg("addr", bind_addr)
+                        .arg("detail", e),
+                );
+                return;
+            }
+        };
+        info!("🌐 联机 UDP 已启动: {:?}", socket.local_addr());
+        run_link(socket, peer, outgoing_rx, incoming_tx).await;
+        info!("🌐 联机连接已关闭");
+    });
+
+    OnlineLink {
+        outgoing,
+        incoming,
+        error,
+    }
+}
+
+async fn run_link(
+    socket: UdpSocket,
+    mut peer: Option<SocketAddr>,
+    mut outgoing_rx: UnboundedReceiver<NetMessage>,
+    incoming_tx: Sender<NetMessage>,
+) {
+    // 主机不预先知道对端，客户端离开后可以接受新的客户端
+    let accepts_new_peer = peer.is_none();
+    let mut buf = [0u8; MAX_PACKET_SIZE];
+    loop {
+        tokio::select! {
+            received = socket.recv_from(&mut buf) => {
+                let (len, from) = match received {
+                    Ok(received) => received,
+                    Err(e) => {
+                        // Windows 上对端关闭时会收到 ConnectionReset，忽略即可
+                        warn!("⚠️ 联机接收出错: {}", e);
+                        continue;
+                    }
+                };
+                let message = match NetMessage::decode(&buf[..len]) {
+                    Some(message) => message,
+                    None => continue,
+                };
+                // 主机以第一个握手的地址作为对端
+                if peer.is_none() && matches!(message, NetMessage::Hello { .. }) {
+                    info!("🤝 客户端已连接: {}", from);
+                    peer = Some(from);
+                }
+                if Some(from) != peer {
+                    continue;
+                }
+                let leaving = matches!(message, NetMessage::Bye);
+                if incoming_tx.send(message).is_err() {
+                    break;
+                }
+                if leaving && accepts_new_peer {
+                    info!("👋 客户端已离开: {}", from);
+                    peer = None;
+                }
+            }
+            outgoing = outgoing_rx.recv() => {
+                match (outgoing, peer) {
+                    (Some(message), Some(peer)) => {
+                        let _ = socket.send_to(&message.encode(), peer).await;
+                    }
+                    (Some(_), None) => {}
+                    (None, peer) => {
+                        if let Some(peer) = peer {
+                            let _ = socket.send_to(&NetMessage::Bye.encode(), peer).await;
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
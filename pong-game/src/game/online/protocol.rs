@@ -0,0 +1,88 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::scoring::MatchScore;
+
+/// 联机协议版本，双方不一致时拒绝连接
+pub const ONLINE_PROTOCOL_VERSION: u32 = 2;
+/// 主机默认监听的 UDP 端口
+pub const DEFAULT_ONLINE_PORT: u16 = 7000;
+
+/// 可序列化的位姿，Transform 本身不支持 serde
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PoseData {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+}
+
+impl PoseData {
+    pub fn translation(&self) -> Vec3 {
+        Vec3::from_array(self.translation)
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_array(self.rotation)
+    }
+
+    pub fn apply_to(&self, transform: &mut Transform) {
+        transform.translation = self.translation();
+        transform.rotation = self.rotation();
+    }
+
+    pub fn lerp(&self, other: &PoseData, t: f32) -> PoseData {
+        PoseData {
+            translation: self.translation().lerp(other.translation(), t).to_array(),
+            rotation: self.rotation().slerp(other.rotation(), t).to_array(),
+        }
+    }
+}
+
+impl From<&Transform> for PoseData {
+    fn from(transform: &Transform) -> Self {
+        PoseData {
+            translation: transform.translation.to_array(),
+            rotation: transform.rotation.to_array(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct BallState {
+    pub pose: PoseData,
+    pub linvel: [f32; 3],
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetMessage {
+    /// 客户端 -> 主机，握手
+    Hello { protocol: u32 },
+    /// 主机 -> 客户端，握手成功
+    Welcome { protocol: u32 },
+    /// 客户端 -> 主机，本地球拍的目标位姿（世界坐标）
+    Input { seq: u32, pose: PoseData },
+    /// 主机 -> 客户端，权威状态
+    Snapshot {
+        tick: u32,
+        time: f32,
+        ack_seq: u32,
+        ball: BallState,
+        host_racket: PoseData,
+        client_racket: PoseData,
+        launched: bool,
+        /// 比分由主机判定
+        score: Box<MatchScore>,
+    },
+    /// 任意一方离开
+    Bye,
+}
+
+impl NetMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<NetMessage> {
+        serde_json::from_slice(bytes).ok()
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::game::online::protocol::{BallState, PoseData};
+
+/// 远端物体的渲染时间比最新快照晚这么多秒，保证总有两帧可以插值
+pub const INTERPOLATION_DELAY: f32 = 0.1;
+/// 快照缓冲上限，约 1 秒
+const MAX_SNAPSHOTS: usize = 32;
+/// 本地预测与主机结果相差超过该距离时才修正
+const RECONCILE_THRESHOLD: f32 = 0.02;
+/// 修正量每秒衰减的比例，避免球拍瞬移
+const CORRECTION_DECAY: f32 = 10.0;
+/// 未确认输入的上限，超出时丢弃最旧的
+const MAX_PENDING_INPUTS: usize = 128;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TimedSnapshot {
+    pub time: f32,
+    pub ball: BallState,
+    pub host_racket: PoseData,
+}
+
+/// 客户端收到的主机快照，按主机时间排序，渲染时在两帧之间插值
+#[derive(Resource, Default)]
+pub struct SnapshotBuffer {
+    snapshots: VecDeque<TimedSnapshot>,
+    /// 主机时间 - 本地时间，取观测到的最小值以排除网络抖动
+    clock_offset: Option<f32>,
+    last_tick: Option<u32>,
+}
+
+impl SnapshotBuffer {
+    /// 收到快照时调用，乱序或重复的快照会被丢弃
+    pub fn push(&mut self, tick: u32, local_time: f32, snapshot: TimedSnapshot) -> bool {
+        if self.last_tick.is_some_and(|last| tick <= last) {
+            return false;
+        }
+        self.last_tick = Some(tick);
+
+        let offset = snapshot.time - local_time;
+        self.clock_offset = Some(match self.clock_offset {
+            Some(current) => current.min(offset),
+            None => offset,
+        });
+
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > MAX_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+        true
+    }
+
+    /// 取本地时间对应的插值结果，缓冲不足时返回最新的一帧
+    pub fn sample(&self, local_time: f32) -> Option<TimedSnapshot> {
+        let offset = self.clock_offset?;
+        let render_time = local_time + offset - INTERPOLATION_DELAY;
+
+        let newer = self
+            .snapshots
+            .iter()
+            .position(|snapshot| snapshot.time >= render_time);
+        match newer {
+            Some(0) => self.snapshots.front().copied(),
+            Some(index) => {
+                let from = &self.snapshots[index - 1];
+                let to = &self.snapshots[index];
+                let span = (to.time - from.time).max(f32::EPSILON);
+                let t = ((render_time - from.time) / span).clamp(0.0, 1.0);
+                Some(TimedSnapshot {
+                    time: render_time,
+                    ball: BallState {
+                        pose: from.ball.pose.lerp(&to.ball.pose, t),
+                        linvel: Vec3::from_array(from.ball.linvel)
+                            .lerp(Vec3::from_array(to.ball.linvel), t)
+                            .to_array(),
+                    },
+                    host_racket: from.host_racket.lerp(&to.host_racket, t),
+                })
+            }
+            None => self.snapshots.back().copied(),
+        }
+    }
+
+    pub fn latest(&self) -> Option<&TimedSnapshot> {
+        self.snapshots.back()
+    }
+}
+
+/// 客户端本地球拍的预测状态。
+/// 本地输入立即生效并带序号发给主机，主机在快照里回传已处理的序号和实际位置，
+/// 与当时的预测不一致时把差值记为修正量，在几帧内平滑抹掉。
+#[derive(Resource, Default)]
+pub struct PredictionState {
+    next_seq: u32,
+    pending: VecDeque<(u32, PoseData)>,
+    correction: Vec3,
+}
+
+impl PredictionState {
+    /// 记录一次本地输入，返回要发给主机的序号
+    pub fn record(&mut self, pose: PoseData) -> u32 {
+        self.next_seq = self.next_seq.wrapping_add(1);
+        self.pending.push_back((self.next_seq, pose));
+        while self.pending.len() > MAX_PENDING_INPUTS {
+            self.pending.pop_front();
+        }
+        self.next_seq
+    }
+
+    /// 用主机确认的位姿校正预测
+    pub fn reconcile(&mut self, ack_seq: u32, server_pose: &PoseData) {
+        let predicted = loop {
+            match self.pending.front() {
+                Some((seq, _)) if *seq < ack_seq => {
+                    self.pending.pop_front();
+                }
+                Some((seq, pose)) if *seq == ack_seq => break Some(*pose),
+                _ => break None,
+            }
+        };
+        let Some(predicted) = predicted else {
+            return;
+        };
+
+        let error = server_pose.translation() - predicted.translation();
+        if error.length() > RECONCILE_THRESHOLD {
+            self.correction = error;
+        }
+    }
+
+    /// 当前帧需要叠加到本地球拍上的修正量
+    pub fn correction(&mut self, delta: f32) -> Vec3 {
+        let correction = self.correction;
+        self.correction *= (1.0 - CORRECTION_DECAY * delta).max(0.0);
+        correction
+    }
+
+    pub fn pending_inputs(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pose(x: f32) -> PoseData {
+        PoseData::from(&Transform::from_xyz(x, 0.0, 0.0))
+    }
+
+    fn snapshot(time: f32, x: f32) -> TimedSnapshot {
+        TimedSnapshot {
+            time,
+            ball: BallState {
+                pose: pose(x),
+                linvel: [x, 0.0, 0.0],
+            },
+            host_racket: pose(-x),
+        }
+    }
+
+    fn ball_x(snapshot: Option<TimedSnapshot>) -> f32 {
+        snapshot.unwrap().ball.pose.translation[0]
+    }
+
+    #[test]
+    fn snapshots_out_of_order_are_dropped() {
+        let mut buffer = SnapshotBuffer::default();
+        assert!(buffer.push(2, 0.0, snapshot(10.0, 0.0)));
+        assert!(!buffer.push(2, 0.1, snapshot(10.1, 1.0)));
+        assert!(!buffer.push(1, 0.1, snapshot(9.9, 1.0)));
+        assert!(buffer.push(3, 0.1, snapshot(10.1, 1.0)));
+        assert_eq!(buffer.latest().unwrap().time, 10.1);
+    }
+
+    #[test]
+    fn sample_interpolates_behind_latest_snapshot() {
+        let mut buffer = SnapshotBuffer::default();
+        assert!(buffer.sample(0.0).is_none());
+        buffer.push(1, 0.0, snapshot(10.0, 0.0));
+        buffer.push(2, 0.1, snapshot(10.1, 1.0));
+
+        // 渲染时间为 10.05，正好在两帧中间
+        let sampled = buffer.sample(0.15).unwrap();
+        assert!((sampled.ball.pose.translation[0] - 0.5).abs() < 1e-3);
+        assert!((sampled.ball.linvel[0] - 0.5).abs() < 1e-3);
+        assert!((sampled.host_racket.translation[0] + 0.5).abs() < 1e-3);
+
+        assert_eq!(ball_x(buffer.sample(0.0)), 0.0, "早于缓冲时取最早一帧");
+        assert_eq!(ball_x(buffer.sample(1.0)), 1.0, "缓冲不足时取最新一帧");
+    }
+
+    #[test]
+    fn clock_offset_follows_slowest_packet() {
+        let mut buffer = SnapshotBuffer::default();
+        buffer.push(1, 0.0, snapshot(10.0, 0.0));
+        // 这一帧在路上多走了 0.3 秒，之后都按这个延迟渲染，保证插值的下一帧已经到了
+        buffer.push(2, 0.4, snapshot(10.1, 1.0));
+        buffer.push(3, 0.5, snapshot(10.2, 2.0));
+        assert!((ball_x(buffer.sample(0.45)) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn snapshot_buffer_keeps_latest_second() {
+        let mut buffer = SnapshotBuffer::default();
+        for tick in 0..MAX_SNAPSHOTS as u32 * 2 {
+            let time = tick as f32 * 0.05;
+            buffer.push(tick, time, snapshot(time, tick as f32));
+        }
+        assert_eq!(buffer.snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(ball_x(buffer.sample(-10.0)), MAX_SNAPSHOTS as f32);
+    }
+
+    #[test]
+    fn reconcile_drops_acknowledged_inputs() {
+        let mut prediction = PredictionState::default();
+        let seqs = (0..4)
+            .map(|i| prediction.record(pose(i as f32)))
+            .collect::<Vec<_>>();
+        assert_eq!(seqs, [1, 2, 3, 4]);
+
+        prediction.reconcile(3, &pose(2.0));
+        assert_eq!(prediction.pending_inputs(), 2);
+        assert_eq!(prediction.correction(0.0), Vec3::ZERO);
+
+        // 没有记录过的序号不做修正
+        prediction.reconcile(10, &pose(5.0));
+        assert_eq!(prediction.correction(0.0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn reconcile_corrects_only_large_errors_and_decays() {
+        let mut prediction = PredictionState::default();
+        let first = prediction.record(pose(0.0));
+        let second = prediction.record(pose(1.0));
+
+        prediction.reconcile(first, &pose(RECONCILE_THRESHOLD / 2.0));
+        assert_eq!(prediction.correction(0.0), Vec3::ZERO);
+
+        prediction.reconcile(second, &pose(1.5));
+        let correction = prediction.correction(0.05);
+        assert!((correction.x - 0.5).abs() < 1e-4);
+        assert!(prediction.correction(0.05).x < correction.x);
+        prediction.correction(1.0);
+        assert_eq!(prediction.correction(0.0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn pending_inputs_are_capped() {
+        let mut prediction = PredictionState::default();
+        for _ in 0..MAX_PENDING_INPUTS + 10 {
+            prediction.record(pose(0.0));
+        }
+        assert_eq!(prediction.pending_inputs(), MAX_PENDING_INPUTS);
+    }
+}
@@ -0,0 +1,259 @@
+use bevy::input::gamepad::{Gamepad, GamepadButton};
+use bevy::prelude::*;
+use bevy_rapier3d::plugin::RapierConfiguration;
+
+use crate::GameState;
+use crate::components::Popup;
+use crate::components::button::{ButtonActions, ButtonKind, button};
+use crate::components::dropdown::{Dropdown, dropdown};
+use crate::components::theme::{PAUSE_LAYER, TextSize, UiTheme, option_row, overlay_column, row_label};
+use crate::despawn_screen;
+use crate::game::LocalVersus;
+use crate::game::input::{ControlScheme, PlayerControls, PlayerRacket, RacketDriver, RacketInputSet};
+use crate::game::scoring::{MatchConfig, RestartMatch, ScoringSet};
+use crate::locale::Locale;
+use crate::menu::navigation::MenuInput;
+
+/// 比赛中的暂停状态，只在各模式的 Running 状态下存在，离开比赛时自动移除。
+/// 暂停时物理和本机输入冻结，恢复时不会重新执行 setup。
+/// 联机时暂停只影响本机：主机暂停会冻结双方的球，客户端暂停只冻结自己的输入。
+#[derive(SubStates, Clone, Copy, Default, Eq, PartialEq, Debug, Hash)]
+#[source(
+    GameState = GameState::GameRunning
+        | GameState::GamePracticeRunning
+        | GameState::GameOnlineRunning
+)]
+pub enum PauseState {
+    #[default]
+    Playing,
+    Paused,
+    /// 暂停菜单中的设置页
+    Settings,
+}
+
+#[derive(Component)]
+struct OnPauseMenuScreen;
+
+#[derive(Component)]
+struct OnPauseSettingsScreen;
+
+#[derive(Component)]
+enum PauseAction {
+    Resume,
+    Restart,
+    Settings,
+    QuitToMenu,
+    BackToPause,
+}
+
+/// 设置页中某名玩家的控制方式
+#[derive(Component, Clone, Copy)]
+struct PauseControlOption {
+    player: usize,
+}
+
+/// 不在暂停中（包括不在比赛中）
+pub fn not_paused(state: Option<Res<State<PauseState>>>) -> bool {
+    state.is_none_or(|state| *state.get() == PauseState::Playing)
+}
+
+pub fn pause_plugin(app: &mut App) {
+    app.add_sub_state::<PauseState>()
+        .configure_sets(Update, (RacketInputSet, ScoringSet).run_if(not_paused))
+        .add_systems(
+            Update,
+            toggle_pause.run_if(resource_exists::<State<PauseState>>),
+        )
+        .add_systems(OnExit(PauseState::Playing), set_physics_active::<false>)
+        .add_systems(OnEnter(PauseState::Playing), set_physics_active::<true>)
+        // 暂停中直接回到菜单时恢复物理，下一场比赛不受影响
+        .add_systems(OnEnter(GameState::Menu), set_physics_active::<true>)
+        .add_systems(OnEnter(PauseState::Paused), pause_menu_setup)
+        .add_systems(OnExit(PauseState::Paused), despawn_screen::<OnPauseMenuScreen>)
+        .add_systems(OnEnter(PauseState::Settings), pause_settings_setup)
+        .add_systems(
+            OnExit(PauseState::Settings),
+            despawn_screen::<OnPauseSettingsScreen>,
+        )
+        .add_systems(
+            Update,
+            pause_action.run_if(not(in_state(PauseState::Playing))),
+        )
+        .add_systems(
+            Update,
+            pause_control_option.run_if(in_state(PauseState::Settings)),
+        );
+}
+
+/// Esc 键、手柄 Start 键或菜单导航的返回键（手机控制器的 `nav:back`）：
+/// 比赛中打开暂停菜单，暂停菜单中继续比赛，设置页中返回暂停菜单。有弹窗时返回键留给弹窗
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut menu_inputs: EventReader<MenuInput>,
+    popups: Query<(), With<Popup>>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    let back = menu_inputs.read().any(|input| *input == MenuInput::Back) && popups.is_empty();
+    let pressed = keys.just_pressed(KeyCode::Escape)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::Start));
+    if !pressed && !back {
+        return;
+    }
+    next_pause_state.set(match pause_state.get() {
+        PauseState::Playing => PauseState::Paused,
+        PauseState::Paused => PauseState::Playing,
+        PauseState::Settings => PauseState::Paused,
+    });
+}
+
+fn set_physics_active<const ACTIVE: bool>(mut configs: Query<&mut RapierConfiguration>) {
+    for mut config in configs.iter_mut() {
+        config.physics_pipeline_active = ACTIVE;
+    }
+}
+
+fn pause_menu_setup(
+    mut commands: Commands,
+    config: Option<Res<MatchConfig>>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    // 联机客户端的比分由主机决定，不能重新开始
+    let can_restart = config.is_none_or(|config| config.authoritative);
+
+    // 半透明遮罩覆盖整个画面，挡住比赛中的按钮
+    commands
+        .spawn((
+            overlay_column(20.0),
+            BackgroundColor(theme.overlay),
+            GlobalZIndex(PAUSE_LAYER),
+            OnPauseMenuScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                theme.text(locale.text("pause-title"), TextSize::Banner),
+                OnPauseMenuScreen,
+            ));
+            let mut buttons = vec![("pause-resume", PauseAction::Resume)];
+            if can_restart {
+                buttons.push(("pause-restart", PauseAction::Restart));
+            }
+            buttons.push(("pause-settings", PauseAction::Settings));
+            buttons.push(("pause-quit", PauseAction::QuitToMenu));
+            for (label, action) in buttons {
+                parent.spawn((
+                    button(&theme, locale.text(label), ButtonKind::Menu),
+                    action,
+                    OnPauseMenuScreen,
+                ));
+            }
+        });
+}
+
+fn pause_settings_setup(
+    mut commands: Commands,
+    controls: Res<PlayerControls>,
+    versus: Option<Res<LocalVersus>>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let schemes = ControlScheme::ALL
+        .iter()
+        .map(|scheme| locale.text(scheme.locale_key()))
+        .collect::<Vec<_>>();
+
+    commands
+        .spawn((
+            overlay_column(20.0),
+            BackgroundColor(theme.overlay),
+            GlobalZIndex(PAUSE_LAYER),
+            OnPauseSettingsScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                theme.text(locale.text("pause-settings"), TextSize::Title),
+                OnPauseSettingsScreen,
+            ));
+            for player in 0..controls.schemes.len() {
+                let (scheme, _) = controls.match_scheme(versus.as_deref(), player);
+                let selected = ControlScheme::ALL
+                    .iter()
+                    .position(|option| *option == scheme)
+                    .unwrap_or(0);
+                parent
+                    .spawn((option_row(), OnPauseSettingsScreen))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            row_label(&theme, locale.format("controls-player", &[("number", &(player + 1))])),
+                            OnPauseSettingsScreen,
+                        ));
+                        parent.spawn((
+                            dropdown(&theme, schemes.clone(), selected),
+                            PauseControlOption { player },
+                            OnPauseSettingsScreen,
+                        ));
+                    });
+            }
+            parent.spawn((
+                button(&theme, locale.text("menu-back"), ButtonKind::Menu),
+                PauseAction::BackToPause,
+                OnPauseSettingsScreen,
+            ));
+        });
+}
+
+fn pause_action(
+    interaction_query: ButtonActions<PauseAction>,
+    mut restarts: EventWriter<RestartMatch>,
+    mut pause_state: ResMut<NextState<PauseState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            PauseAction::Resume => pause_state.set(PauseState::Playing),
+            PauseAction::Restart => {
+                restarts.send(RestartMatch);
+                pause_state.set(PauseState::Playing);
+            }
+            PauseAction::Settings => pause_state.set(PauseState::Settings),
+            PauseAction::QuitToMenu => game_state.set(GameState::Menu),
+            PauseAction::BackToPause => pause_state.set(PauseState::Paused),
+        }
+    }
+}
+
+/// 比赛中切换控制方式：更新选择并移除该玩家球拍的输入源，下一帧按新的方式重新添加。
+/// 对战电脑时改动玩家 2 会取消本场的电脑对手
+fn pause_control_option(
+    dropdowns: Query<(&Dropdown, &PauseControlOption), Changed<Dropdown>>,
+    rackets: Query<(Entity, &PlayerRacket), With<RacketDriver>>,
+    mut commands: Commands,
+    mut controls: ResMut<PlayerControls>,
+    mut versus: Option<ResMut<LocalVersus>>,
+) {
+    for (dropdown, option) in &dropdowns {
+        let scheme = ControlScheme::ALL[dropdown.selected];
+        if controls.match_scheme(versus.as_deref(), option.player).0 == scheme {
+            continue;
+        }
+        if option.player == 1
+            && let Some(versus) = versus.as_mut()
+        {
+            versus.ai_opponent = None;
+        }
+        controls.schemes[option.player] = scheme;
+        for (racket, player_racket) in rackets.iter() {
+            if player_racket.player == option.player {
+                commands.entity(racket).remove::<RacketDriver>();
+            }
+        }
+        info!("🎮 玩家 {} 改为 {} 控制", option.player + 1, scheme.label());
+    }
+}
@@ -4,22 +4,28 @@ use std::sync::{Arc, Mutex};
 use bevy::log::tracing_subscriber::fmt::time;
 use rand::Rng;
 
-use bevy::{prelude::*, render::camera};
+use bevy::prelude::*;
 use bevy_rapier3d::plugin::RapierPhysicsPlugin;
 use bevy_rapier3d::plugin::{RapierConfiguration, TimestepMode};
 use bevy_rapier3d::prelude::*;
 
-use crate::components::button::button_system;
+use crate::components::button::corner_button;
+use crate::components::theme::{TextSize, UiTheme};
+use crate::locale::Locale;
 use crate::{
     GameState,
     game::{
-        command_handler, controller_server,
+        camera::{CameraRig, viewport::ViewportSlot},
+        input::PlayerRacket,
+        pause::{PauseState, not_paused},
+        utils::racket_physics::{KinematicTarget, RacketMotion},
+        utils::court::Court,
+        utils::scene_collider::{SceneCollider, SceneColliderShape},
         utils::{
             Ball, BallTableCollisionCount, CameraComponent, CommandDataType, LaunchState,
-            LeftCamera, ModelComponent, MoveSpeedText, Racket, RacketCommandQueue,
-            RacketTransformCommand, RightCamera, Table, TrajectoryPreview,
+            LeftCamera, ModelComponent, MoveSpeedText, Net, Racket, RacketCommandQueue,
+            RacketTransformCommand, RightCamera, ServerStatusText, Table, TrajectoryPreview,
         },
-        ws_handler,
     },
 };
 
@@ -34,8 +40,6 @@ pub fn game_practice_plugin(app: &mut App) {
             OnEnter(GameState::GamePracticeIniting),
             (
                 setup,
-                ws_handler::start_websocket_server,
-                controller_server::start_controller_server,
                 setup_physics_config,
                 over_init,
             ),
@@ -43,12 +47,10 @@ pub fn game_practice_plugin(app: &mut App) {
         .add_systems(
             Update,
             (
-                command_handler::apply_racket_commands,
                 collision_event_system.in_set(PhysicsSet::SyncBackend),
                 contact_force_system.in_set(PhysicsSet::SyncBackend),
                 bounce_system.in_set(PhysicsSet::SyncBackend),
-                control_ball_system,
-                // move_racket,
+                control_ball_system.run_if(not_paused),
             )
                 .run_if(in_state(GameState::GamePracticeRunning)),
         )
@@ -58,7 +60,7 @@ pub fn game_practice_plugin(app: &mut App) {
         )
         .add_systems(
             Update,
-            (button_system, menu_action).run_if(not(in_state(GameState::Menu))),
+            menu_action.run_if(not(in_state(GameState::Menu))),
         );
 }
 
@@ -69,13 +71,13 @@ enum ButtonAction {
 
 fn menu_action(
     interaction_query: Query<(&Interaction, &ButtonAction), (Changed<Interaction>, With<Button>)>,
-    mut game_state: ResMut<NextState<GameState>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
                 ButtonAction::Esc => {
-                    game_state.set(GameState::Menu);
+                    pause_state.set(PauseState::Paused);
                 }
             }
         }
@@ -105,16 +107,19 @@ fn over_init(mut game_state: ResMut<NextState<GameState>>) {
     game_state.set(GameState::GamePracticeRunning);
 }
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<&Window>) {
-    let window = windows.single();
-    let width = window.width();
-    let height = window.height();
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    court: Res<Court>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
 
     let model_names = vec!["tennis_table.glb", "pong-racket.glb", "ball.glb"];
     let pos = vec![
-        Vec3::new(0.0, 0.0, 0.0),
-        Vec3::new(1.0, 1.0, 0.0),
-        Vec3::new(0.95, 1.05, 0.0),
+        Vec3::ZERO,
+        court.racket_position(),
+        court.serve_position(),
     ];
     let rotation = vec![
         Quat::from_euler(EulerRot::XYZ, 0.0, 0.0, 0.0),
@@ -131,16 +136,18 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         let model_path = format!("models/{}", model_name);
         let gltf_handle = asset_server.load(GltfAssetLabel::Scene(0).from_asset(model_path));
 
-        let scale_num = match *model_name {
-            "ball.glb" => 2.0,
-            _ => 1.0,
+        // 模型按标准尺寸缩放
+        let scale = match *model_name {
+            "tennis_table.glb" => court.table_scale(),
+            "ball.glb" => Vec3::splat(court.ball_scale()),
+            _ => Vec3::ONE,
         };
 
         let mut entity = commands.spawn((
             SceneRoot(gltf_handle),
             Transform::from_xyz(pos[i].x, pos[i].y, pos[i].z)
                 .with_rotation(rotation[i])
-                .with_scale(Vec3::splat(scale_num)),
+                .with_scale(scale),
             OnPracticeGameScreen,
         ));
 
@@ -151,20 +158,43 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
                     Table,
                     RigidBody::Fixed,
                     ActiveEvents::COLLISION_EVENTS,
-                    Collider::cuboid(1.3, 0.74, 0.8),
+                    court.table_collider(),
+                    SceneCollider::new(SceneColliderShape::Cuboid).node("TT_Board"),
                     Ccd { enabled: true },
                     Restitution {
-                        coefficient: 0.9, // 让桌子也有高弹性
+                        coefficient: court.table_restitution,
                         combine_rule: CoefficientCombineRule::Max,
                     },
                 ));
+                // 球网挂在球桌下面，模型加载后从 TT_Net 节点生成碰撞体
+                entity.with_children(|parent| {
+                    parent.spawn((
+                        court.net_transform(),
+                        Net,
+                        OnPracticeGameScreen,
+                        RigidBody::Fixed,
+                        ActiveEvents::COLLISION_EVENTS,
+                        court.net_collider(),
+                        SceneCollider::new(SceneColliderShape::Cuboid).node("TT_Net"),
+                        Ccd { enabled: true },
+                        Restitution {
+                            coefficient: court.table_restitution,
+                            combine_rule: CoefficientCombineRule::Max,
+                        },
+                    ));
+                });
             }
             Some(ModelComponent::Rkt) => {
                 entity.insert((
                     Racket,
-                    RigidBody::KinematicPositionBased,
+                    PlayerRacket::new(0),
+                    RigidBody::KinematicVelocityBased,
+                    Velocity::zero(),
+                    KinematicTarget::default(),
+                    RacketMotion::default(),
                     ActiveEvents::COLLISION_EVENTS,
-                    Collider::cuboid(0.07, 0.01, 0.12),
+                    court.racket_collider(),
+                    SceneCollider::new(SceneColliderShape::Cuboid),
                     Ccd { enabled: true },
                     Restitution {
                         coefficient: 0.,
@@ -179,7 +209,9 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
                     Velocity::zero(),
                     GravityScale(0.0),
                     ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS,
-                    Collider::ball(0.01),
+                    court.ball_collider(),
+                    court.ball_mass(),
+                    SceneCollider::new(SceneColliderShape::Ball),
                     Ccd { enabled: true },
                     Restitution {
                         coefficient: 1.0, // 从 0.8 降到 0.4
@@ -208,40 +240,25 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         Transform::from_xyz(0.0, 3.0, 0.0),
         OnPracticeGameScreen,
     ));
-    // let positions = [UVec2::new(0, 0), UVec2::new(width as u32 / 2, 0)];
-    let size = UVec2::new(width as u32, height as u32);
     commands.spawn((
         Camera3d { ..default() },
         Camera {
-            viewport: Some(camera::Viewport {
-                physical_position: UVec2::new(0, 0),
-                physical_size: size,
-                ..default()
-            }),
             // order: i as isize,
             order: 3,
             ..default()
         },
-        Transform::from_xyz(2.5, 1.50, 0.0).looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+        court.player_camera(1.0),
+        CameraRig {
+            side: 1.0,
+            player: 0,
+        },
+        ViewportSlot::full(),
         OnPracticeGameScreen,
         LeftCamera,
     ));
 
     commands.spawn((
-        Transform::from_xyz(0., 0.74, 0.),
-        OnPracticeGameScreen,
-        RigidBody::Fixed,
-        ActiveEvents::COLLISION_EVENTS,
-        Collider::cuboid(0.02, 0.1, 0.8),
-        Ccd { enabled: true },
-        Restitution {
-            coefficient: 0.9, // 让桌子也有高弹性
-            combine_rule: CoefficientCombineRule::Max,
-        },
-    ));
-
-    commands.spawn((
-        Text::new("move speed:"),
+        Text::new(locale.text("hud-move-speed")),
         Node {
             position_type: PositionType::Absolute,
             top: Val::Px(10.0),
@@ -261,28 +278,20 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>, windows: Query<
         MoveSpeedText,
         OnPracticeGameScreen,
     ));
+    commands.spawn((
+        theme.text(String::new(), TextSize::Small),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        ServerStatusText,
+        OnPracticeGameScreen,
+    ));
 
-    let button_node = Node {
-        // width: Val::Px(100.0),
-        // height: Val::Px(100.0),
-        position_type: PositionType::Absolute,
-        top: Val::Px(10.0),
-        right: Val::Px(10.0),
-        margin: UiRect::all(Val::Px(10.0)),
-        align_items: AlignItems::Center,
-        justify_content: JustifyContent::Center,
-
-        ..default()
-    };
-    let button_text = TextFont {
-        font_size: 32.0,
-        ..default()
-    };
     commands.spawn((
-        Text::new("Esc"),
-        button_text.clone(),
-        Button,
-        button_node.clone(),
+        corner_button(&theme, locale.text("hud-exit")),
         ButtonAction::Esc,
         OnPracticeGameScreen,
     ));
@@ -304,6 +313,7 @@ fn control_ball_system(
     mut counter: ResMut<BallTableCollisionCount>,
     mut gizmos: Gizmos,
     time: Res<Time>,
+    court: Res<Court>,
 ) {
     if preview.pending_reset {
         preview.timer.tick(time.delta());
@@ -352,19 +362,13 @@ fn control_ball_system(
             if gs.unwrap().0 == 0.0 {
                 commands.entity(entity).insert(GravityScale(1.0));
             }
-            if transform.translation.x > 1.0
-                || transform.translation.x < -1.0
-                || transform.translation.y > 2.0
-                || transform.translation.y < 0.5
-                || transform.translation.z > 0.7
-                || transform.translation.z < -0.7
-                || counter.count > 2
-            {
+            if court.left_table_area(transform.translation) || counter.count > 2 {
                 let mut rng = rand::rng();
 
+                // 从对面半场、桌面上方发下一球
                 let translation = Vec3::new(
                     rng.random_range(-0.3..=-0.1),
-                    rng.random_range(1.0..=1.2),
+                    court.table_height + rng.random_range(0.24..=0.44),
                     rng.random_range(-0.4..=0.4),
                 );
                 let linvel = Vec3::new(
@@ -390,9 +394,10 @@ fn bounce_system(
     mut commands: Commands,
     query: Query<(Entity, &Transform), With<Ball>>,
     mut velocities: Query<&mut Velocity, With<Ball>>,
+    court: Res<Court>,
 ) {
     for (entity, tf) in query.iter() {
-        if tf.translation.x < -1.5 {
+        if tf.translation.x < -court.back_wall() {
             // 直接改 Velocity 组件
             if let Ok(mut vel) = velocities.get_mut(entity) {
                 vel.linvel.x = vel.linvel.x.abs() * 0.8;
@@ -422,7 +427,7 @@ fn collision_event_system(
             if hit_racket && !launch_state.launched {
                 launch_state.launched = true;
                 counter.count = 0;
-                // println!("Ball <-> Racket 碰撞触发！");
+                debug!("Ball <-> Racket 碰撞触发！");
             }
 
             // 球-桌子碰撞计数
@@ -430,106 +435,29 @@ fn collision_event_system(
                 || (e2_is_ball && table_q.get(*e1).is_ok());
             if hit_table {
                 counter.count += 1;
-                // println!("Ball <-> Table 碰撞，累计：{}", counter.count);
+                debug!("Ball <-> Table 碰撞，累计：{}", counter.count);
             }
         }
     }
 }
 
+/// 击球由 racket_physics 统一结算，这里只记录球与桌子的接触
 fn contact_force_system(
     mut force_events: EventReader<ContactForceEvent>,
-    ball_q: Query<&Ball>,
-    racket_q: Query<&Racket>,
-    table_q: Query<&Transform, (With<Table>, Without<Ball>)>,
-    launch_state: ResMut<LaunchState>,
-    mut params: ParamSet<(
-        Query<&mut Velocity, With<Ball>>,
-        Query<&mut Transform, With<Ball>>,
-    )>,
-    mut text: Single<&mut Text, With<MoveSpeedText>>,
+    ball_q: Query<&Transform, With<Ball>>,
+    table_q: Query<(), With<Table>>,
 ) {
     for event in force_events.read() {
-        let e1 = event.collider1;
-        let e2 = event.collider2;
-
-        let e1_is_ball = ball_q.get(e1).is_ok();
-        let e2_is_ball = ball_q.get(e2).is_ok();
-        let e1_is_racket = racket_q.get(e1).is_ok();
-        let e2_is_racket = racket_q.get(e2).is_ok();
-        let e1_is_table = table_q.get(e1).is_ok();
-        let e2_is_table = table_q.get(e2).is_ok();
-
-        let (ball_entity, hit) = match (e1_is_ball, e2_is_racket, e2_is_ball, e1_is_racket) {
-            (true, true, _, _) => (e1, true),
-            (_, _, true, true) => (e2, true),
-            _ => (e1, false),
+        let (ball, other) = if ball_q.contains(event.collider1) {
+            (event.collider1, event.collider2)
+        } else {
+            (event.collider2, event.collider1)
         };
-
-        if hit {
-            // 归一化方向，避免 NaN
-            let force_dir = event.total_force.normalize_or_zero();
-
-            if let Ok(mut vel) = params.p0().get_mut(ball_entity) {
-                vel.linvel = force_dir * 3.0;
-                /* println!(
-                    "设置球 {:?} 速度为：方向 {:?}, 强度 {:.2}, 最终速度 {:?}",
-                    ball_entity, force_dir, event.total_force_magnitude, vel.linvel
-                ); */
-            }
+        if !table_q.contains(other) {
+            continue;
         }
-
-        let hit_table = (e1_is_ball && e2_is_table) || (e2_is_ball && e1_is_table);
-        if hit_table {
-            if let Ok(transform) = params.p1().get(ball_entity) {
-                println!("📍 球与桌子接触，桌子位置: {:?}", transform.translation);
-            }
-        }
-        text.0 = format!("{:?} {:?}", hit_table, launch_state.launched);
-    }
-}
-
-fn move_racket(
-    mut query: Query<&mut Transform, (With<Racket>, Without<Ball>)>,
-    mut ball_query: Query<&mut Transform, With<Ball>>,
-    mut text: Single<&mut Text, With<MoveSpeedText>>,
-    launch_state: ResMut<LaunchState>,
-) {
-    let mut ball_transform = match ball_query.get_single_mut() {
-        Ok(t) => t,
-        Err(_) => return, // 没有找到 Racket，跳过
-    };
-    let mut racket_transform = match query.get_single_mut() {
-        Ok(t) => t,
-        Err(_) => return,
-    };
-
-    // let base = Quat::from_euler(EulerRot::XYZ, 0.0, -PI / 2.0 + rotation.x, rotation.w);
-    // transform.rotation = base;
-
-    let rotation = racket_transform.rotation;
-    let x = rotation.y + PI / 2.0;
-    let w = rotation.z;
-
-    racket_transform.translation = Vec3::new(
-        -x.abs().cos() / 4. + 0.1, //rotation.x.abs().sin() / 2. - 0.05,
-        -0.03,
-        0., // rotation.x.abs().cos(),
-    );
-    if x < 0. {
-        racket_transform.translation.z += 0.05;
-    } else {
-        racket_transform.translation.z -= 0.05;
-    }
-    if !launch_state.launched {
-        // ball_transform.translation.z = transform.translation.z + rotation.x.sin() * 0.05;
-        racket_transform.translation.z += ball_transform.translation.z;
-        if ball_transform.translation.x > 0. {
-            racket_transform.translation.x += ball_transform.translation.x;
-            racket_transform.translation.y += ball_transform.translation.y;
+        if let Ok(transform) = ball_q.get(ball) {
+            debug!("📍 球与桌子接触，桌子位置: {:?}", transform.translation);
         }
-        // transform.translation += Vec3::new(0.9, 1.0, rotation.x / 4.);
-    } else {
-        racket_transform.translation += Vec3::new(0.9, 1.0, 0.);
     }
-    text.0 = format!("{:?}", x);
 }
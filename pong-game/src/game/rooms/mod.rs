@@ -0,0 +1,692 @@
+use std::env;
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+
+use bevy::app::{AppLabel, MainSchedulePlugin};
+use bevy::ecs::event::{EventRegistry, EventUpdates, event_update_condition, event_update_system};
+use bevy::ecs::reflect::AppTypeRegistry;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::ecs::system::SystemParam;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::time::TimePlugin;
+use bevy::transform::TransformPlugin;
+use bevy::prelude::*;
+use bevy::scene::SceneSpawner;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::GameState;
+use crate::components::button::{ButtonActions, corner_button};
+use crate::components::theme::UiTheme;
+use crate::locale::Locale;
+use crate::game::camera::viewport::ViewportSlot;
+use crate::game::input::{MAX_PLAYERS, PlayerRacket, RacketOffset, mirror};
+use crate::game::utils::{
+    Ball, BallTableCollisionCount, CommandDataType, LaunchState, Racket, RacketCommandQueue,
+    Net, RoomHandle, RoomRegistry, ServerStatus, Table, command_handler,
+    court::Court,
+    racket_physics::{self, KinematicTarget, RacketHitEvent, RacketMotion},
+    rally::{self, RallyBounds, RallyEnded},
+    tls::TlsMode,
+};
+
+use super::despawn_screen;
+
+/// 房间码字符集，去掉了容易看错的 I 和 O
+const ROOM_CODE_CHARS: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ";
+const ROOM_CODE_LEN: usize = 4;
+/// 3、4 号座位站在同伴旁边（玩家自己一侧的坐标）
+const PARTNER_OFFSET: Vec3 = Vec3::new(0.0, 0.0, 0.45);
+
+#[derive(AppLabel, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+struct RoomApp(usize);
+
+#[derive(Clone, Debug, Default)]
+pub struct RoomSnapshot {
+    pub code: String,
+    pub ball: Transform,
+    /// 按座位顺序排列的球拍
+    pub rackets: Vec<Transform>,
+    pub hits: u32,
+    pub rallies: u32,
+    pub controllers: usize,
+}
+
+/// 各房间的最新状态，由子应用的 extract 每帧写入主世界
+#[derive(Resource, Default)]
+pub struct RoomSnapshots(pub Vec<RoomSnapshot>);
+
+/// 观战画面当前显示的房间
+#[derive(Resource, Default)]
+pub struct SelectedRoom(pub usize);
+
+/// 房间子应用内的比赛状态
+#[derive(Resource)]
+struct RoomState {
+    code: String,
+    hits: u32,
+    rallies: u32,
+}
+
+#[derive(Component)]
+pub struct OnSpectatorScreen;
+
+#[derive(Component)]
+struct SpectatorBall;
+
+/// 观战画面中第 N 个座位的球拍模型
+#[derive(Component)]
+struct SpectatorRacket(usize);
+
+/// 房间球拍记住所在座位手机最近的姿态和横向位移，只收到 `position` 指令时也能移动
+#[derive(Component, Default)]
+struct SeatPose {
+    rotation: Option<Quat>,
+    shift: f32,
+}
+
+#[derive(Component)]
+struct SpectatorText;
+
+/// 房间数量由 `PONG_ROOMS` 决定，默认创建一个房间，设为 0 不创建。
+/// 每个房间是一个无渲染的子应用，拥有独立的物理世界和指令队列。
+pub fn game_rooms_plugin(app: &mut App) {
+    let count = env::var("PONG_ROOMS")
+        .ok()
+        .and_then(|count| count.parse::<usize>().ok())
+        .unwrap_or(1);
+    let registry = app.world().resource::<RoomRegistry>().clone();
+    let court = *app.world().resource::<Court>();
+
+    let mut codes: Vec<String> = Vec::new();
+    for index in 0..count {
+        let code = new_room_code(&codes);
+        let queue = RacketCommandQueue(Arc::new(Mutex::new(Vec::new())));
+        registry.0.lock().unwrap().push(RoomHandle {
+            code: code.clone(),
+            queue: queue.clone(),
+            controllers: 0,
+        });
+        app.insert_sub_app(RoomApp(index), room_sub_app(index, code.clone(), queue, court));
+        codes.push(code);
+    }
+    if count > 0 {
+        info!("🏠 已创建 {} 个房间: {}", count, codes.join(", "));
+    }
+
+    app.init_resource::<RoomSnapshots>()
+        .init_resource::<SelectedRoom>()
+        .add_systems(OnEnter(GameState::Spectating), spectator_setup)
+        .add_systems(
+            Update,
+            (switch_room, sync_spectator_view, menu_action)
+                .run_if(in_state(GameState::Spectating)),
+        )
+        .add_systems(
+            OnEnter(GameState::Menu),
+            despawn_screen::<OnSpectatorScreen>,
+        );
+}
+
+fn new_room_code(existing: &[String]) -> String {
+    let mut rng = rand::rng();
+    loop {
+        let code: String = (0..ROOM_CODE_LEN)
+            .map(|_| ROOM_CODE_CHARS[rng.random_range(0..ROOM_CODE_CHARS.len())] as char)
+            .collect();
+        if !existing.contains(&code) {
+            return code;
+        }
+    }
+}
+
+fn room_sub_app(index: usize, code: String, queue: RacketCommandQueue, court: Court) -> SubApp {
+    let mut room = App::empty();
+    room.init_resource::<AppTypeRegistry>()
+        .init_resource::<EventRegistry>()
+        .add_plugins((
+        MainSchedulePlugin,
+        TimePlugin,
+        TransformPlugin,
+        HierarchyPlugin,
+        racket_physics::racket_physics_plugin,
+        rally::rally_plugin,
+    ));
+    // 只注册物理管线：RapierPhysicsPlugin 的 finish 会为异步碰撞体加上 AssetPlugin、MeshPlugin
+    // 和 ScenePlugin，房间没有模型，不作为插件添加；异步碰撞体系统读取的资源给空的。
+    // 碰撞事件要在 build 之前注册，否则不会每帧清理
+    room.add_event::<CollisionEvent>()
+        .add_event::<ContactForceEvent>();
+    RapierPhysicsPlugin::<NoUserData>::default().build(&mut room);
+    room.init_resource::<Assets<Mesh>>()
+        .init_resource::<SceneSpawner>()
+        .add_systems(
+            First,
+            event_update_system
+                .in_set(EventUpdates)
+                .run_if(event_update_condition),
+        )
+        .insert_resource(queue)
+        .insert_resource(court)
+        .insert_resource(RallyBounds::TableArea)
+        .insert_resource(LaunchState::default())
+        .insert_resource(BallTableCollisionCount::default())
+        .insert_resource(RoomState {
+            code,
+            hits: 0,
+            rallies: 0,
+        })
+        .add_systems(Startup, room_setup)
+        .add_systems(
+            Update,
+            (
+                apply_room_commands,
+                room_hit_system,
+                rally::count_ball_contacts,
+                rally::end_rally_system,
+                serve_room_ball,
+            )
+                .chain(),
+        );
+
+    let mut sub_app = std::mem::replace(room.main_mut(), SubApp::new());
+    sub_app.update_schedule = Some(Main.intern());
+    sub_app.set_extract(move |main_world, room_world| {
+        extract_room_snapshot(index, main_world, room_world);
+    });
+    sub_app
+}
+
+// 与练习模式相同的碰撞体，只是没有模型和相机，使用球场的默认碰撞体
+fn room_setup(mut commands: Commands, court: Res<Court>) {
+    commands
+        .spawn((
+            Transform::from_scale(court.table_scale()),
+            Table,
+            RigidBody::Fixed,
+            ActiveEvents::COLLISION_EVENTS,
+            court.table_collider(),
+            Ccd { enabled: true },
+            Restitution {
+                coefficient: court.table_restitution,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                court.net_transform(),
+                Net,
+                RigidBody::Fixed,
+                ActiveEvents::COLLISION_EVENTS,
+                court.net_collider(),
+                Restitution {
+                    coefficient: court.table_restitution,
+                    combine_rule: CoefficientCombineRule::Max,
+                },
+            ));
+        });
+    // 每个座位一支球拍，和本机双打一样 1、3 号座位在 +x 一侧，2、4 号座位在 -x 一侧
+    for seat in 0..MAX_PLAYERS {
+        let racket = if seat % 2 == 0 {
+            PlayerRacket::new(seat)
+        } else {
+            PlayerRacket::mirrored(seat)
+        };
+        let offset = if seat < 2 { Vec3::ZERO } else { PARTNER_OFFSET };
+        let pose = Transform::from_translation(court.racket_position() + offset)
+            .with_rotation(Quat::from_euler(EulerRot::XYZ, 0.0, -PI / 2.0, 0.0));
+        commands.spawn((
+            if racket.mirrored { mirror(pose) } else { pose },
+            Racket,
+            racket,
+            RacketOffset(offset),
+            SeatPose::default(),
+            RigidBody::KinematicVelocityBased,
+            Velocity::zero(),
+            KinematicTarget::default(),
+            RacketMotion::default(),
+            ActiveEvents::COLLISION_EVENTS,
+            court.racket_collider(),
+            Ccd { enabled: true },
+            Restitution {
+                coefficient: 0.,
+                combine_rule: CoefficientCombineRule::Max,
+            },
+        ));
+    }
+    commands.spawn((
+        Transform::from_translation(court.serve_position())
+            .with_scale(Vec3::splat(court.ball_scale())),
+        Ball,
+        RigidBody::Dynamic,
+        Velocity::zero(),
+        GravityScale(0.0),
+        ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS,
+        court.ball_collider(),
+        court.ball_mass(),
+        Ccd { enabled: true },
+        Restitution {
+            coefficient: 1.0,
+            combine_rule: CoefficientCombineRule::Average,
+        },
+        Friction {
+            coefficient: 0.6,
+            combine_rule: CoefficientCombineRule::Average,
+        },
+        Damping {
+            linear_damping: 0.3,
+            angular_damping: 0.1,
+        },
+    ));
+}
+
+/// 房间中的球拍
+type SeatRacket = (
+    &'static PlayerRacket,
+    &'static RacketOffset,
+    &'static mut SeatPose,
+    &'static mut KinematicTarget,
+);
+
+/// 指令按发送者的座位交给对应的球拍，房间没有菜单，`nav:` 指令忽略
+fn apply_room_commands(
+    mut rackets: Query<SeatRacket, With<Racket>>,
+    ball_query: Query<&Transform, With<Ball>>,
+    command_queue: Res<RacketCommandQueue>,
+    launch_state: Res<LaunchState>,
+    court: Res<Court>,
+) {
+    let Ok(ball) = ball_query.get_single() else {
+        return;
+    };
+    let mut moved = [false; MAX_PLAYERS];
+    for command in command_queue.0.lock().unwrap().drain(..) {
+        let Some((_, _, mut seat, _)) = rackets
+            .iter_mut()
+            .find(|(racket, ..)| racket.player == command.seat)
+        else {
+            continue;
+        };
+        match command.command {
+            CommandDataType::Rotation(rotation) => seat.rotation = Some(rotation),
+            CommandDataType::Position(delta) => {
+                seat.shift = command_handler::shift_racket(&court, seat.shift, delta);
+            }
+            CommandDataType::Menu(_) => continue,
+        }
+        moved[command.seat] = true;
+    }
+
+    for (racket, offset, seat, mut target) in rackets.iter_mut() {
+        let Some(rotation) = seat.rotation.filter(|_| moved[racket.player]) else {
+            continue;
+        };
+        let ball = if racket.mirrored {
+            mirror(*ball).translation
+        } else {
+            ball.translation
+        };
+        let mut pose = command_handler::racket_pose(&court, rotation, ball, launch_state.launched);
+        pose.translation += offset.0 + Vec3::Z * seat.shift;
+        target.0 = Some(if racket.mirrored { mirror(pose) } else { pose });
+    }
+}
+
+// 击球由 racket_physics 结算，这里只计数
+fn room_hit_system(mut hit_events: EventReader<RacketHitEvent>, mut room: ResMut<RoomState>) {
+    room.hits += hit_events.read().count() as u32;
+}
+
+/// 回合结束后从对面重新发球
+fn serve_room_ball(
+    mut commands: Commands,
+    mut ended: EventReader<RallyEnded>,
+    mut room: ResMut<RoomState>,
+    court: Res<Court>,
+) {
+    let mut rng = rand::rng();
+    for event in ended.read() {
+        commands.entity(event.ball).insert((
+            Transform::from_xyz(
+                rng.random_range(-0.3..=-0.1),
+                court.table_height + rng.random_range(0.24..=0.44),
+                rng.random_range(-0.4..=0.4),
+            )
+            .with_scale(Vec3::splat(court.ball_scale())),
+            Velocity {
+                linvel: Vec3::new(rng.random_range(2.0..=4.0), 0.0, rng.random_range(-1.0..=1.0)),
+                angvel: Vec3::ZERO,
+            },
+        ));
+        room.rallies += 1;
+    }
+}
+
+fn extract_room_snapshot(index: usize, main_world: &mut World, room_world: &mut World) {
+    let mut ball_query = room_world.query_filtered::<&Transform, With<Ball>>();
+    let ball = ball_query.get_single(room_world).copied().unwrap_or_default();
+    let mut racket_query = room_world.query_filtered::<(&PlayerRacket, &Transform), With<Racket>>();
+    let mut rackets: Vec<(usize, Transform)> = racket_query
+        .iter(room_world)
+        .map(|(racket, transform)| (racket.player, *transform))
+        .collect();
+    rackets.sort_by_key(|(seat, _)| *seat);
+    let room = room_world.resource::<RoomState>();
+    let controllers = main_world
+        .resource::<RoomRegistry>()
+        .0
+        .lock()
+        .unwrap()
+        .get(index)
+        .map(|handle| handle.controllers)
+        .unwrap_or(0);
+
+    let snapshot = RoomSnapshot {
+        code: room.code.clone(),
+        ball,
+        rackets: rackets
+            .into_iter()
+            .map(|(_, transform)| transform)
+            .collect(),
+        hits: room.hits,
+        rallies: room.rallies,
+        controllers,
+    };
+    let mut snapshots = main_world.resource_mut::<RoomSnapshots>();
+    if snapshots.0.len() <= index {
+        snapshots.0.resize_with(index + 1, Default::default);
+    }
+    snapshots.0[index] = snapshot;
+}
+
+#[derive(Component)]
+enum ButtonAction {
+    Esc,
+}
+
+fn menu_action(
+    interaction_query: ButtonActions<ButtonAction>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, menu_button_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match menu_button_action {
+                ButtonAction::Esc => {
+                    game_state.set(GameState::Menu);
+                }
+            }
+        }
+    }
+}
+
+fn spectator_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    court: Res<Court>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+
+    commands.spawn((
+        SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/tennis_table.glb"))),
+        Transform::from_scale(court.table_scale()),
+        OnSpectatorScreen,
+    ));
+    for seat in 0..MAX_PLAYERS {
+        commands.spawn((
+            SceneRoot(
+                asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/pong-racket.glb")),
+            ),
+            Transform::from_translation(court.racket_position()),
+            SpectatorRacket(seat),
+            OnSpectatorScreen,
+        ));
+    }
+    commands.spawn((
+        SceneRoot(asset_server.load(GltfAssetLabel::Scene(0).from_asset("models/ball.glb"))),
+        Transform::from_translation(court.serve_position())
+            .with_scale(Vec3::splat(court.ball_scale())),
+        SpectatorBall,
+        OnSpectatorScreen,
+    ));
+
+    // light
+    commands.spawn((
+        PointLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 3.0, 0.0),
+        OnSpectatorScreen,
+    ));
+    // 侧面视角，能同时看到双方
+    commands.spawn((
+        Camera3d { ..default() },
+        Camera {
+            order: 3,
+            ..default()
+        },
+        court.side_camera(),
+        ViewportSlot::full(),
+        OnSpectatorScreen,
+    ));
+
+    commands.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        SpectatorText,
+        OnSpectatorScreen,
+    ));
+    commands.spawn((
+        corner_button(&theme, locale.text("hud-exit")),
+        ButtonAction::Esc,
+        OnSpectatorScreen,
+    ));
+}
+
+fn switch_room(
+    keys: Res<ButtonInput<KeyCode>>,
+    snapshots: Res<RoomSnapshots>,
+    mut selected: ResMut<SelectedRoom>,
+) {
+    let count = snapshots.0.len();
+    if count == 0 {
+        return;
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        selected.0 = (selected.0 + 1) % count;
+    }
+    if keys.just_pressed(KeyCode::ArrowLeft) {
+        selected.0 = (selected.0 + count - 1) % count;
+    }
+    let digits = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    for (i, key) in digits.iter().enumerate() {
+        if i < count && keys.just_pressed(*key) {
+            selected.0 = i;
+        }
+    }
+}
+
+/// 观看房间时显示的球和球拍模型
+#[derive(SystemParam)]
+struct SpectatorModels<'w, 's> {
+    ball: Query<'w, 's, &'static mut Transform, (With<SpectatorBall>, Without<SpectatorRacket>)>,
+    rackets:
+        Query<'w, 's, (&'static SpectatorRacket, &'static mut Transform), Without<SpectatorBall>>,
+}
+
+fn sync_spectator_view(
+    snapshots: Res<RoomSnapshots>,
+    selected: Res<SelectedRoom>,
+    status: Res<ServerStatus>,
+    tls_mode: Res<TlsMode>,
+    locale: Res<Locale>,
+    mut models: SpectatorModels,
+    mut text: Single<&mut Text, With<SpectatorText>>,
+) {
+    let snapshot = match snapshots.0.get(selected.0) {
+        Some(snapshot) => snapshot,
+        None => {
+            text.0 = locale.text("rooms-none");
+            return;
+        }
+    };
+
+    for mut transform in models.ball.iter_mut() {
+        transform.translation = snapshot.ball.translation;
+        transform.rotation = snapshot.ball.rotation;
+    }
+    for (racket, mut transform) in models.rackets.iter_mut() {
+        if let Some(pose) = snapshot.rackets.get(racket.0) {
+            transform.translation = pose.translation;
+            transform.rotation = pose.rotation;
+        }
+    }
+
+    let info = status.0.lock().unwrap();
+    let url = format!(
+        "{}://{}:{}/?room={}",
+        tls_mode.http_scheme(),
+        info.host,
+        info.port,
+        snapshot.code
+    );
+    text.0 = locale.format(
+        "rooms-info",
+        &[
+            ("code", &snapshot.code),
+            ("index", &(selected.0 + 1)),
+            ("count", &snapshots.0.len()),
+            ("controllers", &snapshot.controllers),
+            ("hits", &snapshot.hits),
+            ("rallies", &snapshot.rallies),
+            ("url", &url),
+        ],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::game::utils::RacketTransformCommand;
+
+    fn headless_room() -> SubApp {
+        let queue = RacketCommandQueue(Arc::new(Mutex::new(Vec::new())));
+        let mut room = room_sub_app(0, "TEST".to_string(), queue, Court::default());
+        room.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(16)));
+        room.finish();
+        room.cleanup();
+        room.update();
+        room
+    }
+
+    fn ball_position(room: &mut SubApp) -> Vec3 {
+        let world = room.world_mut();
+        let mut balls = world.query_filtered::<&Transform, With<Ball>>();
+        balls.single(world).translation
+    }
+
+    #[test]
+    fn room_runs_without_render_world() {
+        let mut room = headless_room();
+        let world = room.world();
+        assert!(!world.contains_resource::<AssetServer>());
+        assert!(!world.contains_resource::<Assets<Image>>());
+
+        let start = ball_position(&mut room);
+        room.world_mut().resource_mut::<LaunchState>().launched = true;
+        for _ in 0..10 {
+            room.update();
+        }
+        assert!(ball_position(&mut room).y < start.y, "击球后球应该在重力下落");
+    }
+
+    #[test]
+    fn ball_leaving_table_starts_next_rally() {
+        let mut room = headless_room();
+        let world = room.world_mut();
+        let mut balls = world.query_filtered::<&mut Transform, With<Ball>>();
+        balls.single_mut(world).translation = Vec3::new(5.0, 1.0, 0.0);
+        world.resource_mut::<LaunchState>().launched = true;
+        room.update();
+        room.update();
+
+        assert_eq!(room.world().resource::<RoomState>().rallies, 1);
+        assert!(!room.world().resource::<LaunchState>().launched);
+        assert!(ball_position(&mut room).x < 0.0, "下一球从对面发出");
+    }
+
+    fn send(room: &SubApp, seat: usize, command: CommandDataType) {
+        let queue = room.world().resource::<RacketCommandQueue>();
+        queue.0.lock().unwrap().push(RacketTransformCommand { command, seat });
+    }
+
+    fn racket_poses(room: &mut SubApp) -> Vec<(usize, Vec3, f32)> {
+        let world = room.world_mut();
+        let mut rackets = world.query::<(&PlayerRacket, &Transform, &SeatPose)>();
+        let mut poses: Vec<_> = rackets
+            .iter(world)
+            .map(|(racket, transform, seat)| (racket.player, transform.translation, seat.shift))
+            .collect();
+        poses.sort_by_key(|(seat, ..)| *seat);
+        poses
+    }
+
+    #[test]
+    fn every_seat_has_its_own_racket() {
+        let mut room = headless_room();
+        let poses = racket_poses(&mut room);
+        assert_eq!(poses.len(), MAX_PLAYERS);
+        assert!(poses[0].1.x > 0.0 && poses[2].1.x > 0.0, "1、3 号座位在 +x 一侧");
+        assert!(poses[1].1.x < 0.0 && poses[3].1.x < 0.0, "2、4 号座位在 -x 一侧");
+    }
+
+    #[test]
+    fn commands_move_only_the_senders_racket() {
+        let mut room = headless_room();
+        let before = racket_poses(&mut room);
+        for _ in 0..5 {
+            send(&room, 1, CommandDataType::Rotation(Quat::from_xyzw(0.4, 0.0, 0.0, 0.2)));
+            room.update();
+        }
+        let after = racket_poses(&mut room);
+        for seat in 0..MAX_PLAYERS {
+            let moved = before[seat].1.distance(after[seat].1) > 1e-4;
+            assert_eq!(moved, seat == 1, "座位 {} 的球拍", seat + 1);
+        }
+    }
+
+    #[test]
+    fn position_commands_shift_racket_within_table() {
+        let mut room = headless_room();
+        let half_width = Court::default().half_width();
+        send(&room, 0, CommandDataType::Position(Vec3::new(0.1, 9.8, 0.0)));
+        room.update();
+        let poses = racket_poses(&mut room);
+        assert!((poses[0].2 - 0.1).abs() < 1e-6, "只使用横向位移");
+        assert_eq!(poses[2].2, 0.0);
+
+        send(&room, 0, CommandDataType::Position(Vec3::new(5.0, 0.0, 0.0)));
+        room.update();
+        assert_eq!(racket_poses(&mut room)[0].2, half_width);
+    }
+}
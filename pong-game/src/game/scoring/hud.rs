@@ -0,0 +1,330 @@
+use bevy::prelude::*;
+
+use crate::GameState;
+use crate::components::button::{ButtonActions, ButtonKind, button};
+use crate::components::theme::{SUMMARY_LAYER, TextSize, UiTheme, overlay_column};
+use crate::despawn_screen;
+use crate::game::LocalVersus;
+use crate::locale::Locale;
+
+use super::{MatchConfig, MatchPhase, MatchScore, RestartMatch};
+
+#[derive(Component)]
+pub struct OnScoreboardScreen;
+
+#[derive(Component)]
+struct MatchSummaryScreen;
+
+/// 记分牌上的各个文本
+#[derive(Component, Clone, Copy)]
+enum ScoreboardField {
+    /// 左侧为本机玩家
+    Player(usize),
+    Points,
+    Rally,
+    Banner,
+}
+
+#[derive(Component)]
+enum SummaryAction {
+    Rematch,
+    MainMenu,
+}
+
+pub fn scoreboard_hud_plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        spawn_scoreboard.run_if(resource_added::<MatchConfig>),
+    )
+    .add_systems(
+        Update,
+        (update_scoreboard, toggle_match_summary, summary_action)
+            .run_if(resource_exists::<MatchConfig>)
+            .run_if(not(in_state(GameState::Menu))),
+    )
+    .add_systems(
+        OnEnter(GameState::Menu),
+        despawn_screen::<OnScoreboardScreen>,
+    );
+}
+
+/// 本机玩家在左，对手在右
+fn display_order(config: &MatchConfig) -> [usize; 2] {
+    [config.local_player, 1 - config.local_player]
+}
+
+fn spawn_scoreboard(mut commands: Commands, config: Res<MatchConfig>, theme: Res<UiTheme>) {
+    let [left, right] = display_order(&config);
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            OnScoreboardScreen,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Row,
+                        align_items: AlignItems::Center,
+                        column_gap: Val::Px(24.0),
+                        padding: UiRect::axes(Val::Px(16.0), Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(theme.panel),
+                    OnScoreboardScreen,
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        theme.text(String::new(), TextSize::Option),
+                        ScoreboardField::Player(left),
+                        OnScoreboardScreen,
+                    ));
+                    row.spawn((
+                        theme.text(String::new(), TextSize::Score),
+                        ScoreboardField::Points,
+                        OnScoreboardScreen,
+                    ));
+                    row.spawn((
+                        theme.text(String::new(), TextSize::Option),
+                        ScoreboardField::Player(right),
+                        OnScoreboardScreen,
+                    ));
+                });
+            parent.spawn((
+                theme.text(String::new(), TextSize::Caption),
+                ScoreboardField::Rally,
+                OnScoreboardScreen,
+            ));
+        });
+
+    // 得分、局终时显示在屏幕中间
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Percent(35.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            OnScoreboardScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                theme.text(String::new(), TextSize::Banner),
+                TextLayout::new_with_justify(JustifyText::Center),
+                ScoreboardField::Banner,
+                Visibility::Hidden,
+                OnScoreboardScreen,
+            ));
+        });
+}
+
+fn update_scoreboard(
+    config: Res<MatchConfig>,
+    score: Res<MatchScore>,
+    locale: Res<Locale>,
+    mut fields: Query<(&mut Text, &mut Visibility, &ScoreboardField)>,
+) {
+    let [left, right] = display_order(&config);
+    for (mut text, mut visibility, field) in fields.iter_mut() {
+        let content = match *field {
+            ScoreboardField::Player(player) => {
+                // ● 表示发球方
+                let serve = if score.server == player { "● " } else { "" };
+                format!("{}{} [{}]", serve, config.names[player], score.games[player])
+            }
+            ScoreboardField::Points => {
+                format!("{} : {}", score.points[left], score.points[right])
+            }
+            ScoreboardField::Rally => locale.format(
+                "hud-rally",
+                &[
+                    ("rally", &score.rally),
+                    ("speed", &format!("{:.0}", score.last_shot_speed * 3.6)),
+                ],
+            ),
+            ScoreboardField::Banner => {
+                let reason = score
+                    .last_reason
+                    .map(|r| locale.text(r.locale_key()))
+                    .unwrap_or_default();
+                let banner = match score.phase {
+                    MatchPhase::Playing | MatchPhase::MatchOver { .. } => None,
+                    MatchPhase::PointOver { winner } => Some(locale.format(
+                        "hud-point",
+                        &[("player", &config.names[winner]), ("reason", &reason)],
+                    )),
+                    MatchPhase::GameOver { winner } => {
+                        let last = score.finished_games.last().copied().unwrap_or_default();
+                        Some(locale.format(
+                            "hud-game",
+                            &[
+                                ("player", &config.names[winner]),
+                                ("left", &last[left]),
+                                ("right", &last[right]),
+                            ],
+                        ))
+                    }
+                };
+                *visibility = if banner.is_some() {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                };
+                banner.unwrap_or_default()
+            }
+        };
+        if text.0 != content {
+            text.0 = content;
+        }
+    }
+}
+
+/// 整场比赛结束后显示总结页，重新开始后关闭
+fn toggle_match_summary(
+    mut commands: Commands,
+    config: Res<MatchConfig>,
+    versus: Option<Res<LocalVersus>>,
+    score: Res<MatchScore>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+    summary: Query<Entity, With<MatchSummaryScreen>>,
+) {
+    let winner = match score.phase {
+        MatchPhase::MatchOver { winner } => Some(winner),
+        _ => None,
+    };
+    let in_tournament = versus.is_some_and(|versus| versus.tournament.is_some());
+    match (winner, summary.is_empty()) {
+        (Some(winner), true) => spawn_match_summary(
+            &mut commands,
+            &config,
+            &score,
+            &theme,
+            &locale,
+            winner,
+            in_tournament,
+        ),
+        (None, false) => {
+            for entity in summary.iter() {
+                commands.entity(entity).despawn();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn spawn_match_summary(
+    commands: &mut Commands,
+    config: &MatchConfig,
+    score: &MatchScore,
+    theme: &UiTheme,
+    locale: &Locale,
+    winner: usize,
+    in_tournament: bool,
+) {
+    let [left, right] = display_order(config);
+    let games = score
+        .finished_games
+        .iter()
+        .map(|game| format!("{}-{}", game[left], game[right]))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let stats = &score.stats;
+    let lines = [
+        locale.format(
+            "summary-versus",
+            &[("left", &config.names[left]), ("right", &config.names[right])],
+        ),
+        locale.format("summary-games", &[("games", &games)]),
+        locale.format(
+            "summary-points",
+            &[("left", &stats.points_won[left]), ("right", &stats.points_won[right])],
+        ),
+        locale.format(
+            "summary-shots",
+            &[("left", &stats.shots[left]), ("right", &stats.shots[right])],
+        ),
+        locale.format("summary-longest-rally", &[("rally", &stats.longest_rally)]),
+        locale.format(
+            "summary-fastest-shot",
+            &[("speed", &format!("{:.0}", stats.fastest_shot * 3.6))],
+        ),
+    ];
+
+    commands
+        .spawn((
+            overlay_column(12.0),
+            BackgroundColor(theme.scrim),
+            GlobalZIndex(SUMMARY_LAYER),
+            MatchSummaryScreen,
+            OnScoreboardScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                theme.text(
+                    locale.format("summary-winner", &[("player", &config.names[winner])]),
+                    TextSize::Banner,
+                ),
+                MatchSummaryScreen,
+                OnScoreboardScreen,
+            ));
+            for line in lines {
+                parent.spawn((
+                    theme.text(line, TextSize::Body),
+                    MatchSummaryScreen,
+                    OnScoreboardScreen,
+                ));
+            }
+            // 锦标赛的结果已经记录，不能重赛，只能回到对阵表
+            if config.authoritative && !in_tournament {
+                parent.spawn((
+                    button(theme, locale.text("summary-rematch"), ButtonKind::Menu),
+                    SummaryAction::Rematch,
+                    MatchSummaryScreen,
+                    OnScoreboardScreen,
+                ));
+            } else if !config.authoritative {
+                parent.spawn((
+                    theme.text(locale.text("summary-waiting"), TextSize::Body),
+                    MatchSummaryScreen,
+                    OnScoreboardScreen,
+                ));
+            }
+            let back = if in_tournament { "summary-bracket" } else { "summary-main-menu" };
+            parent.spawn((
+                button(theme, locale.text(back), ButtonKind::Menu),
+                SummaryAction::MainMenu,
+                MatchSummaryScreen,
+                OnScoreboardScreen,
+            ));
+        });
+}
+
+fn summary_action(
+    interaction_query: ButtonActions<SummaryAction>,
+    mut restarts: EventWriter<RestartMatch>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            SummaryAction::Rematch => {
+                restarts.send(RestartMatch);
+            }
+            SummaryAction::MainMenu => game_state.set(GameState::Menu),
+        }
+    }
+}
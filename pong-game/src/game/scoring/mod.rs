@@ -0,0 +1,795 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+use crate::game::input::{PlayerRacket, mirror};
+use crate::game::utils::court::Court;
+use crate::game::utils::racket_physics::RacketHitEvent;
+use crate::game::utils::rally::RallyEnded;
+use crate::game::utils::{Ball, BallTableCollisionCount, LaunchState, Racket, Table};
+
+pub mod hud;
+
+/// 得分后横幅显示的时间，之后开始下一分
+const POINT_PAUSE: f32 = 1.5;
+/// 一局结束后横幅显示的时间
+const GAME_PAUSE: f32 = 3.0;
+
+/// 比赛规则，默认 11 分制、领先 2 分获胜、每人连发 2 球、三局两胜
+#[derive(Clone, Copy, Debug)]
+pub struct MatchRules {
+    pub points_to_win: u32,
+    pub win_by: u32,
+    pub serves_per_turn: u32,
+    pub best_of: u32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        MatchRules {
+            points_to_win: 11,
+            win_by: 2,
+            serves_per_turn: 2,
+            best_of: 3,
+        }
+    }
+}
+
+impl MatchRules {
+    /// `PONG_BEST_OF` 设置局数（取奇数）
+    pub fn from_env() -> Self {
+        let best_of = std::env::var("PONG_BEST_OF")
+            .ok()
+            .and_then(|value| value.parse::<u32>().ok())
+            .filter(|value| *value > 0)
+            .map(|value| value | 1)
+            .unwrap_or(3);
+        MatchRules {
+            best_of,
+            ..default()
+        }
+    }
+
+    pub fn games_to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+}
+
+/// 当前模式的比赛设置，由各模式进入时插入，回到菜单时移除
+#[derive(Resource, Clone, Debug)]
+pub struct MatchConfig {
+    pub names: [String; 2],
+    /// 对墙练习时墙不能发球
+    pub can_serve: [bool; 2],
+    /// 由本机判定得分；联机客户端为 false，比分来自主机快照
+    pub authoritative: bool,
+    /// 本机玩家，显示在记分牌左侧
+    pub local_player: usize,
+    /// 双打：每方两人轮流击球，发球须从右半区斜线发到对方右半区
+    pub doubles: bool,
+    pub rules: MatchRules,
+}
+
+impl MatchConfig {
+    /// 玩家 1 对墙，`names` 由调用方按界面语言给出
+    pub fn solo(names: [String; 2]) -> Self {
+        MatchConfig {
+            names,
+            can_serve: [true, false],
+            authoritative: true,
+            local_player: 0,
+            doubles: false,
+            rules: MatchRules::from_env(),
+        }
+    }
+
+    /// 本机双人对战
+    pub fn local(names: [String; 2]) -> Self {
+        MatchConfig {
+            names,
+            can_serve: [true, true],
+            authoritative: true,
+            local_player: 0,
+            doubles: false,
+            rules: MatchRules::from_env(),
+        }
+    }
+
+    /// 本机双打，`names` 为两方的队名
+    pub fn doubles(names: [String; 2]) -> Self {
+        MatchConfig {
+            doubles: true,
+            ..MatchConfig::local(names)
+        }
+    }
+
+    /// 联机对战，主机为玩家 1，`names` 为主机和客人的显示名字
+    pub fn versus(host: bool, names: [String; 2]) -> Self {
+        MatchConfig {
+            names,
+            can_serve: [true, true],
+            authoritative: host,
+            local_player: if host { 0 } else { 1 },
+            doubles: false,
+            rules: MatchRules::from_env(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum MatchPhase {
+    #[default]
+    Playing,
+    PointOver { winner: usize },
+    GameOver { winner: usize },
+    MatchOver { winner: usize },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointReason {
+    /// 击球没有落在对方台面
+    Out,
+    /// 击球落在自己的台面
+    OwnSide,
+    /// 球在接球方台面弹跳两次
+    DoubleBounce,
+    /// 双打中没有按顺序轮流击球
+    OutOfTurn,
+    /// 双打发球没有落在接发球方的右半区
+    WrongHalf,
+}
+
+impl PointReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PointReason::Out => "Out",
+            PointReason::OwnSide => "Own side",
+            PointReason::DoubleBounce => "Double bounce",
+            PointReason::OutOfTurn => "Out of turn",
+            PointReason::WrongHalf => "Serve not diagonal",
+        }
+    }
+
+    /// 界面上显示的文本 id
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            PointReason::Out => "reason-out",
+            PointReason::OwnSide => "reason-own-side",
+            PointReason::DoubleBounce => "reason-double-bounce",
+            PointReason::OutOfTurn => "reason-out-of-turn",
+            PointReason::WrongHalf => "reason-wrong-half",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MatchStats {
+    pub total_points: u32,
+    pub points_won: [u32; 2],
+    pub shots: [u32; 2],
+    pub longest_rally: u32,
+    /// 最快击球速度（m/s）
+    pub fastest_shot: f32,
+}
+
+/// 双打中的同伴，座位 0、2 在 +x 一侧，1、3 在 -x 一侧
+pub fn partner(seat: usize) -> usize {
+    (seat + 2) % 4
+}
+
+/// 双打的发球顺序（座位号）。本局第一分 A1 发给 B1，之后每轮发球
+/// 由上一轮的接发球员发给上一轮发球员的同伴：A1→B1、B1→A2、A2→B2、B2→A1
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DoublesOrder {
+    pub first_server: usize,
+    pub first_receiver: usize,
+    pub server: usize,
+    pub receiver: usize,
+}
+
+impl Default for DoublesOrder {
+    fn default() -> Self {
+        DoublesOrder {
+            first_server: 0,
+            first_receiver: 1,
+            server: 0,
+            receiver: 1,
+        }
+    }
+}
+
+impl DoublesOrder {
+    fn set_turn(&mut self, turn: u32) {
+        let cycle = [
+            self.first_server,
+            self.first_receiver,
+            partner(self.first_server),
+            partner(self.first_receiver),
+        ];
+        self.server = cycle[turn as usize % 4];
+        self.receiver = cycle[(turn as usize + 1) % 4];
+    }
+
+    /// 换局后上一局先接发球的一方先发球，发给上一局发球给他的人
+    fn next_game(&mut self) {
+        std::mem::swap(&mut self.first_server, &mut self.first_receiver);
+    }
+
+    /// 本分第 `shot` 次击球（从 0 开始）的击球员：发球员、接发球员、发球员同伴、接发球员同伴轮流
+    pub fn striker(&self, shot: u32) -> usize {
+        [
+            self.server,
+            self.receiver,
+            partner(self.server),
+            partner(self.receiver),
+        ][shot as usize % 4]
+    }
+}
+
+/// 比分和比赛进程，联机时随快照同步给客户端
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MatchScore {
+    pub points: [u32; 2],
+    pub games: [u32; 2],
+    /// 当前发球方
+    pub server: usize,
+    /// 本局第一分的发球方，每局交换
+    pub game_first_server: usize,
+    /// 本分的击球次数
+    pub rally: u32,
+    /// 上一次击球后的球速（m/s）
+    pub last_shot_speed: f32,
+    pub phase: MatchPhase,
+    pub last_reason: Option<PointReason>,
+    /// 已结束各局的比分
+    pub finished_games: Vec<[u32; 2]>,
+    pub stats: MatchStats,
+    /// 双打的发球顺序，单打为 `None`
+    #[serde(default)]
+    pub doubles: Option<DoublesOrder>,
+}
+
+impl MatchScore {
+    pub fn new(config: &MatchConfig) -> Self {
+        let mut score = MatchScore {
+            doubles: config.doubles.then(DoublesOrder::default),
+            ..default()
+        };
+        score.update_server(config);
+        score
+    }
+
+    /// 本局第几轮发球：每 `serves_per_turn` 分换发，双方都到局点前一分后每分换发
+    fn serving_turn(&self, rules: &MatchRules) -> u32 {
+        let total = self.points[0] + self.points[1];
+        let deuce = rules.points_to_win - 1;
+        if self.points.iter().all(|points| *points >= deuce) {
+            deuce * 2 / rules.serves_per_turn + (total - deuce * 2)
+        } else {
+            total / rules.serves_per_turn
+        }
+    }
+
+    /// 按规则计算当前发球方，双打时同时轮换发球员和接发球员
+    fn update_server(&mut self, config: &MatchConfig) {
+        let turn = self.serving_turn(&config.rules);
+        if let Some(order) = self.doubles.as_mut() {
+            order.set_turn(turn);
+        }
+        let server = (self.game_first_server + turn as usize) % 2;
+        self.server = if config.can_serve[server] { server } else { 1 - server };
+    }
+
+    /// 双打中下一次应该击球的座位，单打为 `None`
+    pub fn expected_striker(&self) -> Option<usize> {
+        self.doubles.map(|order| order.striker(self.rally))
+    }
+
+    fn record_shot(&mut self, shot: &ShotEvent) {
+        self.rally += 1;
+        self.last_shot_speed = shot.speed;
+        self.stats.shots[shot.player] += 1;
+        self.stats.fastest_shot = self.stats.fastest_shot.max(shot.speed);
+    }
+
+    fn award_point(&mut self, winner: usize, reason: PointReason, config: &MatchConfig) {
+        let loser = 1 - winner;
+        let rules = &config.rules;
+        self.points[winner] += 1;
+        self.last_reason = Some(reason);
+        self.stats.total_points += 1;
+        self.stats.points_won[winner] += 1;
+        self.stats.longest_rally = self.stats.longest_rally.max(self.rally);
+        self.rally = 0;
+
+        let won_game = self.points[winner] >= rules.points_to_win
+            && self.points[winner] >= self.points[loser] + rules.win_by;
+        self.phase = if won_game {
+            self.finished_games.push(self.points);
+            self.games[winner] += 1;
+            self.points = [0, 0];
+            self.game_first_server = 1 - self.game_first_server;
+            if let Some(order) = self.doubles.as_mut() {
+                order.next_game();
+            }
+            if self.games[winner] >= rules.games_to_win() {
+                MatchPhase::MatchOver { winner }
+            } else {
+                MatchPhase::GameOver { winner }
+            }
+        } else {
+            MatchPhase::PointOver { winner }
+        };
+        self.update_server(config);
+    }
+}
+
+/// 击球，`player` 为击球方（0 在 +x 一侧），`speed` 为击球后的球速
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ShotEvent {
+    pub player: usize,
+    /// 击球的本机球拍（[`PlayerRacket::player`]），墙和联机对手为 `None`
+    pub seat: Option<usize>,
+    pub speed: f32,
+}
+
+/// 一分结束
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PointScored {
+    pub winner: usize,
+    pub reason: PointReason,
+}
+
+/// 重新开始比赛：比分清零，球回到发球位置。没有比赛时（练习模式）只重置球
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RestartMatch;
+
+/// 判定得分的系统，各模式的重置逻辑需要排在它之后
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScoringSet;
+
+/// 本分的击球和落台情况
+#[derive(Resource, Default)]
+struct Rally {
+    last_hitter: Option<usize>,
+    /// 上一次击球后球落在哪一方的台面
+    bounced_on: Option<usize>,
+}
+
+/// 横幅显示的计时
+#[derive(Resource, Default)]
+struct PhaseTimer(Timer);
+
+/// 球所在的一方，玩家 1 在 +x 一侧
+pub fn side_of(position: Vec3) -> usize {
+    if position.x >= 0.0 { 0 } else { 1 }
+}
+
+/// 发球方的发球位置，玩家 2 取镜像
+pub fn serve_position(court: &Court, server: usize) -> Vec3 {
+    let position = court.serve_position();
+    if server == 0 {
+        position
+    } else {
+        mirror(Transform::from_translation(position)).translation
+    }
+}
+
+/// 本分的发球位置，双打时在发球方的右半区
+pub fn serve_spot(court: &Court, score: &MatchScore) -> Vec3 {
+    let mut position = serve_position(court, score.server);
+    if score.doubles.is_some() {
+        position.z = court.table_width / 4.0 * if score.server == 0 { -1.0 } else { 1.0 };
+    }
+    position
+}
+
+/// 回合结束后球回到由比分决定的发球位置，通常得分判定已经先一步放好了球
+pub fn serve_after_rally(
+    mut ended: EventReader<RallyEnded>,
+    mut balls: Query<&mut Transform, With<Ball>>,
+    court: Res<Court>,
+    score: Res<MatchScore>,
+) {
+    for event in ended.read() {
+        if let Ok(mut transform) = balls.get_mut(event.ball) {
+            transform.translation = serve_spot(&court, &score);
+        }
+    }
+}
+
+/// 从 `side` 一方的球员看过去，`z` 是否在其右半区，中线算在两边
+fn in_right_half(side: usize, z: f32) -> bool {
+    if side == 0 { z <= 0.0 } else { z >= 0.0 }
+}
+
+fn is_authoritative(config: Option<Res<MatchConfig>>) -> bool {
+    config.is_some_and(|config| config.authoritative)
+}
+
+pub fn scoring_plugin(app: &mut App) {
+    app.add_event::<ShotEvent>()
+        .add_event::<PointScored>()
+        .add_event::<RestartMatch>()
+        .init_resource::<MatchScore>()
+        .init_resource::<Rally>()
+        .init_resource::<PhaseTimer>()
+        .add_systems(
+            Update,
+            start_match.run_if(resource_added::<MatchConfig>),
+        )
+        .add_systems(
+            Update,
+            (
+                racket_shots,
+                referee_system,
+                advance_phase,
+                hold_ball_between_points,
+            )
+                .chain()
+                .in_set(ScoringSet)
+                .run_if(is_authoritative)
+                .run_if(not(in_state(GameState::Menu))),
+        )
+        .add_systems(
+            Update,
+            restart_match
+                .before(ScoringSet)
+                .run_if(on_event::<RestartMatch>)
+                .run_if(not(in_state(GameState::Menu))),
+        )
+        .add_systems(OnEnter(GameState::Menu), end_match)
+        .add_plugins(hud::scoreboard_hud_plugin);
+}
+
+fn start_match(
+    config: Res<MatchConfig>,
+    mut score: ResMut<MatchScore>,
+    mut rally: ResMut<Rally>,
+) {
+    *score = MatchScore::new(&config);
+    *rally = Rally::default();
+    info!(
+        "🏓 比赛开始: {} vs {}，{} 局 {} 胜",
+        config.names[0],
+        config.names[1],
+        config.rules.best_of,
+        config.rules.games_to_win()
+    );
+}
+
+/// 得分后和重新开始时把球放回发球位置
+#[derive(SystemParam)]
+struct ServeBall<'w, 's> {
+    commands: Commands<'w, 's>,
+    court: Res<'w, Court>,
+    launch_state: ResMut<'w, LaunchState>,
+    counter: ResMut<'w, BallTableCollisionCount>,
+    balls: Query<'w, 's, (Entity, &'static mut Transform, &'static mut Velocity), With<Ball>>,
+}
+
+impl ServeBall<'_, '_> {
+    /// 球停在本分发球方的位置并关闭重力，等待发球
+    fn reset(&mut self, score: &MatchScore) {
+        for (entity, mut transform, mut velocity) in self.balls.iter_mut() {
+            transform.translation = serve_spot(&self.court, score);
+            *velocity = Velocity::zero();
+            self.commands.entity(entity).insert(GravityScale(0.0));
+        }
+        self.launch_state.launched = false;
+        self.counter.count = 0;
+    }
+}
+
+/// 联机客户端的比分由主机同步，只有主机能重新开始
+fn restart_match(
+    mut restarts: EventReader<RestartMatch>,
+    config: Option<Res<MatchConfig>>,
+    mut score: ResMut<MatchScore>,
+    mut rally: ResMut<Rally>,
+    mut serve: ServeBall,
+) {
+    restarts.clear();
+    if let Some(config) = &config {
+        if !config.authoritative {
+            return;
+        }
+        *score = MatchScore::new(config);
+    }
+    *rally = Rally::default();
+    serve.reset(&score);
+    info!("🏓 重新开始比赛");
+}
+
+fn end_match(mut commands: Commands) {
+    commands.remove_resource::<MatchConfig>();
+}
+
+/// 球拍在哪一侧就是哪一方的击球
+fn racket_shots(
+    mut hit_events: EventReader<RacketHitEvent>,
+    rackets: Query<(&Transform, Option<&PlayerRacket>), With<Racket>>,
+    mut shots: EventWriter<ShotEvent>,
+) {
+    for hit in hit_events.read() {
+        if let Ok((racket, player_racket)) = rackets.get(hit.racket) {
+            shots.send(ShotEvent {
+                player: side_of(racket.translation),
+                seat: player_racket.map(|racket| racket.player),
+                speed: hit.speed,
+            });
+        }
+    }
+}
+
+/// 本帧的击球和球的碰撞
+#[derive(SystemParam)]
+struct RallyContacts<'w, 's> {
+    shots: EventReader<'w, 's, ShotEvent>,
+    collisions: EventReader<'w, 's, CollisionEvent>,
+    tables: Query<'w, 's, (), With<Table>>,
+}
+
+fn referee_system(
+    config: Res<MatchConfig>,
+    mut score: ResMut<MatchScore>,
+    mut rally: ResMut<Rally>,
+    mut timer: ResMut<PhaseTimer>,
+    mut contacts: RallyContacts,
+    mut points: EventWriter<PointScored>,
+    mut serve: ServeBall,
+) {
+    if score.phase != MatchPhase::Playing {
+        contacts.shots.clear();
+        contacts.collisions.clear();
+        return;
+    }
+    let court = *serve.court;
+    let Ok((entity, mut transform, mut velocity)) = serve.balls.get_single_mut() else {
+        return;
+    };
+
+    let mut outcome = None;
+    for shot in contacts.shots.read() {
+        // 双打必须按发球员、接发球员、双方同伴的顺序轮流击球
+        let out_of_turn = match (score.expected_striker(), shot.seat) {
+            (Some(expected), Some(seat)) => seat != expected,
+            _ => false,
+        };
+        if out_of_turn && outcome.is_none() {
+            outcome = Some((1 - shot.player, PointReason::OutOfTurn));
+        }
+        score.record_shot(shot);
+        rally.last_hitter = Some(shot.player);
+        rally.bounced_on = None;
+    }
+
+    for event in contacts.collisions.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+        let hit_table = (*e1 == entity && contacts.tables.contains(*e2))
+            || (*e2 == entity && contacts.tables.contains(*e1));
+        let Some(hitter) = rally.last_hitter else {
+            continue;
+        };
+        if !hit_table || outcome.is_some() {
+            continue;
+        }
+        let side = side_of(transform.translation);
+        outcome = match rally.bounced_on {
+            None if side == hitter => Some((1 - hitter, PointReason::OwnSide)),
+            // 双打发球要斜线落到接发球方的右半区
+            None if config.doubles
+                && score.rally == 1
+                && !in_right_half(side, transform.translation.z) =>
+            {
+                Some((1 - hitter, PointReason::WrongHalf))
+            }
+            None => {
+                rally.bounced_on = Some(side);
+                None
+            }
+            Some(bounced) if bounced == side => Some((hitter, PointReason::DoubleBounce)),
+            Some(_) => None,
+        };
+    }
+
+    if outcome.is_none() && court.out_of_bounds(transform.translation) {
+        outcome = match rally.last_hitter {
+            Some(hitter) if rally.bounced_on == Some(1 - hitter) => {
+                Some((hitter, PointReason::DoubleBounce))
+            }
+            Some(hitter) => Some((1 - hitter, PointReason::Out)),
+            // 还没有人击球，直接重新发球
+            None => {
+                transform.translation = serve_spot(&court, &score);
+                *velocity = Velocity::zero();
+                None
+            }
+        };
+    }
+
+    let Some((winner, reason)) = outcome else {
+        return;
+    };
+    score.award_point(winner, reason, &config);
+    *rally = Rally::default();
+    points.send(PointScored { winner, reason });
+    info!(
+        "🏓 {} 得分（{}），比分 {}-{}，局数 {}-{}",
+        config.names[winner],
+        reason.label(),
+        score.points[0],
+        score.points[1],
+        score.games[0],
+        score.games[1]
+    );
+
+    let pause = match score.phase {
+        MatchPhase::GameOver { .. } => GAME_PAUSE,
+        _ => POINT_PAUSE,
+    };
+    timer.0 = Timer::from_seconds(pause, TimerMode::Once);
+
+    // 球回到下一分发球方的位置
+    serve.reset(&score);
+}
+
+/// 横幅显示一段时间后开始下一分，整场比赛结束后等待玩家选择
+fn advance_phase(time: Res<Time>, mut score: ResMut<MatchScore>, mut timer: ResMut<PhaseTimer>) {
+    match score.phase {
+        MatchPhase::PointOver { .. } | MatchPhase::GameOver { .. } => {
+            if timer.0.tick(time.delta()).finished() {
+                score.phase = MatchPhase::Playing;
+            }
+        }
+        MatchPhase::Playing | MatchPhase::MatchOver { .. } => {}
+    }
+}
+
+/// 两分之间球停在发球位置
+fn hold_ball_between_points(
+    court: Res<Court>,
+    score: Res<MatchScore>,
+    mut launch_state: ResMut<LaunchState>,
+    mut ball_query: Query<(&mut Transform, &mut Velocity), With<Ball>>,
+) {
+    if score.phase == MatchPhase::Playing {
+        return;
+    }
+    for (mut transform, mut velocity) in ball_query.iter_mut() {
+        transform.translation = serve_spot(&court, &score);
+        *velocity = Velocity::zero();
+    }
+    launch_state.launched = false;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(doubles: bool) -> MatchConfig {
+        let names = ["A".to_string(), "B".to_string()];
+        MatchConfig {
+            doubles,
+            rules: MatchRules::default(),
+            ..MatchConfig::local(names)
+        }
+    }
+
+    /// 按顺序打完 `winners` 中的每一分
+    fn play(score: &mut MatchScore, config: &MatchConfig, winners: &[usize]) {
+        for winner in winners {
+            score.award_point(*winner, PointReason::Out, config);
+        }
+    }
+
+    #[test]
+    fn serve_changes_every_two_points() {
+        let config = config(false);
+        let mut score = MatchScore::new(&config);
+        let mut servers = vec![score.server];
+        for _ in 0..5 {
+            play(&mut score, &config, &[0]);
+            servers.push(score.server);
+        }
+        assert_eq!(servers, [0, 0, 1, 1, 0, 0]);
+    }
+
+    #[test]
+    fn serve_alternates_every_point_from_deuce() {
+        let config = config(false);
+        let mut score = MatchScore::new(&config);
+        play(&mut score, &config, &[0; 10]);
+        play(&mut score, &config, &[1; 9]);
+        assert_eq!(score.points, [10, 9]);
+        assert_eq!(score.server, 1);
+
+        let mut servers = Vec::new();
+        for winner in [1, 0, 1, 0] {
+            play(&mut score, &config, &[winner]);
+            servers.push(score.server);
+        }
+        assert_eq!(score.points, [12, 11]);
+        assert_eq!(servers, [0, 1, 0, 1]);
+        assert_eq!(score.phase, MatchPhase::PointOver { winner: 0 });
+    }
+
+    #[test]
+    fn game_needs_two_point_lead_and_swaps_first_server() {
+        let config = config(false);
+        let mut score = MatchScore::new(&config);
+        play(&mut score, &config, &[0; 10]);
+        play(&mut score, &config, &[1; 10]);
+        play(&mut score, &config, &[0, 0]);
+
+        assert_eq!(score.phase, MatchPhase::GameOver { winner: 0 });
+        assert_eq!(score.games, [1, 0]);
+        assert_eq!(score.finished_games, [[12, 10]]);
+        assert_eq!(score.points, [0, 0]);
+        assert_eq!(score.server, 1);
+
+        play(&mut score, &config, &[0; 11]);
+        assert_eq!(score.phase, MatchPhase::MatchOver { winner: 0 });
+    }
+
+    #[test]
+    fn wall_never_serves() {
+        let config = MatchConfig {
+            rules: MatchRules::default(),
+            ..MatchConfig::solo(["Player 1".to_string(), "Wall".to_string()])
+        };
+        let mut score = MatchScore::new(&config);
+        for _ in 0..6 {
+            play(&mut score, &config, &[1]);
+            assert_eq!(score.server, 0);
+        }
+    }
+
+    #[test]
+    fn doubles_serve_cycles_through_all_four_players() {
+        let config = config(true);
+        let mut score = MatchScore::new(&config);
+        let mut turns = Vec::new();
+        for _ in 0..5 {
+            let order = score.doubles.unwrap();
+            assert_eq!(order.server % 2, score.server, "发球员属于发球方");
+            turns.push((order.server, order.receiver));
+            play(&mut score, &config, &[0, 1]);
+        }
+        // A1→B1、B1→A2、A2→B2、B2→A1，然后重新开始
+        assert_eq!(turns, [(0, 1), (1, 2), (2, 3), (3, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn doubles_strikers_alternate_between_partners() {
+        let config = config(true);
+        let mut score = MatchScore::new(&config);
+        play(&mut score, &config, &[0, 1]);
+        let order = score.doubles.unwrap();
+        let strikers = (0..5).map(|shot| order.striker(shot)).collect::<Vec<_>>();
+        assert_eq!(strikers, [1, 2, 3, 0, 1]);
+        assert_eq!(score.expected_striker(), Some(1));
+    }
+
+    #[test]
+    fn doubles_next_game_starts_with_previous_receiver() {
+        let config = config(true);
+        let mut score = MatchScore::new(&config);
+        play(&mut score, &config, &[0; 11]);
+        assert_eq!(score.phase, MatchPhase::GameOver { winner: 0 });
+
+        let order = score.doubles.unwrap();
+        assert_eq!((order.server, order.receiver), (1, 0));
+        assert_eq!(score.server, 1);
+        play(&mut score, &config, &[0, 0]);
+        let order = score.doubles.unwrap();
+        assert_eq!((order.server, order.receiver), (0, 3));
+    }
+}
@@ -0,0 +1,591 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+use crate::game::LocalVersus;
+use crate::game::input::{ControlScheme, PlayerControls};
+use crate::game::scoring::{
+    MatchConfig, MatchPhase, MatchScore, MatchStats, PointReason, PointScored, RestartMatch,
+    ScoringSet, ShotEvent,
+};
+use crate::game::utils::racket_physics::RacketHitEvent;
+use crate::game::utils::{Ball, Table};
+use crate::locale::Locale;
+
+/// 没有设置 `PONG_STATS_PATH` 时的存档位置
+const DEFAULT_STATS_PATH: &str = "./pong-stats.json";
+
+/// 玩家档案，按座位（或玩家输入的名字）和控制设备区分，例如 "player-1 (Phone)"。
+/// id 不随界面语言变化，`name` 是最近一次使用时显示的名字
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub id: String,
+    pub name: String,
+    pub device: String,
+    pub first_seen: u64,
+    pub last_seen: u64,
+}
+
+/// 一次击球，`landing` 为之后第一次落台的位置（x, z）
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ShotRecord {
+    pub player: usize,
+    pub speed: f32,
+    pub landing: Option<[f32; 2]>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RallyRecord {
+    pub winner: usize,
+    pub reason: PointReason,
+    pub shots: Vec<ShotRecord>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    #[default]
+    Local,
+    Online,
+}
+
+/// 一场比赛，中途退出的比赛 `winner` 为 `None`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MatchRecord {
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub mode: MatchMode,
+    /// 双方的档案 id
+    pub players: [String; 2],
+    pub winner: Option<usize>,
+    pub games: [u32; 2],
+    pub game_scores: Vec<[u32; 2]>,
+    pub stats: MatchStats,
+    /// 联机客户端的比分来自主机，没有逐分记录
+    pub rallies: Vec<RallyRecord>,
+}
+
+/// 一次练习
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PracticeRecord {
+    pub started_at: u64,
+    pub duration: f32,
+    pub shots: u32,
+    pub average_speed: f32,
+    pub fastest_shot: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct StatsDb {
+    pub profiles: Vec<PlayerProfile>,
+    pub matches: Vec<MatchRecord>,
+    pub practice: Vec<PracticeRecord>,
+}
+
+impl PlayerProfile {
+    /// 界面上显示的名字和设备
+    pub fn label(&self, locale: &Locale) -> String {
+        format!("{} ({})", self.name, locale.text(device_locale_key(&self.device)))
+    }
+}
+
+/// 档案里的设备是固定的英文 id，显示时换成界面语言的文本 id
+fn device_locale_key(device: &str) -> &str {
+    match device {
+        "Online" => "stats-device-online",
+        "Wall" => "stats-device-wall",
+        _ => ControlScheme::ALL
+            .iter()
+            .find(|scheme| scheme.label() == device)
+            .map_or(device, |scheme| scheme.locale_key()),
+    }
+}
+
+impl StatsDb {
+    /// `key` 是座位或玩家输入的名字，与 `device` 一起组成档案 id；`name` 只用于显示
+    fn touch_profile(&mut self, key: &str, name: &str, device: &str, now: u64) -> String {
+        let id = format!("{} ({})", key, device);
+        match self.profiles.iter_mut().find(|profile| profile.id == id) {
+            Some(profile) => {
+                profile.name = name.to_string();
+                profile.last_seen = now;
+            }
+            None => self.profiles.push(PlayerProfile {
+                id: id.clone(),
+                name: name.to_string(),
+                device: device.to_string(),
+                first_seen: now,
+                last_seen: now,
+            }),
+        }
+        id
+    }
+
+    /// 档案 id 对应的显示名字，找不到档案时显示 id
+    pub fn profile_label(&self, id: &str, locale: &Locale) -> String {
+        self.profiles
+            .iter()
+            .find(|profile| profile.id == id)
+            .map_or_else(|| id.to_string(), |profile| profile.label(locale))
+    }
+
+    /// 档案参加并打完的比赛数和胜场数
+    pub fn record_of(&self, profile: &str) -> (u32, u32) {
+        let mut played = 0;
+        let mut won = 0;
+        for record in &self.matches {
+            let Some(player) = record.players.iter().position(|id| id == profile) else {
+                continue;
+            };
+            if let Some(winner) = record.winner {
+                played += 1;
+                if winner == player {
+                    won += 1;
+                }
+            }
+        }
+        (played, won)
+    }
+}
+
+/// 本地战绩存档，启动时从 `PONG_STATS_PATH` 读取，每记录一场比赛或练习后写回
+#[derive(Resource)]
+pub struct StatsStore {
+    path: PathBuf,
+    pub db: StatsDb,
+    /// 损坏的存档没能改名时为 `false`，不再写回，避免覆盖用户的历史记录
+    writable: bool,
+}
+
+impl StatsStore {
+    pub fn from_env() -> Self {
+        let path = PathBuf::from(
+            env::var("PONG_STATS_PATH").unwrap_or_else(|_| DEFAULT_STATS_PATH.to_string()),
+        );
+        let mut writable = true;
+        let db = match fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice::<StatsDb>(&bytes) {
+                Ok(db) => {
+                    info!(
+                        "📊 读取战绩: {}（{} 场比赛，{} 次练习）",
+                        path.display(),
+                        db.matches.len(),
+                        db.practice.len()
+                    );
+                    db
+                }
+                Err(e) => {
+                    error!("❌ 战绩文件格式错误 {}: {}", path.display(), e);
+                    writable = set_aside_corrupt(&path);
+                    StatsDb::default()
+                }
+            },
+            Err(_) => StatsDb::default(),
+        };
+        StatsStore { path, db, writable }
+    }
+
+    /// 先写临时文件再替换，避免写到一半时退出损坏存档
+    fn save(&self) {
+        if !self.writable {
+            return;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        let result = serde_json::to_vec_pretty(&self.db)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| fs::write(&tmp, bytes).map_err(|e| e.to_string()))
+            .and_then(|_| fs::rename(&tmp, &self.path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("❌ 无法保存战绩 {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// 进行中的比赛
+#[derive(Default)]
+struct OpenMatch {
+    started_at: u64,
+    mode: MatchMode,
+    players: [String; 2],
+    /// 整场结束后已经写入存档，等待下一场开始
+    closed: bool,
+    score: MatchScore,
+    rallies: Vec<RallyRecord>,
+    shots: Vec<ShotRecord>,
+}
+
+/// 进行中的练习
+struct OpenPractice {
+    started_at: u64,
+    elapsed: f32,
+    shots: u32,
+    total_speed: f32,
+    fastest_shot: f32,
+}
+
+#[derive(Resource, Default)]
+struct StatsRecorder {
+    current: Option<OpenMatch>,
+    practice: Option<OpenPractice>,
+}
+
+/// 无法解析的存档改名为 `<文件名>.corrupt-<时间戳>` 留给用户处理，新的存档从空白开始。
+/// 改名失败时返回 `false`，调用方不应再写回原文件
+pub fn set_aside_corrupt(path: &Path) -> bool {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".corrupt-{}", now()));
+    let backup = PathBuf::from(backup);
+    match fs::rename(path, &backup) {
+        Ok(()) => {
+            warn!("⚠️ 已将无法读取的文件另存为 {}", backup.display());
+            true
+        }
+        Err(e) => {
+            error!("❌ 无法另存 {}，本次运行不会写回该文件: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn stats_plugin(app: &mut App) {
+    app.insert_resource(StatsStore::from_env())
+        .init_resource::<StatsRecorder>()
+        .add_systems(
+            Update,
+            begin_match_record.run_if(resource_added::<MatchConfig>),
+        )
+        .add_systems(
+            Update,
+            record_match
+                .after(ScoringSet)
+                .run_if(resource_exists::<MatchConfig>)
+                .run_if(not(in_state(GameState::Menu))),
+        )
+        .add_systems(OnEnter(GameState::GamePracticeRunning), begin_practice_record)
+        .add_systems(
+            Update,
+            record_practice.run_if(in_state(GameState::GamePracticeRunning)),
+        )
+        .add_systems(OnEnter(GameState::Menu), close_records);
+}
+
+fn begin_match_record(
+    config: Res<MatchConfig>,
+    controls: Res<PlayerControls>,
+    versus: Option<Res<LocalVersus>>,
+    game_state: Res<State<GameState>>,
+    mut store: ResMut<StatsStore>,
+    mut recorder: ResMut<StatsRecorder>,
+) {
+    let mode = match game_state.get() {
+        GameState::GameOnlineIniting | GameState::GameOnlineRunning => MatchMode::Online,
+        _ => MatchMode::Local,
+    };
+    let time = now();
+    let tournament = versus.as_ref().is_some_and(|versus| versus.tournament.is_some());
+    let players = [0, 1].map(|player| {
+        let device = if player == config.local_player {
+            controls.schemes[0].label()
+        } else if mode == MatchMode::Online {
+            "Online"
+        } else if config.can_serve[player] {
+            controls.match_scheme(versus.as_deref(), player).0.label()
+        } else {
+            "Wall"
+        };
+        let key = profile_key(&config, mode, tournament, player);
+        store.db.touch_profile(&key, &config.names[player], device, time)
+    });
+    recorder.current = Some(OpenMatch {
+        started_at: time,
+        mode,
+        players,
+        ..default()
+    });
+}
+
+/// 档案 id 中不随语言变化的部分：锦标赛中是玩家输入的名字，其他比赛按座位区分
+fn profile_key(config: &MatchConfig, mode: MatchMode, tournament: bool, player: usize) -> String {
+    if mode == MatchMode::Online {
+        ["host", "guest"][player].to_string()
+    } else if tournament {
+        config.names[player].clone()
+    } else if !config.can_serve[player] {
+        "wall".to_string()
+    } else {
+        format!("player-{}", player + 1)
+    }
+}
+
+/// 比赛中需要记录的事件
+#[derive(SystemParam)]
+struct MatchEvents<'w, 's> {
+    restarts: EventReader<'w, 's, RestartMatch>,
+    shots: EventReader<'w, 's, ShotEvent>,
+    points: EventReader<'w, 's, PointScored>,
+    collisions: EventReader<'w, 's, CollisionEvent>,
+}
+
+/// 记录每一分的击球和落点；整场结束或重新开始时写入存档
+fn record_match(
+    mut store: ResMut<StatsStore>,
+    mut recorder: ResMut<StatsRecorder>,
+    score: Res<MatchScore>,
+    mut events: MatchEvents,
+    ball_query: Query<(Entity, &Transform), With<Ball>>,
+    table_q: Query<(), With<Table>>,
+) {
+    let Some(open) = recorder.current.as_mut() else {
+        return;
+    };
+
+    // 重新开始前的比赛按上一帧的比分结算；联机客户端没有重新开始事件，比分从整场结束变回进行中即为新的一场
+    let rematch = open.closed && !matches!(score.phase, MatchPhase::MatchOver { .. });
+    if events.restarts.read().count() > 0 || rematch {
+        let next = OpenMatch {
+            started_at: now(),
+            mode: open.mode,
+            players: open.players.clone(),
+            ..default()
+        };
+        let finished = std::mem::replace(open, next);
+        close_match(&mut store, finished);
+    }
+
+    for shot in events.shots.read() {
+        open.shots.push(ShotRecord {
+            player: shot.player,
+            speed: shot.speed,
+            landing: None,
+        });
+    }
+    if let Ok((ball, transform)) = ball_query.get_single() {
+        for event in events.collisions.read() {
+            let CollisionEvent::Started(e1, e2, _) = event else {
+                continue;
+            };
+            let hit_table =
+                (*e1 == ball && table_q.contains(*e2)) || (*e2 == ball && table_q.contains(*e1));
+            if !hit_table {
+                continue;
+            }
+            if let Some(shot) = open.shots.last_mut().filter(|shot| shot.landing.is_none()) {
+                shot.landing = Some([transform.translation.x, transform.translation.z]);
+            }
+        }
+    }
+    for point in events.points.read() {
+        open.rallies.push(RallyRecord {
+            winner: point.winner,
+            reason: point.reason,
+            shots: std::mem::take(&mut open.shots),
+        });
+    }
+
+    open.score.clone_from(&score);
+    if matches!(score.phase, MatchPhase::MatchOver { .. }) && !open.closed {
+        let finished = OpenMatch {
+            started_at: open.started_at,
+            mode: open.mode,
+            players: open.players.clone(),
+            closed: false,
+            score: open.score.clone(),
+            rallies: std::mem::take(&mut open.rallies),
+            shots: Vec::new(),
+        };
+        close_match(&mut store, finished);
+        open.closed = true;
+    }
+}
+
+/// 写入一场比赛，已经写入过的和一分都没打的比赛不记录
+fn close_match(store: &mut StatsStore, open: OpenMatch) {
+    let score = open.score;
+    if open.closed || score.stats.total_points == 0 {
+        return;
+    }
+    let winner = match score.phase {
+        MatchPhase::MatchOver { winner } => Some(winner),
+        _ => None,
+    };
+    info!(
+        "📊 记录比赛: {} vs {}，局数 {}-{}{}",
+        open.players[0],
+        open.players[1],
+        score.games[0],
+        score.games[1],
+        if winner.is_some() { "" } else { "（未完成）" }
+    );
+    store.db.matches.push(MatchRecord {
+        started_at: open.started_at,
+        finished_at: now(),
+        mode: open.mode,
+        players: open.players,
+        winner,
+        games: score.games,
+        game_scores: score.finished_games,
+        stats: score.stats,
+        rallies: open.rallies,
+    });
+    store.save();
+}
+
+fn begin_practice_record(mut recorder: ResMut<StatsRecorder>) {
+    recorder.practice = Some(OpenPractice {
+        started_at: now(),
+        elapsed: 0.0,
+        shots: 0,
+        total_speed: 0.0,
+        fastest_shot: 0.0,
+    });
+}
+
+fn record_practice(
+    time: Res<Time>,
+    mut recorder: ResMut<StatsRecorder>,
+    mut hits: EventReader<RacketHitEvent>,
+) {
+    let Some(practice) = recorder.practice.as_mut() else {
+        return;
+    };
+    practice.elapsed += time.delta_secs();
+    for hit in hits.read() {
+        practice.shots += 1;
+        practice.total_speed += hit.speed;
+        practice.fastest_shot = practice.fastest_shot.max(hit.speed);
+    }
+}
+
+fn close_records(mut store: ResMut<StatsStore>, mut recorder: ResMut<StatsRecorder>) {
+    if let Some(open) = recorder.current.take() {
+        close_match(&mut store, open);
+    }
+    if let Some(practice) = recorder.practice.take() {
+        if practice.shots == 0 {
+            return;
+        }
+        info!(
+            "📊 记录练习: {} 次击球，{:.0} 秒",
+            practice.shots, practice.elapsed
+        );
+        store.db.practice.push(PracticeRecord {
+            started_at: practice.started_at,
+            duration: practice.elapsed,
+            shots: practice.shots,
+            average_speed: practice.total_speed / practice.shots as f32,
+            fastest_shot: practice.fastest_shot,
+        });
+        store.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(players: [&str; 2], winner: Option<usize>) -> MatchRecord {
+        MatchRecord {
+            started_at: 0,
+            finished_at: 0,
+            mode: MatchMode::Local,
+            players: players.map(str::to_string),
+            winner,
+            games: [0, 0],
+            game_scores: Vec::new(),
+            stats: MatchStats::default(),
+            rallies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn touch_profile_keeps_id_when_display_name_changes() {
+        let mut db = StatsDb::default();
+        let id = db.touch_profile("player-1", "Player 1", "Phone", 10);
+        assert_eq!(id, "player-1 (Phone)");
+
+        // 切换界面语言后同一座位仍是同一个档案，只更新显示的名字
+        let again = db.touch_profile("player-1", "玩家 1", "Phone", 20);
+        assert_eq!(again, id);
+        assert_eq!(db.profiles.len(), 1);
+        let profile = &db.profiles[0];
+        assert_eq!((profile.first_seen, profile.last_seen), (10, 20));
+        assert_eq!(profile.name, "玩家 1");
+        assert_eq!(profile.device, "Phone");
+
+        db.touch_profile("player-1", "Player 1", "Keyboard", 30);
+        assert_eq!(db.profiles.len(), 2, "不同设备是不同的档案");
+    }
+
+    #[test]
+    fn devices_are_shown_through_locale_keys() {
+        assert_eq!(device_locale_key("Phone"), "scheme-phone");
+        assert_eq!(device_locale_key("AI"), "scheme-ai");
+        assert_eq!(device_locale_key("Online"), "stats-device-online");
+        assert_eq!(device_locale_key("Wall"), "stats-device-wall");
+        // 未知的设备原样显示
+        assert_eq!(device_locale_key("Joystick"), "Joystick");
+    }
+
+    #[test]
+    fn record_of_counts_only_finished_matches() {
+        let db = StatsDb {
+            matches: vec![
+                record(["a", "b"], Some(0)),
+                record(["b", "a"], Some(0)),
+                record(["a", "c"], Some(0)),
+                record(["a", "b"], None),
+            ],
+            ..default()
+        };
+        assert_eq!(db.record_of("a"), (3, 2));
+        assert_eq!(db.record_of("b"), (2, 1));
+        assert_eq!(db.record_of("c"), (1, 0));
+        assert_eq!(db.record_of("d"), (0, 0));
+    }
+
+    #[test]
+    fn profile_key_ignores_localized_names() {
+        let mut config = MatchConfig::local(["玩家 1".to_string(), "玩家 2".to_string()]);
+        assert_eq!(profile_key(&config, MatchMode::Local, false, 1), "player-2");
+        assert_eq!(profile_key(&config, MatchMode::Online, false, 0), "host");
+        assert_eq!(profile_key(&config, MatchMode::Local, true, 0), "玩家 1");
+        config.can_serve[1] = false;
+        assert_eq!(profile_key(&config, MatchMode::Local, false, 1), "wall");
+    }
+
+    #[test]
+    fn corrupt_file_is_set_aside() {
+        let dir = env::temp_dir().join(format!("pong-stats-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stats.json");
+        fs::write(&path, b"{ not json").unwrap();
+
+        assert!(set_aside_corrupt(&path));
+        assert!(!path.exists());
+        let backups: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with("stats.json.corrupt-"));
+        assert_eq!(fs::read(dir.join(&backups[0])).unwrap(), b"{ not json");
+
+        // 文件不存在时改名失败，不能再写回
+        assert!(!set_aside_corrupt(&path));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,465 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::GameState;
+use crate::game::LocalVersus;
+use crate::game::scoring::{MatchPhase, MatchScore, ScoringSet};
+use crate::game::stats;
+
+/// 没有设置 `PONG_TOURNAMENT_PATH` 时的存档位置
+const DEFAULT_TOURNAMENT_PATH: &str = "./pong-tournament.json";
+/// 参赛人数上限
+pub const MAX_PLAYERS: usize = 16;
+
+/// 赛制
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TournamentFormat {
+    /// 单败淘汰，人数不是 2 的幂时前几号种子首轮轮空
+    #[default]
+    SingleElimination,
+    /// 单循环，每两名玩家之间打一场
+    RoundRobin,
+}
+
+impl TournamentFormat {
+    pub const ALL: [TournamentFormat; 2] =
+        [TournamentFormat::SingleElimination, TournamentFormat::RoundRobin];
+
+    /// 界面上显示的文本 id
+    pub fn locale_key(&self) -> &'static str {
+        match self {
+            TournamentFormat::SingleElimination => "tournament-single-elimination",
+            TournamentFormat::RoundRobin => "tournament-round-robin",
+        }
+    }
+}
+
+/// 一场对阵，`players` 为玩家序号，淘汰赛中 `None` 表示轮空或尚未决出
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BracketMatch {
+    pub players: [Option<usize>; 2],
+    /// 胜者在 `players` 中的位置
+    pub winner: Option<usize>,
+    pub games: [u32; 2],
+}
+
+impl BracketMatch {
+    /// 双方都已确定、还没有结果
+    pub fn is_ready(&self) -> bool {
+        self.winner.is_none() && self.players.iter().all(Option::is_some)
+    }
+
+    pub fn winning_player(&self) -> Option<usize> {
+        self.winner.and_then(|slot| self.players[slot])
+    }
+}
+
+/// 循环赛积分榜中的一行
+#[derive(Clone, Debug)]
+pub struct Standing {
+    pub player: usize,
+    pub won: u32,
+    pub lost: u32,
+    /// 胜局数减负局数
+    pub game_diff: i32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tournament {
+    pub format: TournamentFormat,
+    pub players: Vec<String>,
+    /// 每一轮的对阵，淘汰赛下一轮第 i 场由本轮第 2i、2i+1 场的胜者组成
+    pub rounds: Vec<Vec<BracketMatch>>,
+    pub created_at: u64,
+}
+
+impl Tournament {
+    /// 打乱顺序后生成对阵
+    pub fn new(format: TournamentFormat, mut players: Vec<String>) -> Self {
+        players.shuffle(&mut rand::rng());
+        let rounds = match format {
+            TournamentFormat::SingleElimination => elimination_rounds(players.len()),
+            TournamentFormat::RoundRobin => round_robin_rounds(players.len()),
+        };
+        let mut tournament = Tournament {
+            format,
+            players,
+            rounds,
+            created_at: stats::now(),
+        };
+        // 首轮轮空的玩家直接晋级
+        if tournament.format == TournamentFormat::SingleElimination {
+            for index in 0..tournament.rounds[0].len() {
+                let bracket = &mut tournament.rounds[0][index];
+                if let [Some(_), None] = bracket.players {
+                    bracket.winner = Some(0);
+                    tournament.advance(0, index);
+                }
+            }
+        }
+        tournament
+    }
+
+    /// 下一场要打的比赛（轮次，场次）
+    pub fn next_match(&self) -> Option<(usize, usize)> {
+        self.rounds.iter().enumerate().find_map(|(round, matches)| {
+            matches
+                .iter()
+                .position(BracketMatch::is_ready)
+                .map(|index| (round, index))
+        })
+    }
+
+    pub fn name(&self, player: Option<usize>) -> Option<&str> {
+        player.and_then(|player| self.players.get(player)).map(String::as_str)
+    }
+
+    /// 记录一场比赛的结果，淘汰赛中胜者进入下一轮。已经有结果的对阵不会被覆盖
+    pub fn record(&mut self, round: usize, index: usize, winner: usize, games: [u32; 2]) -> bool {
+        let Some(bracket) = self.rounds.get_mut(round).and_then(|matches| matches.get_mut(index))
+        else {
+            return false;
+        };
+        if !bracket.is_ready() {
+            return false;
+        }
+        bracket.winner = Some(winner);
+        bracket.games = games;
+        if self.format == TournamentFormat::SingleElimination {
+            self.advance(round, index);
+        }
+        true
+    }
+
+    fn advance(&mut self, round: usize, index: usize) {
+        let winner = self.rounds[round][index].winning_player();
+        if let Some(next) = self.rounds.get_mut(round + 1) {
+            next[index / 2].players[index % 2] = winner;
+        }
+    }
+
+    pub fn played(&self) -> usize {
+        self.rounds.iter().flatten().filter(|bracket| bracket.winner.is_some()).count()
+    }
+
+    pub fn total(&self) -> usize {
+        self.rounds.iter().map(Vec::len).sum()
+    }
+
+    /// 循环赛按胜场、净胜局排序
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings = (0..self.players.len())
+            .map(|player| Standing {
+                player,
+                won: 0,
+                lost: 0,
+                game_diff: 0,
+            })
+            .collect::<Vec<_>>();
+        for bracket in self.rounds.iter().flatten() {
+            let (Some(winner), [Some(a), Some(b)]) = (bracket.winner, bracket.players) else {
+                continue;
+            };
+            for (slot, player) in [a, b].into_iter().enumerate() {
+                let standing = &mut standings[player];
+                if slot == winner {
+                    standing.won += 1;
+                } else {
+                    standing.lost += 1;
+                }
+                standing.game_diff += bracket.games[slot] as i32 - bracket.games[1 - slot] as i32;
+            }
+        }
+        standings.sort_by(|a, b| b.won.cmp(&a.won).then(b.game_diff.cmp(&a.game_diff)));
+        standings
+    }
+
+    /// 所有比赛结束后的冠军
+    pub fn champion(&self) -> Option<usize> {
+        if self.next_match().is_some() {
+            return None;
+        }
+        match self.format {
+            TournamentFormat::SingleElimination => {
+                self.rounds.last()?.first()?.winning_player()
+            }
+            TournamentFormat::RoundRobin => self.standings().first().map(|standing| standing.player),
+        }
+    }
+}
+
+/// 按种子排位生成淘汰赛：1 号和 2 号种子只会在决赛相遇，轮空都排给靠前的种子
+fn elimination_rounds(players: usize) -> Vec<Vec<BracketMatch>> {
+    let size = players.next_power_of_two().max(2);
+    let mut seeds = vec![0];
+    while seeds.len() < size {
+        let len = seeds.len() * 2;
+        seeds = seeds.iter().flat_map(|seed| [*seed, len - 1 - seed]).collect();
+    }
+    let first = seeds
+        .chunks(2)
+        .map(|pair| BracketMatch {
+            players: [0, 1].map(|slot| Some(pair[slot]).filter(|seed| *seed < players)),
+            ..default()
+        })
+        .collect::<Vec<_>>();
+    let mut rounds = vec![first];
+    while rounds.last().is_some_and(|round| round.len() > 1) {
+        let len = rounds.last().map_or(0, Vec::len) / 2;
+        rounds.push(vec![BracketMatch::default(); len]);
+    }
+    rounds
+}
+
+/// 轮转法生成循环赛，人数为奇数时每轮有一人轮空，轮空的对阵不记录
+fn round_robin_rounds(players: usize) -> Vec<Vec<BracketMatch>> {
+    let mut slots = (0..players).map(Some).collect::<Vec<_>>();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+    let half = slots.len() / 2;
+    (0..slots.len().saturating_sub(1))
+        .map(|_| {
+            let round = (0..half)
+                .filter_map(|i| match (slots[i], slots[slots.len() - 1 - i]) {
+                    (Some(a), Some(b)) => Some(BracketMatch {
+                        players: [Some(a), Some(b)],
+                        ..default()
+                    }),
+                    _ => None,
+                })
+                .collect();
+            // 第一个位置不动，其余位置顺时针转一格
+            slots[1..].rotate_right(1);
+            round
+        })
+        .collect()
+}
+
+/// 进行中的锦标赛存档，启动时从 `PONG_TOURNAMENT_PATH` 读取，每次修改后写回，
+/// 重启游戏后可以继续比赛
+#[derive(Resource)]
+pub struct TournamentStore {
+    path: PathBuf,
+    pub tournament: Option<Tournament>,
+    /// 损坏的存档没能改名时为 `false`，不再写回
+    writable: bool,
+}
+
+impl TournamentStore {
+    pub fn from_env() -> Self {
+        let path = PathBuf::from(
+            env::var("PONG_TOURNAMENT_PATH")
+                .unwrap_or_else(|_| DEFAULT_TOURNAMENT_PATH.to_string()),
+        );
+        let mut writable = true;
+        let tournament = match fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice::<Option<Tournament>>(&bytes) {
+                Ok(tournament) => {
+                    if let Some(tournament) = &tournament {
+                        info!(
+                            "🏆 读取锦标赛: {}（{} 名玩家，已完成 {}/{} 场）",
+                            path.display(),
+                            tournament.players.len(),
+                            tournament.played(),
+                            tournament.total()
+                        );
+                    }
+                    tournament
+                }
+                Err(e) => {
+                    error!("❌ 锦标赛文件格式错误 {}: {}", path.display(), e);
+                    writable = stats::set_aside_corrupt(&path);
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+        TournamentStore {
+            path,
+            tournament,
+            writable,
+        }
+    }
+
+    /// 替换或清除锦标赛并写回存档
+    pub fn set(&mut self, tournament: Option<Tournament>) {
+        self.tournament = tournament;
+        self.save();
+    }
+
+    /// 先写临时文件再替换，避免写到一半时退出损坏存档
+    fn save(&self) {
+        if !self.writable {
+            return;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        let result = serde_json::to_vec_pretty(&self.tournament)
+            .map_err(|e| e.to_string())
+            .and_then(|bytes| fs::write(&tmp, bytes).map_err(|e| e.to_string()))
+            .and_then(|_| fs::rename(&tmp, &self.path).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            error!("❌ 无法保存锦标赛 {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+pub fn tournament_plugin(app: &mut App) {
+    app.insert_resource(TournamentStore::from_env()).add_systems(
+        Update,
+        record_tournament_match
+            .after(ScoringSet)
+            .run_if(resource_exists::<LocalVersus>)
+            .run_if(in_state(GameState::GameRunning)),
+    );
+}
+
+/// 锦标赛中的比赛结束后自动记录结果，重新开始后再打完的比赛不会覆盖已记录的结果
+fn record_tournament_match(
+    versus: Res<LocalVersus>,
+    score: Res<MatchScore>,
+    mut store: ResMut<TournamentStore>,
+) {
+    let (Some((round, index)), MatchPhase::MatchOver { winner }) = (versus.tournament, score.phase)
+    else {
+        return;
+    };
+    let Some(tournament) = store.tournament.as_mut() else {
+        return;
+    };
+    if !tournament.record(round, index, winner, score.games) {
+        return;
+    }
+    info!(
+        "🏆 记录锦标赛比赛: {} 获胜，局数 {}-{}",
+        versus.names[winner], score.games[0], score.games[1]
+    );
+    if let Some(champion) = tournament.champion() {
+        info!("🏆 锦标赛冠军: {}", tournament.players[champion]);
+    }
+    store.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn tournament(format: TournamentFormat, players: usize) -> Tournament {
+        Tournament::new(format, (0..players).map(|i| format!("P{i}")).collect())
+    }
+
+    fn pairs(round: &[BracketMatch]) -> Vec<[Option<usize>; 2]> {
+        round.iter().map(|bracket| bracket.players).collect()
+    }
+
+    #[test]
+    fn top_seeds_meet_only_in_final() {
+        let rounds = elimination_rounds(8);
+        assert_eq!(rounds.iter().map(Vec::len).collect::<Vec<_>>(), [4, 2, 1]);
+        assert_eq!(
+            pairs(&rounds[0]),
+            [
+                [Some(0), Some(7)],
+                [Some(3), Some(4)],
+                [Some(1), Some(6)],
+                [Some(2), Some(5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn byes_go_to_top_seeds_and_advance() {
+        let tournament = tournament(TournamentFormat::SingleElimination, 5);
+        assert_eq!(
+            pairs(&tournament.rounds[0]),
+            [
+                [Some(0), None],
+                [Some(3), Some(4)],
+                [Some(1), None],
+                [Some(2), None],
+            ]
+        );
+        assert_eq!(
+            pairs(&tournament.rounds[1]),
+            [[Some(0), None], [Some(1), Some(2)]]
+        );
+        assert_eq!(tournament.played(), 3);
+        assert_eq!(tournament.total(), 7);
+        assert_eq!(tournament.next_match(), Some((0, 1)));
+    }
+
+    #[test]
+    fn elimination_winner_becomes_champion() {
+        let mut tournament = tournament(TournamentFormat::SingleElimination, 4);
+        assert!(!tournament.record(1, 0, 0, [2, 0]), "决赛双方还没有决出");
+        assert_eq!(tournament.champion(), None);
+
+        while let Some((round, index)) = tournament.next_match() {
+            let players = tournament.rounds[round][index].players;
+            let winner = if players[1] == Some(3) { 1 } else { 0 };
+            assert!(tournament.record(round, index, winner, [2, 1]));
+            assert!(
+                !tournament.record(round, index, 1 - winner, [0, 2]),
+                "结果不会被覆盖"
+            );
+        }
+        assert_eq!(tournament.champion(), Some(3));
+        assert_eq!(tournament.played(), tournament.total());
+    }
+
+    #[test]
+    fn round_robin_pairs_everyone_once() {
+        for players in [2, 4, 5, 8] {
+            let tournament = tournament(TournamentFormat::RoundRobin, players);
+            let rounds = players + players % 2 - 1;
+            assert_eq!(tournament.rounds.len(), rounds);
+            assert_eq!(tournament.total(), players * (players - 1) / 2);
+
+            let mut seen = HashSet::new();
+            for round in &tournament.rounds {
+                let mut busy = HashSet::new();
+                for bracket in round {
+                    let [Some(a), Some(b)] = bracket.players else {
+                        panic!("循环赛不记录轮空的对阵");
+                    };
+                    assert!(busy.insert(a) && busy.insert(b), "每轮每人最多打一场");
+                    assert!(seen.insert((a.min(b), a.max(b))), "{a} 和 {b} 重复对阵");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn standings_rank_by_wins_then_game_difference() {
+        let mut tournament = tournament(TournamentFormat::RoundRobin, 3);
+        while let Some((round, index)) = tournament.next_match() {
+            let players = tournament.rounds[round][index].players;
+            // 0 号全胜；1 号和 2 号各赢一场，2 号赢得更干脆
+            let (winner, games) = match players {
+                [Some(0), _] => (0, [2, 1]),
+                [_, Some(0)] => (1, [1, 2]),
+                [Some(2), _] => (0, [2, 0]),
+                _ => (1, [0, 2]),
+            };
+            tournament.record(round, index, winner, games);
+        }
+
+        let standings = tournament.standings();
+        let order = standings
+            .iter()
+            .map(|standing| standing.player)
+            .collect::<Vec<_>>();
+        assert_eq!(order, [0, 2, 1]);
+        assert_eq!((standings[0].won, standings[0].lost), (2, 0));
+        assert_eq!(standings[1].game_diff, 1);
+        assert_eq!(tournament.champion(), Some(0));
+    }
+}
@@ -0,0 +1,60 @@
+use std::env;
+
+use bevy::log::info;
+use warp::Filter;
+use warp::filters::BoxedFilter;
+use warp::reply::{Reply, Response};
+
+/// 控制器网页的构建产物目录，可通过 `PONG_DIST_DIR` 覆盖
+#[cfg(not(feature = "embed-controller"))]
+const DEFAULT_DIST_DIR: &str = "./dist";
+
+/// 编译时打包进二进制的 pong-controller 构建产物（`--features embed-controller`）
+#[cfg(feature = "embed-controller")]
+#[derive(rust_embed::Embed)]
+#[folder = "$CARGO_MANIFEST_DIR/../pong-controller/dist"]
+struct ControllerAssets;
+
+/// 控制器网页的静态文件路由。
+/// 启用 `embed-controller` 且未设置 `PONG_DIST_DIR` 时从内存提供，否则读取磁盘目录。
+pub fn static_files() -> BoxedFilter<(Response,)> {
+    match env::var("PONG_DIST_DIR") {
+        Ok(dir) => disk_files(dir),
+        Err(_) => default_files(),
+    }
+}
+
+fn disk_files(dir: String) -> BoxedFilter<(Response,)> {
+    info!("📁 控制器网页目录: {}", dir);
+    warp::fs::dir(dir)
+        .map(|file: warp::fs::File| file.into_response())
+        .boxed()
+}
+
+#[cfg(not(feature = "embed-controller"))]
+fn default_files() -> BoxedFilter<(Response,)> {
+    disk_files(DEFAULT_DIST_DIR.to_string())
+}
+
+#[cfg(feature = "embed-controller")]
+fn default_files() -> BoxedFilter<(Response,)> {
+    info!("📦 使用内置的控制器网页");
+    warp::get()
+        .and(warp::path::tail())
+        .and_then(|tail: warp::path::Tail| async move { serve_embedded(tail.as_str()) })
+        .boxed()
+}
+
+#[cfg(feature = "embed-controller")]
+fn serve_embedded(path: &str) -> Result<Response, warp::Rejection> {
+    use warp::http::header::{CONTENT_TYPE, HeaderValue};
+
+    let path = if path.is_empty() { "index.html" } else { path };
+    let asset = ControllerAssets::get(path).ok_or_else(warp::reject::not_found)?;
+
+    let mut response = Response::new(asset.data.into_owned().into());
+    if let Ok(mime) = HeaderValue::from_str(asset.metadata.mimetype()) {
+        response.headers_mut().insert(CONTENT_TYPE, mime);
+    }
+    Ok(response)
+}
@@ -1,20 +1,264 @@
-use crate::game::utils::WsRuntime;
+use crate::game::utils::controller_assets;
+use crate::game::utils::discovery::{self, MDNS_HOST};
+use crate::game::utils::spectator::{self, SpectatorFeed};
+use crate::game::utils::tls::{CertReload, TlsMode};
+use crate::game::utils::ws_handler::{self, PROTOCOL_VERSION};
+use crate::game::utils::{
+    ControllerSeats, RacketCommandQueue, RoomRegistry, ServerStatus, SharedGameStatus, WsRuntime,
+};
+use crate::locale::LocaleMessage;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use mdns_sd::ServiceDaemon;
+use serde::Serialize;
+use warp::Filter;
 
-use std::env;
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, SocketAddr},
+};
 
-pub fn start_controller_server(rt: Res<WsRuntime>) {
+#[derive(Serialize)]
+struct ServerInfo {
+    name: &'static str,
+    version: &'static str,
+    protocol: u32,
+    controller_url: String,
+    ws_url: String,
+    spectate_url: String,
+    controllers: usize,
+}
+
+#[derive(Serialize)]
+struct RoomInfo {
+    code: String,
+    controllers: usize,
+}
+
+/// 同一端口上的全部路由：
+/// - `/ws`          控制器 WebSocket
+/// - `/spectate`    只读的观战 WebSocket，`?room=<房间码>` 观看指定房间
+/// - `/api/info`    服务器信息
+/// - `/api/status`  当前游戏状态
+/// - `/api/rooms`   房间列表
+/// - 其余路径       控制器网页静态文件
+fn routes(
+    tls_mode: TlsMode,
+    command_queue: RacketCommandQueue,
+    rooms: RoomRegistry,
+    seats: ControllerSeats,
+    status: ServerStatus,
+    game_status: SharedGameStatus,
+    feed: SpectatorFeed,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone + Send + Sync + 'static
+{
+    let ws_status = status.clone();
+    let ws_rooms = rooms.clone();
+    let ws_route = warp::path("ws")
+        .and(warp::path::end())
+        .and(warp::ws())
+        .and(warp::addr::remote())
+        .map(move |ws: warp::ws::Ws, addr: Option<SocketAddr>| {
+            let command_queue = command_queue.clone();
+            let rooms = ws_rooms.clone();
+            let seats = seats.clone();
+            let status = ws_status.clone();
+            ws.on_upgrade(move |socket| {
+                ws_handler::handle_socket(socket, addr, command_queue, rooms, seats, status)
+            })
+        });
+
+    let spectate_route = warp::path("spectate")
+        .and(warp::path::end())
+        .and(warp::ws())
+        .and(warp::query::<HashMap<String, String>>())
+        .map(move |ws: warp::ws::Ws, query: HashMap<String, String>| {
+            let feed = feed.clone();
+            let room = query.get("room").map(|code| code.trim().to_uppercase());
+            ws.on_upgrade(move |socket| spectator::handle_spectator(socket, room, feed))
+        });
+
+    let info_route = warp::path!("api" / "info").and(warp::get()).map(move || {
+        let info = status.0.lock().unwrap();
+        let host = format!("{}:{}", info.host, info.port);
+        warp::reply::json(&ServerInfo {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+            protocol: PROTOCOL_VERSION,
+            controller_url: format!("{}://{}", tls_mode.http_scheme(), host),
+            ws_url: format!("{}://{}/ws", tls_mode.ws_scheme(), host),
+            spectate_url: format!("{}://{}/spectate", tls_mode.ws_scheme(), host),
+            controllers: info.controllers,
+        })
+    });
+
+    let status_route = warp::path!("api" / "status")
+        .and(warp::get())
+        .map(move || warp::reply::json(&*game_status.0.lock().unwrap()));
+
+    let rooms_route = warp::path!("api" / "rooms").and(warp::get()).map(move || {
+        let list: Vec<RoomInfo> = rooms
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|room| RoomInfo {
+                code: room.code.clone(),
+                controllers: room.controllers,
+            })
+            .collect();
+        warp::reply::json(&list)
+    });
+
+    let static_route = controller_assets::static_files();
+
+    ws_route
+        .or(spectate_route)
+        .or(info_route)
+        .or(status_route)
+        .or(rooms_route)
+        .or(static_route)
+}
+
+/// 服务器绑定成功后在局域网广播，失败只影响自动发现，不影响连接
+fn advertise_lan(status: &ServerStatus, port: u16, tls_mode: &TlsMode) -> Option<ServiceDaemon> {
+    let ip: IpAddr = status.0.lock().unwrap().host.parse().ok()?;
+    match discovery::advertise(ip, port, tls_mode) {
+        Ok(daemon) => {
+            status.0.lock().unwrap().mdns_host = Some(MDNS_HOST.trim_end_matches('.').to_string());
+            Some(daemon)
+        }
+        Err(e) => {
+            warn!("⚠️ mDNS 广播失败: {:#}", e);
+            None
+        }
+    }
+}
+
+/// 服务器各路由与游戏共享的状态
+#[derive(SystemParam)]
+pub struct ControllerShared<'w> {
+    command_queue: Res<'w, RacketCommandQueue>,
+    rooms: Res<'w, RoomRegistry>,
+    seats: Res<'w, ControllerSeats>,
+    status: Res<'w, ServerStatus>,
+    game_status: Res<'w, SharedGameStatus>,
+    feed: Res<'w, SpectatorFeed>,
+}
+
+pub fn start_controller_server(
+    rt: Res<WsRuntime>,
+    tls_mode: Res<TlsMode>,
+    cert_reload: Option<Res<CertReload>>,
+    shared: ControllerShared,
+) {
+    let ControllerShared {
+        command_queue,
+        rooms,
+        seats,
+        status,
+        game_status,
+        feed,
+    } = shared;
+    let port = {
+        // 重新进入游戏时服务器仍在运行，不重复绑定端口
+        let mut info = status.0.lock().unwrap();
+        if info.started || matches!(*tls_mode, TlsMode::Unavailable(_)) {
+            return;
+        }
+        info.started = true;
+        info.port
+    };
+
+    let tls_mode = tls_mode.clone();
+    let mut cert_reload = cert_reload.map(|r| r.0.clone());
+    let status = status.clone();
+    let routes = routes(
+        tls_mode.clone(),
+        command_queue.clone(),
+        rooms.clone(),
+        seats.clone(),
+        status.clone(),
+        game_status.clone(),
+        feed.clone(),
+    );
     rt.0.spawn(async move {
-        let cert_url = env::var("SSL_CERT_PATH").expect("CERT_URL 环境变量未设置");
-        let key_url = env::var("SSL_KEY_PATH").expect("KEY_URL 环境变量未设置");
-        let controller_route = warp::fs::dir("./dist");
-
-        warp::serve(controller_route)
-            .tls()
-            .cert_path(cert_url)
-            .key_path(key_url)
-            .run(([0, 0, 0, 0], 3000))
-            .await;
+        let addr = SocketAddr::new(tls_mode.bind_ip(), port);
+
+        let paths = match &tls_mode {
+            TlsMode::Secure(paths) => paths.clone(),
+            _ => {
+                match warp::serve(routes).try_bind_ephemeral(addr) {
+                    Ok((_, server)) => {
+                        info!("✅ 服务器已启动，监听 http://{}", addr);
+                        server.await;
+                    }
+                    Err(e) => {
+                        error!("❌ 服务器启动失败: {}", e);
+                        status.set_error(Some(
+                            LocaleMessage::new("status-error-bind")
+                                .arg("port", port)
+                                .arg("detail", e),
+                        ));
+                        status.0.lock().unwrap().started = false;
+                    }
+                }
+                return;
+            }
+        };
+
+        // warp 不支持替换运行中的证书，证书更新时平滑关闭后用新证书重新绑定
+        let mut mdns = None;
+        loop {
+            let (cert, key) = match (fs::read(&paths.cert), fs::read(&paths.key)) {
+                (Ok(cert), Ok(key)) => (cert, key),
+                (Err(e), _) | (_, Err(e)) => {
+                    error!("❌ 无法读取证书文件: {}", e);
+                    status.set_error(Some(
+                        LocaleMessage::new("status-error-certificate").arg("detail", e),
+                    ));
+                    status.0.lock().unwrap().started = false;
+                    return;
+                }
+            };
+            let mut reload = cert_reload.clone();
+            let shutdown = async move {
+                match reload.as_mut() {
+                    Some(rx) => {
+                        if rx.changed().await.is_err() {
+                            std::future::pending::<()>().await;
+                        }
+                    }
+                    None => std::future::pending::<()>().await,
+                }
+            };
+
+            let server = warp::serve(routes.clone())
+                .tls()
+                .cert(cert)
+                .key(key)
+                .try_bind_with_graceful_shutdown(addr, shutdown);
+            match server {
+                Ok((_, server)) => {
+                    info!("✅ 服务器已启动，监听 https://{}（网页、/ws、/api）", addr);
+                    if mdns.is_none() {
+                        mdns = advertise_lan(&status, port, &tls_mode);
+                    }
+                    server.await;
+                    info!("🔄 服务器证书已更新，重新启动");
+                }
+                Err(e) => {
+                    error!("❌ 服务器启动失败: {}", e);
+                    status.set_error(Some(LocaleMessage::new("status-error-server").arg("detail", e)));
+                    status.0.lock().unwrap().started = false;
+                    return;
+                }
+            }
+            // 标记当前版本已处理，避免下一轮立即再次关闭
+            if let Some(rx) = cert_reload.as_mut() {
+                rx.borrow_and_update();
+            }
+        }
     });
-    println!("✅ 控制器服务器已启动，监听 3000 端口");
 }
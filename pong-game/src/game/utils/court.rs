@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// tennis_table.glb 中桌面的尺寸（长、桌面高度、宽），单位米
+const TABLE_MODEL_SIZE: Vec3 = Vec3::new(2.574, 0.851, 1.552);
+/// ball.glb 中球的半径
+const BALL_MODEL_RADIUS: f32 = 0.007185;
+/// 发球时球在球拍前方、桌面上方的位置
+const SERVE_OFFSET: Vec3 = Vec3::new(-0.1, 0.24, 0.0);
+/// 击球位置在端线后的距离，发球时球在端线后方
+const HIT_MARGIN: f32 = 0.2;
+/// 球拍能伸出击球位置的距离（约一臂），再远的球算出界
+const ARM_REACH: f32 = 0.43;
+/// 出界判定在球桌外留出的距离：端线或边线外先是击球位置，再加上一臂
+const BOUNDS_MARGIN: f32 = HIT_MARGIN + ARM_REACH;
+/// 玩家视角在端线后的距离，能看到整张球桌和自己的球拍
+const CAMERA_BEHIND_END_LINE: f32 = 1.13;
+/// 高于该高度视为出界
+const CEILING: f32 = 2.0;
+
+/// 球场尺寸，单位为米、千克。默认值为 ITTF 规则的标准尺寸。
+///
+/// 坐标约定：球桌中心在原点，长边沿 x 轴，球网在 x = 0，玩家 1 在 +x 一侧，y 轴向上。
+/// 模型按这里的尺寸缩放，生成实体、出界判定和相机位置都从这里计算。
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Court {
+    /// 球桌长度（x 方向）
+    pub table_length: f32,
+    /// 球桌宽度（z 方向）
+    pub table_width: f32,
+    /// 桌面离地高度
+    pub table_height: f32,
+    /// 球网高出桌面的高度
+    pub net_height: f32,
+    /// 球网两端伸出边线的长度
+    pub net_overhang: f32,
+    /// 球的直径
+    pub ball_diameter: f32,
+    /// 球的质量
+    pub ball_mass: f32,
+    /// 球从 30.5 cm 落到桌面弹起约 23 cm，对应的恢复系数
+    pub table_restitution: f32,
+    /// 拍面的半尺寸，拍面法线为本地 Y 轴
+    pub racket_half_extents: Vec3,
+}
+
+impl Default for Court {
+    fn default() -> Self {
+        Court {
+            table_length: 2.74,
+            table_width: 1.525,
+            table_height: 0.76,
+            net_height: 0.1525,
+            net_overhang: 0.1525,
+            ball_diameter: 0.04,
+            ball_mass: 0.0027,
+            table_restitution: 0.87,
+            racket_half_extents: Vec3::new(0.075, 0.01, 0.1),
+        }
+    }
+}
+
+impl Court {
+    pub fn half_length(&self) -> f32 {
+        self.table_length / 2.0
+    }
+
+    pub fn half_width(&self) -> f32 {
+        self.table_width / 2.0
+    }
+
+    /// 球拍击球位置离球网的距离，在端线后 [`HIT_MARGIN`]
+    pub fn hit_depth(&self) -> f32 {
+        self.half_length() + HIT_MARGIN
+    }
+
+    pub fn ball_radius(&self) -> f32 {
+        self.ball_diameter / 2.0
+    }
+
+    /// 球桌模型的缩放，使桌面与标准尺寸一致
+    pub fn table_scale(&self) -> Vec3 {
+        Vec3::new(self.table_length, self.table_height, self.table_width) / TABLE_MODEL_SIZE
+    }
+
+    /// 球模型的缩放
+    pub fn ball_scale(&self) -> f32 {
+        self.ball_radius() / BALL_MODEL_RADIUS
+    }
+
+    /// 球桌的默认碰撞体，使用模型坐标（实体缩放为 [`Court::table_scale`]），顶面即桌面
+    pub fn table_collider(&self) -> Collider {
+        let scale = self.table_scale();
+        let half = Vec3::new(self.half_length(), self.table_height / 2.0, self.half_width()) / scale;
+        Collider::compound(vec![(
+            Vec3::Y * half.y,
+            Quat::IDENTITY,
+            Collider::cuboid(half.x, half.y, half.z),
+        )])
+    }
+
+    /// 球网相对球桌的位置，作为球桌子实体时使用（模型坐标）
+    pub fn net_transform(&self) -> Transform {
+        let y = self.table_height + self.net_height / 2.0;
+        Transform::from_xyz(0.0, y / self.table_scale().y, 0.0)
+    }
+
+    /// 球网的默认碰撞体（模型坐标）
+    pub fn net_collider(&self) -> Collider {
+        let scale = self.table_scale();
+        Collider::cuboid(
+            0.005 / scale.x,
+            self.net_height / 2.0 / scale.y,
+            (self.half_width() + self.net_overhang) / scale.z,
+        )
+    }
+
+    /// 球的默认碰撞体（实体缩放为 [`Court::ball_scale`]）
+    pub fn ball_collider(&self) -> Collider {
+        Collider::ball(self.ball_radius() / self.ball_scale())
+    }
+
+    pub fn ball_mass(&self) -> ColliderMassProperties {
+        ColliderMassProperties::Mass(self.ball_mass)
+    }
+
+    pub fn racket_collider(&self) -> Collider {
+        let half = self.racket_half_extents;
+        Collider::cuboid(half.x, half.y, half.z)
+    }
+
+    /// 玩家 1 一侧的球拍起始位置
+    pub fn racket_position(&self) -> Vec3 {
+        Vec3::new(self.hit_depth(), self.table_height + SERVE_OFFSET.y, 0.0)
+    }
+
+    /// 玩家 1 发球时球的位置
+    pub fn serve_position(&self) -> Vec3 {
+        self.racket_position() + Vec3::new(SERVE_OFFSET.x, 0.0, SERVE_OFFSET.z)
+    }
+
+    /// 单人模式中对面球桌后方的虚拟墙（x 坐标取负），站在对手击球的位置
+    pub fn back_wall(&self) -> f32 {
+        self.hit_depth()
+    }
+
+    /// 球是否离开了球场（落地、飞太高或离球桌太远）
+    pub fn out_of_bounds(&self, position: Vec3) -> bool {
+        position.x.abs() > self.half_length() + BOUNDS_MARGIN
+            || position.z.abs() > self.half_width() + BOUNDS_MARGIN
+            || position.y < 0.0
+            || position.y > CEILING
+    }
+
+    /// 球是否越过击球位置、飞出桌面两侧或落到桌面以下，用于练习模式尽快发下一球
+    pub fn left_table_area(&self, position: Vec3) -> bool {
+        position.x.abs() > self.hit_depth()
+            || position.z.abs() > self.half_width()
+            || position.y < self.table_height - 0.25
+            || position.y > CEILING
+    }
+
+    /// 站在 `side`（1.0 为 +x，-1.0 为 -x）一侧球桌后方的视角
+    pub fn player_camera(&self, side: f32) -> Transform {
+        Transform::from_xyz(
+            side * (self.half_length() + CAMERA_BEHIND_END_LINE),
+            self.table_height + 0.74,
+            0.0,
+        )
+        .looking_at(Vec3::new(0.0, self.table_height + 0.24, 0.0), Vec3::Y)
+    }
+
+    /// 从球桌侧面看向球网的观战视角
+    pub fn side_camera(&self) -> Transform {
+        Transform::from_xyz(0.0, self.table_height + 1.24, self.half_width() + 1.74)
+            .looking_at(Vec3::new(0.0, self.table_height, 0.0), Vec3::Y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hit_plane_is_behind_the_end_line() {
+        let court = Court::default();
+        assert!(court.hit_depth() > court.half_length());
+        assert!(court.serve_position().x > court.half_length(), "发球时球在端线后");
+        assert!(court.back_wall() > court.half_length());
+    }
+
+    #[test]
+    fn ball_over_the_table_stays_in_table_area() {
+        let court = Court::default();
+        let y = court.table_height + 0.2;
+        for x in [0.0, court.half_length() - 0.01, court.half_length() + 0.01] {
+            for side in [1.0, -1.0] {
+                let position = Vec3::new(side * x, y, 0.0);
+                assert!(!court.left_table_area(position), "{position} 还在击球范围内");
+            }
+        }
+        assert!(court.left_table_area(Vec3::new(court.hit_depth() + 0.01, y, 0.0)));
+        assert!(court.left_table_area(Vec3::new(0.5, y, court.half_width() + 0.01)));
+        assert!(court.left_table_area(Vec3::new(0.5, court.table_height - 0.3, 0.0)));
+    }
+
+    #[test]
+    fn out_of_bounds_leaves_room_to_reach_the_ball() {
+        let court = Court::default();
+        let y = court.table_height + 0.2;
+        assert!(!court.out_of_bounds(Vec3::new(court.hit_depth() + ARM_REACH - 0.01, y, 0.0)));
+        assert!(court.out_of_bounds(Vec3::new(court.hit_depth() + ARM_REACH + 0.01, y, 0.0)));
+        assert!(court.out_of_bounds(Vec3::new(0.0, y, court.half_width() + BOUNDS_MARGIN + 0.01)));
+        assert!(court.out_of_bounds(Vec3::new(0.0, -0.01, 0.0)));
+    }
+}
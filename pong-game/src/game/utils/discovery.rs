@@ -0,0 +1,46 @@
+use std::net::IpAddr;
+
+use anyhow::{Context, Result};
+use bevy::log::info;
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+
+use crate::game::utils::tls::TlsMode;
+use crate::game::utils::ws_handler::PROTOCOL_VERSION;
+
+/// 局域网内的主机名，手机可直接访问 `https://pong.local:<port>`
+pub const MDNS_HOST: &str = "pong.local.";
+/// DNS-SD 服务类型
+pub const SERVICE_TYPE: &str = "_pong._tcp.local.";
+
+/// 在局域网内广播 `pong.local` 主机名和 `_pong._tcp` 服务记录。
+/// 返回的 daemon 需要一直持有，drop 前广播一直有效。
+pub fn advertise(ip: IpAddr, port: u16, tls_mode: &TlsMode) -> Result<ServiceDaemon> {
+    let daemon = ServiceDaemon::new().context("无法启动 mDNS 服务")?;
+
+    let instance_name = format!("pong-{}", ip).replace('.', "-");
+    let properties = [
+        ("proto", PROTOCOL_VERSION.to_string()),
+        ("scheme", tls_mode.ws_scheme().to_string()),
+        ("path", "/ws".to_string()),
+        ("port", port.to_string()),
+        ("version", env!("CARGO_PKG_VERSION").to_string()),
+    ];
+    let service = ServiceInfo::new(
+        SERVICE_TYPE,
+        &instance_name,
+        MDNS_HOST,
+        ip,
+        port,
+        &properties[..],
+    )
+    .context("构建 mDNS 服务记录失败")?;
+
+    daemon.register(service).context("注册 mDNS 服务失败")?;
+    info!(
+        "📡 已在局域网广播 {}:{}（{}）",
+        MDNS_HOST.trim_end_matches('.'),
+        port,
+        SERVICE_TYPE
+    );
+    Ok(daemon)
+}
@@ -1,14 +1,31 @@
 pub mod command_handler;
+pub mod controller_assets;
 pub mod controller_server;
+pub mod court;
+pub mod discovery;
+pub mod racket_physics;
+pub mod rally;
+pub mod scene_collider;
+pub mod spectator;
+pub mod tls;
 pub mod ws_handler;
 
 use std::sync::{Arc, Mutex};
 
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
+use dotenv::dotenv;
+
+use tls::TlsMode;
+
+use crate::components::qr::QrCode;
+use crate::components::toast::Toast;
+use crate::game::input::MAX_PLAYERS;
+use crate::locale::{Locale, LocaleMessage};
+use crate::menu::navigation::MenuInput;
 
 #[derive(Resource)]
-pub struct WsRuntime(tokio::runtime::Runtime);
+pub struct WsRuntime(pub tokio::runtime::Runtime);
 
 #[derive(Component, Clone, Copy)]
 pub struct Racket;
@@ -19,6 +36,9 @@ pub struct Ball;
 #[derive(Component, Clone, Copy)]
 pub struct Table;
 
+#[derive(Component, Clone, Copy)]
+pub struct Net;
+
 #[derive(Component, Clone, Copy)]
 pub enum ModelComponent {
     Tbl,
@@ -33,11 +53,15 @@ pub struct RacketCommandQueue(pub Arc<Mutex<Vec<RacketTransformCommand>>>);
 pub enum CommandDataType {
     Position(Vec3),
     Rotation(Quat),
+    /// 手机倾斜或点按产生的菜单导航
+    Menu(MenuInput),
 }
 
 #[derive(Clone, Debug)]
 pub struct RacketTransformCommand {
     pub command: CommandDataType,
+    /// 发送指令的控制器所在的座位，见 [`ControllerSeats`]
+    pub seat: usize,
 }
 
 #[derive(Component)]
@@ -54,6 +78,116 @@ pub enum CameraComponent {
 #[derive(Component)]
 pub struct MoveSpeedText;
 
+#[derive(Component)]
+pub struct ServerStatusText;
+
+/// 主菜单中手机扫码打开控制器页面的二维码
+#[derive(Component)]
+pub struct ControllerQrCode;
+
+#[derive(Clone, Debug, Default)]
+pub struct ServerStatusInfo {
+    pub host: String,
+    pub port: u16,
+    pub started: bool,
+    pub controllers: usize,
+    pub mdns_host: Option<String>,
+    pub error: Option<LocaleMessage>,
+}
+
+/// 网络服务状态，由 tokio 任务写入、Bevy 系统读取后显示在界面上
+#[derive(Resource, Clone, Default)]
+pub struct ServerStatus(pub Arc<Mutex<ServerStatusInfo>>);
+
+impl ServerStatusInfo {
+    /// 控制器页面的局域网地址
+    pub fn url(&self, scheme: &str) -> String {
+        format!("{}://{}:{}", scheme, self.host, self.port)
+    }
+}
+
+impl ServerStatus {
+    pub fn set_error(&self, error: Option<LocaleMessage>) {
+        self.0.lock().unwrap().error = error;
+    }
+
+    /// 只清除文本 id 为 `id` 的错误，其他来源的错误保留
+    pub fn clear_error(&self, id: &str) {
+        let mut info = self.0.lock().unwrap();
+        if info.error.as_ref().is_some_and(|error| error.id == id) {
+            info.error = None;
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize)]
+pub struct GameStatusInfo {
+    pub state: String,
+    pub launched: bool,
+    pub table_bounces: u32,
+}
+
+#[derive(Clone)]
+pub struct RoomHandle {
+    pub code: String,
+    pub queue: RacketCommandQueue,
+    pub controllers: usize,
+}
+
+/// 房间码到房间指令队列的映射，控制器发送 `join:<code>` 后指令进入对应房间
+#[derive(Resource, Clone, Default)]
+pub struct RoomRegistry(pub Arc<Mutex<Vec<RoomHandle>>>);
+
+impl RoomRegistry {
+    pub fn join(&self, code: &str) -> Option<RacketCommandQueue> {
+        let mut rooms = self.0.lock().unwrap();
+        let room = rooms
+            .iter_mut()
+            .find(|room| room.code.eq_ignore_ascii_case(code))?;
+        room.controllers += 1;
+        Some(room.queue.clone())
+    }
+
+    pub fn leave(&self, code: &str) {
+        let mut rooms = self.0.lock().unwrap();
+        if let Some(room) = rooms
+            .iter_mut()
+            .find(|room| room.code.eq_ignore_ascii_case(code))
+        {
+            room.controllers = room.controllers.saturating_sub(1);
+        }
+    }
+}
+
+/// 本机比赛的控制器座位，第 N 个座位的手机控制第 N 名玩家的球拍。
+/// 手机连接时坐到人数最少的座位，发送 `seat:<1-4>` 可以换座位；坐满后多台手机共用座位
+#[derive(Resource, Clone, Default)]
+pub struct ControllerSeats(pub Arc<Mutex<[usize; MAX_PLAYERS]>>);
+
+impl ControllerSeats {
+    pub fn take(&self) -> usize {
+        let mut seats = self.0.lock().unwrap();
+        let seat = (0..MAX_PLAYERS).min_by_key(|seat| seats[*seat]).unwrap_or(0);
+        seats[seat] += 1;
+        seat
+    }
+
+    pub fn switch(&self, from: usize, to: usize) {
+        let mut seats = self.0.lock().unwrap();
+        seats[from] = seats[from].saturating_sub(1);
+        seats[to] += 1;
+    }
+
+    pub fn release(&self, seat: usize) {
+        let mut seats = self.0.lock().unwrap();
+        seats[seat] = seats[seat].saturating_sub(1);
+    }
+}
+
+/// 游戏状态快照，供 `/api/status` 读取
+#[derive(Resource, Clone, Default)]
+pub struct SharedGameStatus(pub Arc<Mutex<GameStatusInfo>>);
+
 #[derive(Resource)]
 pub struct LaunchState {
     pub launched: bool,
@@ -80,14 +214,46 @@ pub struct TrajectoryPreview {
 }
 
 pub fn init_resources(app: &mut App) {
+    dotenv().ok();
+
     let rt = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .unwrap();
     let command_queue: Arc<Mutex<Vec<RacketTransformCommand>>> = Arc::new(Mutex::new(Vec::new()));
 
+    let tls_mode = tls::resolve_tls_mode();
+    let server_status = ServerStatus::default();
+    {
+        let mut info = server_status.0.lock().unwrap();
+        info.port = std::env::var("PONG_PORT")
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(3000);
+        info.host = match tls_mode {
+            TlsMode::Plain => "localhost".to_string(),
+            _ => local_ip_address::local_ip()
+                .map(|ip| ip.to_string())
+                .unwrap_or_else(|_| "localhost".to_string()),
+        };
+        if let TlsMode::Unavailable(reason) = &tls_mode {
+            info.error = Some(LocaleMessage::new("status-error-tls").arg("reason", reason));
+        }
+    }
+    if let TlsMode::Secure(paths) = &tls_mode {
+        let reload = tls::watch_certificates(&rt, paths.clone(), server_status.clone());
+        app.insert_resource(reload);
+    }
+
     app.insert_resource(WsRuntime(rt))
+        .insert_resource(tls_mode)
+        .insert_resource(server_status)
+        .insert_resource(SharedGameStatus::default())
+        .insert_resource(RoomRegistry::default())
+        .insert_resource(ControllerSeats::default())
+        .insert_resource(spectator::SpectatorFeed::default())
         .insert_resource(RacketCommandQueue(command_queue))
+        .insert_resource(court::Court::default())
         .insert_resource(LaunchState::default())
         .insert_resource(BallTableCollisionCount::default())
         .insert_resource(TrajectoryPreview {
@@ -100,3 +266,83 @@ pub fn init_resources(app: &mut App) {
         .add_event::<CollisionEvent>()
         .add_event::<ContactForceEvent>();
 }
+
+pub fn update_server_status_text(
+    status: Res<ServerStatus>,
+    tls_mode: Res<TlsMode>,
+    locale: Res<Locale>,
+    mut query: Query<&mut Text, With<ServerStatusText>>,
+) {
+    let info = status.0.lock().unwrap();
+    let scheme = tls_mode.http_scheme();
+    let url = info.url(scheme);
+    let content = match (&info.error, &info.mdns_host) {
+        (Some(error), _) => {
+            locale.format("status-network-error", &[("error", &locale.message(error))])
+        }
+        (None, Some(mdns_host)) => locale.format(
+            "status-controller-mdns",
+            &[
+                ("url", &format!("{}://{}:{}", scheme, mdns_host, info.port)),
+                ("fallback", &url),
+            ],
+        ),
+        (None, None) => locale.format("status-controller", &[("url", &url)]),
+    };
+    for mut text in query.iter_mut() {
+        if text.0 != content {
+            text.0 = content.clone();
+        }
+    }
+}
+
+/// 二维码使用 IP 地址而不是 mDNS 域名，部分手机无法解析 `.local`
+pub fn update_controller_qr_code(
+    status: Res<ServerStatus>,
+    tls_mode: Res<TlsMode>,
+    mut query: Query<&mut QrCode, With<ControllerQrCode>>,
+) {
+    let info = status.0.lock().unwrap();
+    let url = if info.started && info.error.is_none() {
+        info.url(tls_mode.http_scheme())
+    } else {
+        String::new()
+    };
+    for mut qr in query.iter_mut() {
+        if qr.0 != url {
+            qr.0 = url.clone();
+        }
+    }
+}
+
+/// 手机控制器连接或断开时显示提示
+pub fn toast_controller_changes(
+    status: Res<ServerStatus>,
+    locale: Res<Locale>,
+    mut known: Local<usize>,
+    mut toasts: EventWriter<Toast>,
+) {
+    let controllers = status.0.lock().unwrap().controllers;
+    if controllers == *known {
+        return;
+    }
+    let id = if controllers > *known {
+        "toast-controller-connected"
+    } else {
+        "toast-controller-disconnected"
+    };
+    toasts.send(Toast(locale.format(id, &[("count", &controllers)])));
+    *known = controllers;
+}
+
+pub fn sync_game_status(
+    state: Res<State<crate::GameState>>,
+    launch_state: Res<LaunchState>,
+    counter: Res<BallTableCollisionCount>,
+    shared: Res<SharedGameStatus>,
+) {
+    let mut info = shared.0.lock().unwrap();
+    info.state = format!("{:?}", state.get());
+    info.launched = launch_state.launched;
+    info.table_bounces = counter.count;
+}
@@ -0,0 +1,377 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_rapier3d::plugin::TimestepMode;
+use bevy_rapier3d::prelude::*;
+
+use crate::game::utils::court::Court;
+use crate::game::utils::scene_collider::ColliderBounds;
+use crate::game::utils::{Ball, BallTableCollisionCount, LaunchState, Racket};
+
+/// 目标位姿离当前位姿太远时直接瞬移（如发球时跟随球），不算作挥拍
+const TELEPORT_DISTANCE: f32 = 0.5;
+/// 球拍速度上限，避免丢帧后算出离谱的速度
+const MAX_RACKET_SPEED: f32 = 25.0;
+/// 拍面的恢复系数
+const RACKET_RESTITUTION: f32 = 0.85;
+/// 击球后球朝对面飞的最小速度，保证轻碰也能回球
+const MIN_RETURN_SPEED: f32 = 2.0;
+/// 击球后球速上限
+const MAX_RETURN_SPEED: f32 = 8.0;
+/// 同一球拍两次击球的最短间隔，避免一次挥拍结算多次
+const HIT_COOLDOWN: f32 = 0.15;
+/// 击球后把球推出拍面的额外距离
+const SEPARATION: f32 = 0.002;
+/// 球速不会超过击球后的上限加上球拍速度；一帧内移动得更远说明球被直接摆放（发球、出界重置），不做扫掠
+const MAX_BALL_SPEED: f32 = MAX_RETURN_SPEED + MAX_RACKET_SPEED;
+
+/// 球拍的目标位姿，物理步进前换算成运动学速度
+#[derive(Component, Default)]
+pub struct KinematicTarget(pub Option<Transform>);
+
+/// 球拍上一次物理步进后的位姿，用于两帧之间的扫掠检测
+#[derive(Component, Default)]
+pub struct RacketMotion {
+    previous: Option<Transform>,
+    last_hit: Option<f32>,
+}
+
+/// 球拍击中球，`speed` 为击球后的球速
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RacketHitEvent {
+    pub racket: Entity,
+    pub ball: Entity,
+    pub speed: f32,
+}
+
+/// 球拍使用 `RigidBody::KinematicVelocityBased`：
+/// 输入源给出目标位姿，物理步进前换算成速度交给 rapier 积分，步进后在上一帧和当前帧之间做扫掠检测并结算击球。
+/// 主应用和房间子应用都会添加该插件。
+pub fn racket_physics_plugin(app: &mut App) {
+    app.add_event::<RacketHitEvent>()
+        .add_systems(
+            PostUpdate,
+            drive_kinematic_rackets.before(PhysicsSet::SyncBackend),
+        )
+        .add_systems(
+            PostUpdate,
+            resolve_racket_hits.after(PhysicsSet::Writeback),
+        );
+}
+
+/// 与 rapier 下一次步进使用的 dt 保持一致
+pub(crate) fn physics_dt(timestep_mode: &TimestepMode, delta: f32) -> f32 {
+    match *timestep_mode {
+        TimestepMode::Fixed { dt, .. } => dt,
+        TimestepMode::Variable {
+            max_dt, time_scale, ..
+        } => (delta * time_scale).min(max_dt),
+        TimestepMode::Interpolated { dt, .. } => dt,
+    }
+}
+
+fn drive_kinematic_rackets(
+    time: Res<Time>,
+    timestep_mode: Res<TimestepMode>,
+    mut query: Query<(&mut Transform, &mut KinematicTarget, &mut Velocity), With<Racket>>,
+) {
+    let dt = physics_dt(&timestep_mode, time.delta_secs());
+    for (mut transform, mut target, mut velocity) in query.iter_mut() {
+        let Some(target) = target.0.take() else {
+            // 没有新输入时停住
+            *velocity = Velocity::zero();
+            continue;
+        };
+        let offset = target.translation - transform.translation;
+        if dt <= 0.0 || offset.length() > TELEPORT_DISTANCE {
+            *transform = target.with_scale(transform.scale);
+            *velocity = Velocity::zero();
+            continue;
+        }
+
+        // 取最短路径的旋转差
+        let mut delta_rotation = target.rotation * transform.rotation.inverse();
+        if delta_rotation.w < 0.0 {
+            delta_rotation = -delta_rotation;
+        }
+        velocity.linvel = (offset / dt).clamp_length_max(MAX_RACKET_SPEED);
+        velocity.angvel = delta_rotation.to_scaled_axis() / dt;
+    }
+}
+
+/// 拍面在球拍坐标系下的位姿和半尺寸，最薄的一轴换到本地 Y 轴作为拍面法线。
+/// 模型加载前使用球场的标准拍面尺寸。
+fn racket_face(court: &Court, bounds: Option<&ColliderBounds>) -> (Transform, Vec3) {
+    let Some(bounds) = bounds else {
+        return (Transform::IDENTITY, court.racket_half_extents);
+    };
+    let h = bounds.half_extents;
+    let (swap, half) = if h.x <= h.y && h.x <= h.z {
+        (Quat::from_rotation_z(-FRAC_PI_2), Vec3::new(h.y, h.x, h.z))
+    } else if h.z <= h.y {
+        (Quat::from_rotation_x(FRAC_PI_2), Vec3::new(h.x, h.z, h.y))
+    } else {
+        (Quat::IDENTITY, h)
+    };
+    (
+        Transform::from_translation(bounds.center).with_rotation(bounds.rotation * swap),
+        half,
+    )
+}
+
+/// 球拍位姿加上拍面偏移（忽略缩放）
+fn face_transform(racket: &Transform, face: &Transform) -> Transform {
+    Transform::from_translation(racket.translation + racket.rotation * face.translation)
+        .with_rotation(racket.rotation * face.rotation)
+}
+
+/// 世界坐标转到拍面本地坐标
+fn to_racket_local(face: &Transform, point: Vec3) -> Vec3 {
+    face.rotation.inverse() * (point - face.translation)
+}
+
+/// 扫掠的起点：上一帧的位置离当前位置超过 `max_distance` 时视为瞬移，从当前位置开始
+fn sweep_start(previous: Option<Vec3>, current: Vec3, max_distance: f32) -> Vec3 {
+    previous
+        .filter(|previous| previous.distance(current) <= max_distance)
+        .unwrap_or(current)
+}
+
+/// 球心在两帧之间的相对运动是否穿过拍面，返回接触点（本地坐标）和球所在的一侧
+fn swept_contact(from: Vec3, to: Vec3, radius: f32, half: Vec3) -> Option<(Vec3, f32)> {
+    let reach = half.y + radius;
+    let side = if from.y >= 0.0 { 1.0 } else { -1.0 };
+    let contact = if from.y * to.y < 0.0 {
+        // 穿过了拍面所在平面，取穿过时的位置
+        from.lerp(to, from.y / (from.y - to.y))
+    } else if to.y.abs() <= reach && (to.y - from.y) * side <= 0.0 {
+        to
+    } else {
+        return None;
+    };
+    let inside = contact.x.abs() <= half.x + radius && contact.z.abs() <= half.z + radius;
+    inside.then_some((Vec3::new(contact.x, 0.0, contact.z), side))
+}
+
+type HitRacket = (
+    Entity,
+    &'static Transform,
+    &'static Velocity,
+    &'static mut RacketMotion,
+    Option<&'static ColliderBounds>,
+);
+
+type HitBall = (
+    Entity,
+    &'static mut Transform,
+    &'static mut Velocity,
+    Option<&'static ColliderBounds>,
+);
+
+/// 参与击球结算的球拍和球，以及球上一帧的位置
+#[derive(SystemParam)]
+struct HitBodies<'w, 's> {
+    rackets: Query<'w, 's, HitRacket, (With<Racket>, Without<Ball>)>,
+    balls: Query<'w, 's, HitBall, (With<Ball>, Without<Racket>)>,
+    previous_ball: Local<'s, Option<Vec3>>,
+}
+
+/// 当前时间和本帧物理步进的 dt
+#[derive(SystemParam)]
+struct StepClock<'w> {
+    time: Res<'w, Time>,
+    timestep_mode: Res<'w, TimestepMode>,
+}
+
+fn resolve_racket_hits(
+    clock: StepClock,
+    court: Res<Court>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut hit_events: EventWriter<RacketHitEvent>,
+    mut launch_state: ResMut<LaunchState>,
+    mut counter: ResMut<BallTableCollisionCount>,
+    mut bodies: HitBodies,
+) {
+    // rapier 报告的接触也交给这里统一结算
+    let touched: Vec<Entity> = collision_events
+        .read()
+        .filter_map(|event| match event {
+            CollisionEvent::Started(e1, e2, _) => Some([*e1, *e2]),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    let Ok((ball_entity, mut ball_transform, mut ball_velocity, ball_bounds)) =
+        bodies.balls.get_single_mut()
+    else {
+        *bodies.previous_ball = None;
+        return;
+    };
+    let radius = ball_bounds.map_or(court.ball_radius(), |b| {
+        b.half_extents.x * ball_transform.scale.x
+    });
+    let dt = physics_dt(&clock.timestep_mode, clock.time.delta_secs());
+    let ball_from = sweep_start(
+        *bodies.previous_ball,
+        ball_transform.translation,
+        MAX_BALL_SPEED * dt,
+    );
+    let now = clock.time.elapsed_secs();
+
+    for (racket_entity, racket, racket_velocity, mut motion, bounds) in bodies.rackets.iter_mut() {
+        // 球拍瞬移（如发球时跟随球）同样不扫掠
+        let racket_from = motion
+            .previous
+            .filter(|previous| {
+                previous.translation.distance(racket.translation) <= TELEPORT_DISTANCE
+            })
+            .unwrap_or(*racket);
+        motion.previous = Some(*racket);
+        if motion.last_hit.is_some_and(|last| now - last < HIT_COOLDOWN) {
+            continue;
+        }
+
+        let (face, half) = racket_face(&court, bounds);
+        let face_from = face_transform(&racket_from, &face);
+        let face_now = face_transform(racket, &face);
+        let from = to_racket_local(&face_from, ball_from);
+        let to = to_racket_local(&face_now, ball_transform.translation);
+        let contact = swept_contact(from, to, radius, half).or_else(|| {
+            let touching = touched.contains(&racket_entity) && touched.contains(&ball_entity);
+            touching.then(|| (Vec3::new(to.x, 0.0, to.z), if to.y >= 0.0 { 1.0 } else { -1.0 }))
+        });
+        let Some((local_contact, side)) = contact else {
+            continue;
+        };
+
+        let contact = face_now.translation + face_now.rotation * local_contact;
+        let normal = face_now.rotation * Vec3::Y * side;
+        let racket_point_velocity = racket_velocity.linvel
+            + racket_velocity.angvel.cross(contact - racket.translation);
+
+        // 在球拍参考系里按拍面法线反弹，再加回球拍速度
+        let mut relative = ball_velocity.linvel - racket_point_velocity;
+        let approach = relative.dot(normal);
+        if approach < 0.0 {
+            relative -= (1.0 + RACKET_RESTITUTION) * approach * normal;
+        }
+        let mut linvel = relative + racket_point_velocity;
+
+        // 保证球朝对面飞
+        let toward_opponent = -racket.translation.x.signum();
+        if linvel.x * toward_opponent < MIN_RETURN_SPEED {
+            linvel.x = MIN_RETURN_SPEED * toward_opponent;
+        }
+        linvel = linvel.clamp_length_max(MAX_RETURN_SPEED);
+
+        ball_transform.translation =
+            contact + normal * (half.y + radius + SEPARATION);
+        ball_velocity.linvel = linvel;
+        motion.last_hit = Some(now);
+
+        launch_state.launched = true;
+        counter.count = 0;
+        hit_events.send(RacketHitEvent {
+            racket: racket_entity,
+            ball: ball_entity,
+            speed: linvel.length(),
+        });
+    }
+
+    *bodies.previous_ball = Some(ball_transform.translation);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF: Vec3 = Vec3::new(0.075, 0.01, 0.1);
+    const RADIUS: f32 = 0.02;
+
+    fn sweep(from: Vec3, to: Vec3) -> Option<(Vec3, f32)> {
+        swept_contact(from, to, RADIUS, HALF)
+    }
+
+    #[test]
+    fn fast_ball_tunnelling_through_face_is_hit() {
+        // 一帧内从拍面前方 0.5 m 穿到后方 0.5 m，两端都不在拍面附近
+        let hit = sweep(Vec3::new(0.02, 0.5, 0.0), Vec3::new(0.02, -0.5, 0.04));
+        let (contact, side) = hit.expect("穿过拍面应该算击中");
+        assert!((contact - Vec3::new(0.02, 0.0, 0.02)).length() < 1e-5);
+        assert_eq!(side, 1.0);
+    }
+
+    #[test]
+    fn ball_passing_beside_face_is_missed() {
+        let beside = sweep(Vec3::new(0.2, 0.5, 0.0), Vec3::new(0.2, -0.5, 0.0));
+        assert!(beside.is_none());
+        // 在拍面附近但正在离开
+        let leaving = sweep(Vec3::new(0.0, 0.015, 0.0), Vec3::new(0.0, 0.025, 0.0));
+        assert!(leaving.is_none());
+    }
+
+    #[test]
+    fn back_face_approach_reports_back_side() {
+        let through = sweep(Vec3::new(0.0, -0.3, 0.0), Vec3::new(0.0, 0.3, 0.0));
+        assert_eq!(through.map(|(_, side)| side), Some(-1.0));
+        let touching = sweep(Vec3::new(0.0, -0.1, 0.0), Vec3::new(0.0, -0.025, 0.0));
+        assert_eq!(touching.map(|(_, side)| side), Some(-1.0));
+    }
+
+    #[test]
+    fn teleported_ball_is_not_swept() {
+        let current = Vec3::new(1.5, 1.0, 0.0);
+        assert_eq!(sweep_start(None, current, 0.5), current);
+        let previous = Vec3::new(1.4, 1.0, 0.0);
+        assert_eq!(sweep_start(Some(previous), current, 0.5), previous);
+        // 出界后放回发球位置
+        assert_eq!(sweep_start(Some(Vec3::new(-2.0, 0.1, 0.0)), current, 0.5), current);
+    }
+
+    #[test]
+    fn racket_face_normal_is_thinnest_axis() {
+        let court = Court::default();
+        assert_eq!(racket_face(&court, None), (Transform::IDENTITY, court.racket_half_extents));
+
+        for (extents, normal) in [
+            (Vec3::new(0.01, 0.1, 0.075), Vec3::X),
+            (Vec3::new(0.1, 0.075, 0.01), Vec3::Z),
+            (Vec3::new(0.1, 0.01, 0.075), Vec3::Y),
+        ] {
+            let bounds = ColliderBounds {
+                center: Vec3::new(0.0, 0.05, 0.0),
+                rotation: Quat::IDENTITY,
+                half_extents: extents,
+            };
+            let (face, half) = racket_face(&court, Some(&bounds));
+            assert_eq!(face.translation, bounds.center);
+            assert!((face.rotation * Vec3::Y).abs().abs_diff_eq(normal, 1e-5), "{extents}");
+            assert_eq!(half.y, 0.01);
+            // 拍面两轴仍覆盖原来的两条长边
+            let mut long = [half.x, half.z];
+            long.sort_by(f32::total_cmp);
+            assert_eq!(long, [0.075, 0.1]);
+        }
+    }
+
+    #[test]
+    fn physics_dt_follows_timestep_mode() {
+        let fixed = TimestepMode::Fixed { dt: 0.01, substeps: 1 };
+        assert_eq!(physics_dt(&fixed, 0.5), 0.01);
+        let variable = TimestepMode::Variable {
+            max_dt: 1.0 / 60.0,
+            time_scale: 0.5,
+            substeps: 1,
+        };
+        assert_eq!(physics_dt(&variable, 0.01), 0.005);
+        assert_eq!(physics_dt(&variable, 0.5), 1.0 / 60.0);
+        let interpolated = TimestepMode::Interpolated {
+            dt: 1.0 / 50.0,
+            time_scale: 1.0,
+            substeps: 1,
+        };
+        assert_eq!(physics_dt(&interpolated, 0.1), 1.0 / 50.0);
+    }
+}
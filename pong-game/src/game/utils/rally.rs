@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::game::utils::court::Court;
+use crate::game::utils::{Ball, BallTableCollisionCount, LaunchState, Racket, Table};
+
+/// 回合结束的判定：比赛等球出界，房间像练习模式一样球离开桌面区域就发下一球
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RallyBounds {
+    #[default]
+    Court,
+    TableArea,
+}
+
+/// 一个回合结束，球已经停住并关闭重力，由各模式放到下一球的位置
+#[derive(Event)]
+pub struct RallyEnded {
+    pub ball: Entity,
+}
+
+/// 本机比赛、联机主机和房间共用的回合逻辑，系统由各模式按自己的运行条件添加：
+/// [`count_ball_contacts`] 之后是 [`end_rally_system`]，再由各模式读取 [`RallyEnded`] 发下一球
+pub fn rally_plugin(app: &mut App) {
+    app.init_resource::<RallyBounds>().add_event::<RallyEnded>();
+}
+
+/// 球碰到球拍时回合开始并清零弹跳次数，碰到桌面时弹跳次数加一
+pub fn count_ball_contacts(
+    mut collision_events: EventReader<CollisionEvent>,
+    racket_q: Query<(), With<Racket>>,
+    ball_q: Query<(), With<Ball>>,
+    table_q: Query<(), With<Table>>,
+    mut launch_state: ResMut<LaunchState>,
+    mut counter: ResMut<BallTableCollisionCount>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+        let other = if ball_q.contains(*e1) {
+            *e2
+        } else if ball_q.contains(*e2) {
+            *e1
+        } else {
+            continue;
+        };
+        if racket_q.contains(other) {
+            launch_state.launched = true;
+            counter.count = 0;
+            debug!("Ball <-> Racket 碰撞触发！");
+        } else if table_q.contains(other) {
+            counter.count += 1;
+            debug!("Ball <-> Table 碰撞，累计：{}", counter.count);
+        }
+    }
+}
+
+/// 击球后打开重力；球出界或在桌面上连续弹跳后停住球，结束这个回合
+pub fn end_rally_system(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform, &GravityScale), With<Ball>>,
+    mut launch_state: ResMut<LaunchState>,
+    mut counter: ResMut<BallTableCollisionCount>,
+    bounds: Res<RallyBounds>,
+    court: Res<Court>,
+    mut ended: EventWriter<RallyEnded>,
+) {
+    for (entity, transform, gravity) in query.iter() {
+        if launch_state.launched && gravity.0 == 0.0 {
+            commands.entity(entity).insert(GravityScale(1.0));
+        }
+        let left = match *bounds {
+            RallyBounds::Court => court.out_of_bounds(transform.translation),
+            RallyBounds::TableArea => court.left_table_area(transform.translation),
+        };
+        if left || counter.count > 2 {
+            commands
+                .entity(entity)
+                .insert((GravityScale(0.0), Velocity::zero()));
+            launch_state.launched = false;
+            counter.count = 0;
+            ended.send(RallyEnded { ball: entity });
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use bevy::ecs::system::SystemParam;
+use bevy::math::Affine3A;
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy::scene::SceneInstanceReady;
+use bevy_rapier3d::prelude::*;
+
+/// glTF 中名称以该前缀开头的节点只用作碰撞体，加载后隐藏
+pub const COLLIDER_NODE_PREFIX: &str = "collider";
+
+/// 由模型网格生成碰撞体的方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneColliderShape {
+    /// 每个网格取自身坐标系下的包围盒，组合成一个碰撞体
+    Cuboid,
+    /// 所有顶点的凸包
+    ConvexHull,
+    /// 所有顶点的包围球
+    Ball,
+}
+
+/// 场景加载完成后根据模型网格生成碰撞体，替换生成前的手写碰撞体。
+///
+/// 挂在 `SceneRoot` 实体上，或挂在它的直接子实体上（如球网），碰撞体按该实体自身的坐标系计算。
+/// 场景中有名称以 [`COLLIDER_NODE_PREFIX`] 开头的节点时只使用这些节点，否则使用全部网格。
+#[derive(Component, Clone, Debug)]
+pub struct SceneCollider {
+    pub shape: SceneColliderShape,
+    /// 只使用名称（或祖先节点名称）包含该字符串的网格
+    pub node: Option<&'static str>,
+}
+
+impl SceneCollider {
+    pub fn new(shape: SceneColliderShape) -> Self {
+        SceneCollider { shape, node: None }
+    }
+
+    pub fn node(mut self, node: &'static str) -> Self {
+        self.node = Some(node);
+        self
+    }
+}
+
+/// 生成的碰撞体在实体坐标系下（未缩放）的主要包围盒，球形时三个半轴都是半径。
+/// racket_physics 用它确定拍面和球的大小。
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ColliderBounds {
+    pub center: Vec3,
+    pub rotation: Quat,
+    pub half_extents: Vec3,
+}
+
+pub fn scene_collider_plugin(app: &mut App) {
+    app.add_observer(build_scene_colliders);
+}
+
+/// 参与生成碰撞体的网格，`to_target` 为网格到目标实体坐标系的变换
+struct MeshPart {
+    entity: Entity,
+    to_target: Affine3A,
+    positions: Vec<Vec3>,
+    collider_node: bool,
+}
+
+fn is_collider_node(name: &str) -> bool {
+    name.to_lowercase().starts_with(COLLIDER_NODE_PREFIX)
+}
+
+/// 遍历场景节点用到的层级、变换、名称和网格
+#[derive(SystemParam)]
+struct SceneNodes<'w, 's> {
+    children: Query<'w, 's, &'static Children>,
+    parents: Query<'w, 's, &'static Parent>,
+    transforms: Query<'w, 's, &'static Transform>,
+    names: Query<'w, 's, &'static Name>,
+    mesh_handles: Query<'w, 's, &'static Mesh3d>,
+}
+
+fn build_scene_colliders(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    meshes: Res<Assets<Mesh>>,
+    configs: Query<&SceneCollider>,
+    nodes: SceneNodes,
+) {
+    let root = trigger.entity();
+    let targets: Vec<Entity> = std::iter::once(root)
+        .chain(
+            nodes
+                .children
+                .get(root)
+                .into_iter()
+                .flat_map(|c| c.iter().copied()),
+        )
+        .filter(|entity| configs.contains(*entity))
+        .collect();
+
+    for target in targets {
+        let config = configs.get(target).unwrap();
+        // 根节点自身的变换不计入，缩放由 rapier 根据 Transform 处理
+        let target_to_root = if target == root {
+            Affine3A::IDENTITY
+        } else {
+            nodes
+                .transforms
+                .get(target)
+                .map(|t| t.compute_affine())
+                .unwrap_or_default()
+        };
+        let root_to_target = target_to_root.inverse();
+
+        let mut parts = Vec::new();
+        for entity in nodes.children.iter_descendants(root) {
+            let Ok(mesh) = nodes.mesh_handles.get(entity) else {
+                continue;
+            };
+            let Some(mesh) = meshes.get(&mesh.0) else {
+                continue;
+            };
+            let Some(VertexAttributeValues::Float32x3(positions)) =
+                mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+            else {
+                continue;
+            };
+
+            // 从网格往上走到场景根，累积变换并收集节点名称
+            let mut to_root = Affine3A::IDENTITY;
+            let mut path = Vec::new();
+            let mut current = entity;
+            while current != root {
+                if let Ok(transform) = nodes.transforms.get(current) {
+                    to_root = transform.compute_affine() * to_root;
+                }
+                if let Ok(name) = nodes.names.get(current) {
+                    path.push(name.as_str());
+                }
+                match nodes.parents.get(current) {
+                    Ok(parent) => current = parent.get(),
+                    Err(_) => break,
+                }
+            }
+
+            if config
+                .node
+                .is_some_and(|node| !path.iter().any(|name| name.contains(node)))
+            {
+                continue;
+            }
+            parts.push(MeshPart {
+                entity,
+                to_target: root_to_target * to_root,
+                positions: positions.iter().map(|p| Vec3::from_array(*p)).collect(),
+                collider_node: path.iter().any(|name| is_collider_node(name)),
+            });
+        }
+
+        if parts.iter().any(|part| part.collider_node) {
+            parts.retain(|part| part.collider_node);
+            for part in &parts {
+                commands.entity(part.entity).insert(Visibility::Hidden);
+            }
+        }
+
+        match build_collider(config.shape, &parts) {
+            Some((collider, bounds)) => {
+                info!(
+                    "🧱 从模型生成碰撞体: {:?} {:?}, 半尺寸 {:.3?}",
+                    config.node.unwrap_or("*"),
+                    config.shape,
+                    bounds.half_extents
+                );
+                commands.entity(target).insert((collider, bounds));
+            }
+            None => warn!(
+                "⚠️ 模型中没有可用于 {:?} 的网格，保留默认碰撞体",
+                config.node.unwrap_or("*")
+            ),
+        }
+    }
+}
+
+fn build_collider(shape: SceneColliderShape, parts: &[MeshPart]) -> Option<(Collider, ColliderBounds)> {
+    let points: Vec<Vec3> = parts
+        .iter()
+        .flat_map(|part| part.positions.iter().map(|p| part.to_target.transform_point3(*p)))
+        .collect();
+    if points.is_empty() {
+        return None;
+    }
+    let (min, max) = points.iter().fold(
+        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+        |(min, max), p| (min.min(*p), max.max(*p)),
+    );
+    let aabb = ColliderBounds {
+        center: (min + max) / 2.0,
+        rotation: Quat::IDENTITY,
+        half_extents: (max - min) / 2.0,
+    };
+
+    match shape {
+        SceneColliderShape::Cuboid => {
+            let boxes: Vec<ColliderBounds> = parts
+                .iter()
+                .filter(|part| !part.positions.is_empty())
+                .map(|part| {
+                    let (min, max) = part.positions.iter().fold(
+                        (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+                        |(min, max), p| (min.min(*p), max.max(*p)),
+                    );
+                    let (scale, rotation, _) = part.to_target.to_scale_rotation_translation();
+                    ColliderBounds {
+                        center: part.to_target.transform_point3((min + max) / 2.0),
+                        rotation,
+                        half_extents: (max - min) / 2.0 * scale.abs(),
+                    }
+                })
+                .collect();
+            let main = *boxes.iter().max_by(|a, b| {
+                let volume = |b: &ColliderBounds| b.half_extents.x * b.half_extents.y * b.half_extents.z;
+                volume(a).total_cmp(&volume(b))
+            })?;
+            let collider = Collider::compound(
+                boxes
+                    .iter()
+                    .map(|b| {
+                        let h = b.half_extents;
+                        (b.center, b.rotation, Collider::cuboid(h.x, h.y, h.z))
+                    })
+                    .collect(),
+            );
+            Some((collider, main))
+        }
+        SceneColliderShape::ConvexHull => Some((Collider::convex_hull(&points)?, aabb)),
+        SceneColliderShape::Ball => {
+            let radius = points
+                .iter()
+                .map(|p| p.distance(aabb.center))
+                .fold(0.0, f32::max);
+            let collider = if aabb.center.length() < 1e-4 {
+                Collider::ball(radius)
+            } else {
+                Collider::compound(vec![(aabb.center, Quat::IDENTITY, Collider::ball(radius))])
+            };
+            Some((
+                collider,
+                ColliderBounds {
+                    half_extents: Vec3::splat(radius),
+                    ..aabb
+                },
+            ))
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::sync::watch;
+use warp::ws::{Message, WebSocket};
+
+use crate::GameState;
+use crate::game::online::protocol::PoseData;
+use crate::game::rooms::RoomSnapshots;
+use crate::game::scoring::MatchScore;
+use crate::game::utils::{Ball, BallTableCollisionCount, LaunchState, Racket};
+
+/// 观战协议版本，格式变化时递增，见 docs/spectator-protocol.md
+pub const SPECTATOR_PROTOCOL_VERSION: u32 = 1;
+/// 每秒推送给观战端的帧数，与游戏帧率无关
+pub const SPECTATOR_RATE: u32 = 20;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SpectatorScore {
+    pub launched: bool,
+    pub table_bounces: u32,
+    /// 击球数，目前只有房间会统计
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hits: Option<u32>,
+    /// 回合数，目前只有房间会统计
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rallies: Option<u32>,
+    /// 本局双方的得分，计分的比赛才有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points: Option<[u32; 2]>,
+    /// 双方赢得的局数，计分的比赛才有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub games: Option<[u32; 2]>,
+    /// 当前发球方（0 或 1），计分的比赛才有
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<usize>,
+}
+
+/// 某一时刻的比赛状态
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SpectatorFrame {
+    pub seq: u64,
+    pub time: f32,
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room: Option<String>,
+    pub ball: Option<PoseData>,
+    pub rackets: Vec<PoseData>,
+    pub score: SpectatorScore,
+}
+
+/// 本机比赛和全部房间的最新状态
+#[derive(Clone, Debug, Default)]
+pub struct SpectatorWorld {
+    pub main: SpectatorFrame,
+    pub rooms: Vec<SpectatorFrame>,
+}
+
+impl SpectatorWorld {
+    fn frame(&self, room: Option<&str>) -> Option<&SpectatorFrame> {
+        match room {
+            None => Some(&self.main),
+            Some(code) => self
+                .rooms
+                .iter()
+                .find(|frame| frame.room.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(code))),
+        }
+    }
+}
+
+/// Bevy 每帧写入最新状态，观战连接按固定频率读取，新连接可以立即拿到当前状态
+#[derive(Resource, Clone)]
+pub struct SpectatorFeed(pub Arc<watch::Sender<SpectatorWorld>>);
+
+impl Default for SpectatorFeed {
+    fn default() -> Self {
+        SpectatorFeed(Arc::new(watch::channel(SpectatorWorld::default()).0))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SpectatorMessage<'a> {
+    Welcome {
+        protocol: u32,
+        rate: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        room: Option<&'a str>,
+    },
+    /// 连接建立后立即发送一次完整状态
+    Snapshot { frame: &'a SpectatorFrame },
+    Frame { frame: &'a SpectatorFrame },
+    Error { message: String },
+}
+
+impl SpectatorMessage<'_> {
+    fn to_message(&self) -> Message {
+        Message::text(serde_json::to_string(self).unwrap_or_default())
+    }
+}
+
+/// 本机比赛的计分来源，没有计分的模式（练习）没有 [`MatchScore`]
+#[derive(SystemParam)]
+pub struct MainScore<'w> {
+    launch_state: Res<'w, LaunchState>,
+    counter: Res<'w, BallTableCollisionCount>,
+    score: Option<Res<'w, MatchScore>>,
+}
+
+impl MainScore<'_> {
+    fn spectator_score(&self) -> SpectatorScore {
+        SpectatorScore {
+            launched: self.launch_state.launched,
+            table_bounces: self.counter.count,
+            points: self.score.as_ref().map(|score| score.points),
+            games: self.score.as_ref().map(|score| score.games),
+            server: self.score.as_ref().map(|score| score.server),
+            ..default()
+        }
+    }
+}
+
+pub fn publish_spectator_state(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    feed: Res<SpectatorFeed>,
+    main_score: MainScore,
+    room_snapshots: Option<Res<RoomSnapshots>>,
+    balls: Query<&Transform, With<Ball>>,
+    rackets: Query<&Transform, (With<Racket>, Without<Ball>)>,
+) {
+    let seq = feed.0.borrow().main.seq + 1;
+    let elapsed = time.elapsed_secs();
+
+    let main = SpectatorFrame {
+        seq,
+        time: elapsed,
+        state: format!("{:?}", state.get()),
+        room: None,
+        ball: balls.iter().next().map(PoseData::from),
+        rackets: rackets.iter().map(PoseData::from).collect(),
+        score: main_score.spectator_score(),
+    };
+    let rooms = room_snapshots
+        .map(|snapshots| {
+            snapshots
+                .0
+                .iter()
+                .map(|snapshot| SpectatorFrame {
+                    seq,
+                    time: elapsed,
+                    state: "Room".to_string(),
+                    room: Some(snapshot.code.clone()),
+                    ball: Some(PoseData::from(&snapshot.ball)),
+                    rackets: snapshot.rackets.iter().map(PoseData::from).collect(),
+                    score: SpectatorScore {
+                        hits: Some(snapshot.hits),
+                        rallies: Some(snapshot.rallies),
+                        ..default()
+                    },
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    feed.0.send_replace(SpectatorWorld { main, rooms });
+}
+
+/// 只读的观战连接，由 controller_server 的 `/spectate` 路由调用。
+/// 观战端发来的消息一律忽略，只处理关闭。
+pub async fn handle_spectator(ws_stream: WebSocket, room: Option<String>, feed: SpectatorFeed) {
+    let (mut sink, mut stream) = ws_stream.split();
+    let mut world = feed.0.subscribe();
+
+    let snapshot = world.borrow_and_update().frame(room.as_deref()).cloned();
+    let Some(snapshot) = snapshot else {
+        let message = SpectatorMessage::Error {
+            message: format!("unknown room {}", room.unwrap_or_default()),
+        };
+        let _ = sink.send(message.to_message()).await;
+        let _ = sink.close().await;
+        return;
+    };
+
+    let welcome = SpectatorMessage::Welcome {
+        protocol: SPECTATOR_PROTOCOL_VERSION,
+        rate: SPECTATOR_RATE,
+        room: room.as_deref(),
+    };
+    if sink.send(welcome.to_message()).await.is_err()
+        || sink
+            .send(SpectatorMessage::Snapshot { frame: &snapshot }.to_message())
+            .await
+            .is_err()
+    {
+        return;
+    }
+    info!("👀 观战端已连接 {:?}", room);
+
+    let mut last_seq = snapshot.seq;
+    let mut interval = tokio::time::interval(Duration::from_secs(1) / SPECTATOR_RATE);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let frame = world.borrow_and_update().frame(room.as_deref()).cloned();
+                let Some(frame) = frame else {
+                    break;
+                };
+                if frame.seq == last_seq {
+                    continue;
+                }
+                last_seq = frame.seq;
+                if sink.send(SpectatorMessage::Frame { frame: &frame }.to_message()).await.is_err() {
+                    break;
+                }
+            }
+            msg = stream.next() => {
+                match msg {
+                    Some(Ok(msg)) if msg.is_close() => break,
+                    Some(Ok(_)) => {}
+                    Some(Err(_)) | None => break,
+                }
+            }
+        }
+    }
+    info!("👋 观战端已断开 {:?}", room);
+}
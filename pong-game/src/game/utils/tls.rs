@@ -0,0 +1,217 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::BufReader,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, anyhow};
+use bevy::prelude::*;
+use rustls::ServerConfig;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+use tokio::sync::watch;
+
+use crate::game::utils::ServerStatus;
+use crate::locale::LocaleMessage;
+
+/// 证书缓存目录，可通过 `PONG_CERT_DIR` 覆盖
+const DEFAULT_CERT_DIR: &str = "./certs";
+/// 证书文件轮询间隔
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// 证书重新加载失败的错误文本 id，重新加载成功时只清除这一条错误
+const RELOAD_ERROR: &str = "status-error-reload";
+
+#[derive(Clone, Debug)]
+pub struct CertPaths {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+/// 网络服务使用的传输方式，启动时由环境变量决定
+#[derive(Resource, Clone, Debug)]
+pub enum TlsMode {
+    /// wss:// / https://，监听所有网卡
+    Secure(CertPaths),
+    /// ws:// / http://，仅监听 localhost，用于本地开发
+    Plain,
+    /// 证书不可用，网络服务不会启动
+    Unavailable(String),
+}
+
+impl TlsMode {
+    pub fn bind_ip(&self) -> IpAddr {
+        match self {
+            TlsMode::Plain => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            _ => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        }
+    }
+
+    pub fn ws_scheme(&self) -> &'static str {
+        match self {
+            TlsMode::Plain => "ws",
+            _ => "wss",
+        }
+    }
+
+    pub fn http_scheme(&self) -> &'static str {
+        match self {
+            TlsMode::Plain => "http",
+            _ => "https",
+        }
+    }
+}
+
+/// 证书变化时递增的版本号，服务端收到通知后重建 TLS 配置
+#[derive(Resource, Clone)]
+pub struct CertReload(pub watch::Receiver<u64>);
+
+/// 按以下顺序决定 TLS 配置：
+/// 1. `PONG_PLAIN_WS=1` 时使用明文 ws://
+/// 2. 设置了 `SSL_CERT_PATH` / `SSL_KEY_PATH` 时使用指定证书，只设置其中一个视为配置错误
+/// 3. 否则为本机局域网 IP 生成并缓存自签名证书
+pub fn resolve_tls_mode() -> TlsMode {
+    if env::var("PONG_PLAIN_WS").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")) {
+        warn!("⚠️ 已启用明文 ws:// 模式，仅监听 localhost");
+        return TlsMode::Plain;
+    }
+
+    let resolved = match (env::var("SSL_CERT_PATH"), env::var("SSL_KEY_PATH")) {
+        (Ok(cert), Ok(key)) => configured_cert(PathBuf::from(cert), PathBuf::from(key)),
+        (Ok(_), Err(_)) => Err(anyhow!("设置了 SSL_CERT_PATH 但缺少 SSL_KEY_PATH")),
+        (Err(_), Ok(_)) => Err(anyhow!("设置了 SSL_KEY_PATH 但缺少 SSL_CERT_PATH")),
+        (Err(_), Err(_)) => self_signed_cert(),
+    };
+    match resolved {
+        Ok(paths) => TlsMode::Secure(paths),
+        Err(e) => {
+            error!("❌ TLS 证书不可用: {:#}", e);
+            TlsMode::Unavailable(format!("{:#}", e))
+        }
+    }
+}
+
+fn configured_cert(cert: PathBuf, key: PathBuf) -> Result<CertPaths> {
+    let paths = CertPaths { cert, key };
+    // 提前校验一次，配置错误时直接在界面上提示
+    load_server_config(&paths)?;
+    info!("🔐 使用已配置的证书: {}", paths.cert.display());
+    Ok(paths)
+}
+
+fn self_signed_cert() -> Result<CertPaths> {
+    let dir = env::var("PONG_CERT_DIR").unwrap_or_else(|_| DEFAULT_CERT_DIR.to_string());
+    let dir = PathBuf::from(dir);
+    let ip = local_ip_address::local_ip().context("无法获取局域网 IP")?;
+
+    // 文件名带上 IP，换网络后自动生成新证书
+    let paths = CertPaths {
+        cert: dir.join(format!("pong-{}.crt", ip)),
+        key: dir.join(format!("pong-{}.key", ip)),
+    };
+    if paths.cert.exists() && paths.key.exists() && load_server_config(&paths).is_ok() {
+        info!("🔐 使用缓存的自签名证书: {}", paths.cert.display());
+        return Ok(paths);
+    }
+
+    let subject_alt_names = vec![
+        ip.to_string(),
+        "pong.local".to_string(),
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+    ];
+    let generated =
+        rcgen::generate_simple_self_signed(subject_alt_names).context("生成自签名证书失败")?;
+    fs::create_dir_all(&dir).context("无法创建证书目录")?;
+    fs::write(&paths.cert, generated.cert.pem()).context("无法写入证书文件")?;
+    fs::write(&paths.key, generated.key_pair.serialize_pem()).context("无法写入私钥文件")?;
+    info!("🆕 已为 {} 生成自签名证书: {}", ip, paths.cert.display());
+    Ok(paths)
+}
+
+pub fn load_server_config(paths: &CertPaths) -> Result<ServerConfig> {
+    let certs = load_certs(&paths.cert)?;
+    let key = load_key(&paths.key)?;
+    ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("构建 TLS 配置失败")
+}
+
+// 加载 X.509 PEM 格式证书
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certfile = File::open(path).context("无法打开证书文件")?;
+    let mut reader = BufReader::new(certfile);
+    certs(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .context("读取证书失败")
+}
+
+// 加载私钥，支持 PKCS8 和 RSA
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let keyfile = File::open(path).context("无法打开私钥文件")?;
+    let mut reader = BufReader::new(keyfile);
+
+    // 尝试 PKCS8 格式
+    let keys = pkcs8_private_keys(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .context("读取 PKCS8 私钥失败")?;
+    if let Some(key) = keys.into_iter().next() {
+        return Ok(key.into());
+    }
+
+    // 尝试 RSA 格式
+    let keyfile = File::open(path).context("无法重新打开私钥文件")?;
+    let mut reader = BufReader::new(keyfile);
+    let keys = rsa_private_keys(&mut reader)
+        .collect::<Result<Vec<_>, _>>()
+        .context("读取 RSA 私钥失败")?;
+
+    keys.into_iter()
+        .next()
+        .map(Into::into)
+        .ok_or_else(|| anyhow::anyhow!("未找到有效的私钥"))
+}
+
+fn modified_at(paths: &CertPaths) -> Option<(SystemTime, SystemTime)> {
+    let cert = fs::metadata(&paths.cert).and_then(|m| m.modified()).ok()?;
+    let key = fs::metadata(&paths.key).and_then(|m| m.modified()).ok()?;
+    Some((cert, key))
+}
+
+/// 轮询证书文件的修改时间，文件被替换且能成功解析时通知服务端热重载
+pub fn watch_certificates(
+    rt: &tokio::runtime::Runtime,
+    paths: CertPaths,
+    status: ServerStatus,
+) -> CertReload {
+    let (tx, rx) = watch::channel(0u64);
+    rt.spawn(async move {
+        let mut last = modified_at(&paths);
+        let mut interval = tokio::time::interval(RELOAD_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let current = modified_at(&paths);
+            if current.is_none() || current == last {
+                continue;
+            }
+            last = current;
+            match load_server_config(&paths) {
+                Ok(_) => {
+                    info!("🔄 检测到证书更新，重新加载");
+                    status.clear_error(RELOAD_ERROR);
+                    tx.send_modify(|version| *version += 1);
+                }
+                Err(e) => {
+                    error!("❌ 新证书无效，继续使用旧证书: {:#}", e);
+                    status.set_error(Some(
+                        LocaleMessage::new(RELOAD_ERROR).arg("detail", format!("{:#}", e)),
+                    ));
+                }
+            }
+        }
+    });
+    CertReload(rx)
+}
@@ -1,142 +1,113 @@
 use bevy::prelude::*;
-use futures_util::StreamExt;
-use std::{fs::File, io::BufReader, sync::Arc};
-
-use async_tungstenite::tokio::accept_async;
-use async_tungstenite::tungstenite::Message;
-use rustls::ServerConfig;
-use rustls::pki_types::{CertificateDer, PrivateKeyDer};
-use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
-use tokio::net::TcpListener;
-use tokio_rustls::{TlsAcceptor, rustls};
-
-use crate::game::utils::{CommandDataType, RacketCommandQueue, RacketTransformCommand, WsRuntime};
-use anyhow::{Context, Result};
-
-use dotenv::dotenv;
-use std::env;
-
-pub fn start_websocket_server(rt: Res<WsRuntime>, command_queue: Res<RacketCommandQueue>) {
-    let command_queue = command_queue.clone();
-    rt.0.spawn(async move {
-        dotenv().ok();
-
-        let cert_url = env::var("SSL_CERT_PATH").expect("CERT_URL 环境变量未设置");
-        let key_url = env::var("SSL_KEY_PATH").expect("KEY_URL 环境变量未设置");
-        // 1. 加载证书与私钥
-        let certs = load_certs(&cert_url).unwrap();
-        let key = load_key(&key_url).unwrap();
-
-        let config = ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(certs, key)
-            .context("构建 TLS 配置失败")
-            .unwrap();
-        let acceptor = TlsAcceptor::from(Arc::new(config));
-
-        // 2. 启动 TCP 监听
-        let listener = TcpListener::bind("0.0.0.0:8080")
-            .await
-            .context("无法绑定端口 8080")
-            .unwrap();
-        println!("✅ WSS 服务器已启动，监听 8080 端口");
-
-        // 3. 接收连接循环
-        loop {
-            let (stream, addr) = listener.accept().await.unwrap();
-            let acceptor = acceptor.clone();
-
-            let command_queue = command_queue.clone();
-
-            tokio::spawn(async move {
-                match acceptor.accept(stream).await {
-                    Ok(tls_stream) => {
-                        println!("🔐 已建立 TLS 连接: {:?}", addr);
-                        // TODO: 在这里继续处理 WebSocket 握手逻辑
-                        let mut ws_stream = match accept_async(tls_stream).await {
-                            Ok(ws_stream) => ws_stream,
-                            Err(e) => {
-                                eprintln!("❌ WebSocket 握手失败: {}", e);
-                                return;
-                            }
-                        };
-                        println!("🔗 WebSocket 握手成功: {:?}", addr);
-                        while let Some(msg) = ws_stream.next().await {
-                            match msg {
-                                Ok(Message::Text(text)) => {
-                                    // println!("收到文本: {}", text);
-                                    let reply = text.to_uppercase();
-                                    if ws_stream.send(Message::Text(reply.into())).await.is_err() {
-                                        break;
-                                    }
-                                    // 操作 racket
-                                    if let Some(command) = parse_transform_command(&text) {
-                                        let mut queue = command_queue.0.lock().unwrap();
-                                        queue.push(command);
-                                        // println!("队列长度: {}", queue.len());
-                                    }
-                                }
-                                Ok(Message::Close(_)) => {
-                                    println!("🚪 连接关闭");
-                                    break;
-                                }
-                                Err(e) => {
-                                    eprintln!("接收消息出错: {}", e);
-                                    break;
-                                }
-                                _ => {}
+use futures_util::{SinkExt, StreamExt};
+use std::net::SocketAddr;
+
+use warp::ws::{Message, WebSocket};
+
+use crate::game::input::MAX_PLAYERS;
+use crate::game::utils::{
+    CommandDataType, ControllerSeats, RacketCommandQueue, RacketTransformCommand, RoomRegistry,
+    ServerStatus,
+};
+use crate::menu::navigation::MenuInput;
+
+/// 控制器与游戏之间的消息协议版本，格式变化时递增
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// 处理一条已完成升级的 WebSocket 连接，由 controller_server 的 `/ws` 路由调用。
+/// 连接默认控制本机游戏，发送 `join:<房间码>` 后改为控制对应房间。
+/// 连接后先收到 `seat:<n>` 告知所在座位，发送 `seat:<n>` 可以换座位。
+pub async fn handle_socket(
+    mut ws_stream: WebSocket,
+    addr: Option<SocketAddr>,
+    command_queue: RacketCommandQueue,
+    rooms: RoomRegistry,
+    seats: ControllerSeats,
+    status: ServerStatus,
+) {
+    info!("🔗 WebSocket 握手成功: {:?}", addr);
+    status.0.lock().unwrap().controllers += 1;
+    let mut target = command_queue;
+    let mut room_code: Option<String> = None;
+    let mut seat = seats.take();
+    info!("🪑 {:?} 坐到座位 {}", addr, seat + 1);
+    if ws_stream.send(Message::text(format!("seat:{}", seat + 1))).await.is_err() {
+        seats.release(seat);
+        status.0.lock().unwrap().controllers -= 1;
+        return;
+    }
+    while let Some(msg) = ws_stream.next().await {
+        match msg {
+            Ok(msg) if msg.is_text() => {
+                let text = msg.to_str().unwrap_or_default();
+                trace!("收到文本: {}", text);
+                if let Some(code) = text.strip_prefix("join:") {
+                    let code = code.trim().to_uppercase();
+                    let reply = match rooms.join(&code) {
+                        Some(queue) => {
+                            if let Some(previous) = room_code.replace(code.clone()) {
+                                rooms.leave(&previous);
                             }
+                            target = queue;
+                            info!("🏠 {:?} 加入房间 {}", addr, code);
+                            format!("joined:{}", code)
                         }
+                        None => format!("error:unknown room {}", code),
+                    };
+                    if ws_stream.send(Message::text(reply)).await.is_err() {
+                        break;
                     }
-                    Err(e) => {
-                        eprintln!("❌ TLS 握手失败: {}", e);
+                    continue;
+                }
+                if let Some(number) = text.strip_prefix("seat:") {
+                    let reply = match number.trim().parse::<usize>() {
+                        Ok(number) if (1..=MAX_PLAYERS).contains(&number) => {
+                            seats.switch(seat, number - 1);
+                            seat = number - 1;
+                            info!("🪑 {:?} 换到座位 {}", addr, number);
+                            format!("seat:{}", number)
+                        }
+                        _ => format!("error:unknown seat {}", number.trim()),
+                    };
+                    if ws_stream.send(Message::text(reply)).await.is_err() {
+                        break;
                     }
+                    continue;
                 }
-            });
+                let reply = text.to_uppercase();
+                if ws_stream.send(Message::text(reply)).await.is_err() {
+                    break;
+                }
+                // 操作 racket
+                if let Some(command) = parse_transform_command(text, seat) {
+                    let mut queue = target.0.lock().unwrap();
+                    queue.push(command);
+                    trace!("队列长度: {}", queue.len());
+                }
+            }
+            Ok(msg) if msg.is_close() => {
+                info!("🚪 连接关闭");
+                break;
+            }
+            Err(e) => {
+                error!("接收消息出错: {}", e);
+                break;
+            }
+            _ => {}
         }
-    });
-    println!("✅ WebSocket 服务器已启动");
-}
-
-// 加载 X.509 PEM 格式证书
-fn load_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
-    let certfile = File::open(path).context("无法打开证书文件")?;
-    let mut reader = BufReader::new(certfile);
-    certs(&mut reader)
-        .collect::<Result<Vec<_>, _>>()
-        .context("读取证书失败")
-}
-
-// 加载私钥，支持 PKCS8 和 RSA
-fn load_key(path: &str) -> Result<PrivateKeyDer<'static>> {
-    let keyfile = File::open(path).context("无法打开私钥文件")?;
-    let mut reader = BufReader::new(keyfile);
-
-    // 尝试 PKCS8 格式
-    let mut keys = pkcs8_private_keys(&mut reader)
-        .collect::<Result<Vec<_>, _>>()
-        .context("读取 PKCS8 私钥失败")?;
-    if let Some(key) = keys.into_iter().next() {
-        return Ok(key.into());
     }
-
-    // 尝试 RSA 格式
-    let keyfile = File::open(path).context("无法重新打开私钥文件")?;
-    let mut reader = BufReader::new(keyfile);
-    let mut keys = rsa_private_keys(&mut reader)
-        .collect::<Result<Vec<_>, _>>()
-        .context("读取 RSA 私钥失败")?;
-
-    keys.into_iter()
-        .next()
-        .map(Into::into)
-        .ok_or_else(|| anyhow::anyhow!("未找到有效的私钥"))
+    if let Some(code) = room_code {
+        rooms.leave(&code);
+    }
+    seats.release(seat);
+    status.0.lock().unwrap().controllers -= 1;
 }
 
-fn parse_transform_command(text: &str) -> Option<RacketTransformCommand> {
+fn parse_transform_command(text: &str, seat: usize) -> Option<RacketTransformCommand> {
     // 简单解析：x,y,z;rx,ry,rz,rw
     // 新格式: rotation:rx,ry,rz,rw
     //        position:dx,dy,dz
+    //        nav:up|down|left|right|select|back
     let parts: Vec<&str> = text.split(':').collect();
     if parts.len() != 2 {
         return None;
@@ -154,6 +125,7 @@ fn parse_transform_command(text: &str) -> Option<RacketTransformCommand> {
                     rot_vals[2],
                     rot_vals[3],
                 )),
+                seat,
             });
         }
         "position" => {
@@ -167,8 +139,13 @@ fn parse_transform_command(text: &str) -> Option<RacketTransformCommand> {
                     pos_vals[1],
                     pos_vals[2],
                 )),
+                seat,
             });
         }
+        "nav" => MenuInput::parse(parts[1]).map(|input| RacketTransformCommand {
+            command: CommandDataType::Menu(input),
+            seat,
+        }),
         _ => return None,
     }
 }
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Display;
+use std::path::Path;
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+
+/// 文本打包进二进制，避免界面在资源加载完之前没有文字
+const EN_SOURCE: &str = include_str!("../../assets/locales/en.ftl");
+const ZH_SOURCE: &str = include_str!("../../assets/locales/zh.ftl");
+
+/// 中文字体，相对 assets 目录，可通过 `PONG_CJK_FONT` 覆盖
+const DEFAULT_CJK_FONT: &str = "fonts/NotoSansSC-Regular.otf";
+
+/// 界面语言，默认值来自 `PONG_LANG`（en / zh），可在 Settings 页面中修改
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Chinese];
+
+    /// 语言选项总是用该语言自己的名字显示
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Chinese => "中文",
+        }
+    }
+
+    /// 没有该语言的字体时显示的名字，只用默认字体中有的字符
+    fn latin_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Chinese => "Chinese",
+        }
+    }
+
+    /// 默认字体能否显示该语言的文本
+    fn needs_cjk_font(&self) -> bool {
+        *self == Language::Chinese
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::English => EN_SOURCE,
+            Language::Chinese => ZH_SOURCE,
+        }
+    }
+
+    fn from_env() -> Self {
+        match env::var("PONG_LANG") {
+            Ok(code) if code.to_lowercase().starts_with("zh") => Language::Chinese,
+            _ => Language::English,
+        }
+    }
+}
+
+/// 一种语言的全部文本。文件格式为 Fluent（.ftl）的子集：
+/// `id = 文本`，缩进的行接在上一条后面并换行，`{ $name }` 为参数，`#` 开头为注释
+struct Bundle(HashMap<String, String>);
+
+impl Bundle {
+    fn parse(source: &str) -> Self {
+        let mut messages = HashMap::new();
+        let mut current: Option<(String, Vec<&str>)> = None;
+        for line in source.lines() {
+            if line.starts_with(char::is_whitespace) && !line.trim().is_empty() {
+                if let Some((_, lines)) = current.as_mut() {
+                    lines.push(line.trim());
+                }
+                continue;
+            }
+            if let Some((id, lines)) = current.take() {
+                messages.insert(id, lines.join("\n"));
+            }
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((id, value)) = line.split_once('=') {
+                let value = value.trim();
+                let lines = if value.is_empty() { vec![] } else { vec![value] };
+                current = Some((id.trim().to_string(), lines));
+            }
+        }
+        if let Some((id, lines)) = current {
+            messages.insert(id, lines.join("\n"));
+        }
+        Bundle(messages)
+    }
+}
+
+/// 当前语言和所有语言的文本。缺少的文本回退到英文，英文也没有时显示 id；
+/// 中文字体不可用时整个中文界面回退到英文，避免显示成方块。
+/// 界面在生成时取文本，切换语言后重新进入页面即可更新
+#[derive(Resource)]
+pub struct Locale {
+    pub language: Language,
+    bundles: HashMap<Language, Bundle>,
+    /// 中文字体存在并且加载成功（加载完成前视为可用）
+    cjk_font: bool,
+}
+
+impl Locale {
+    fn new(language: Language) -> Self {
+        let bundles = Language::ALL
+            .into_iter()
+            .map(|language| (language, Bundle::parse(language.source())))
+            .collect();
+        Locale {
+            language,
+            bundles,
+            cjk_font: true,
+        }
+    }
+
+    /// 实际显示的语言，缺少字体时为英文
+    fn display_language(&self) -> Language {
+        if self.language.needs_cjk_font() && !self.cjk_font {
+            Language::English
+        } else {
+            self.language
+        }
+    }
+
+    /// 语言选项的名字，缺少字体时改用拉丁字母
+    pub fn language_name(&self, language: Language) -> &'static str {
+        if language.needs_cjk_font() && !self.cjk_font {
+            language.latin_name()
+        } else {
+            language.native_name()
+        }
+    }
+
+    pub fn text(&self, id: &str) -> String {
+        self.format(id, &[])
+    }
+
+    /// 取出文本并把 `{ $name }` 替换为参数
+    pub fn format(&self, id: &str, args: &[(&str, &dyn Display)]) -> String {
+        let Some(pattern) = [self.display_language(), Language::English]
+            .iter()
+            .find_map(|language| self.bundles.get(language)?.0.get(id))
+        else {
+            return id.to_string();
+        };
+
+        let mut result = String::with_capacity(pattern.len());
+        let mut rest = pattern.as_str();
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let Some(end) = rest[start..].find('}') else {
+                rest = &rest[start..];
+                break;
+            };
+            let name = rest[start + 1..start + end].trim().trim_start_matches('$');
+            match args.iter().find(|(arg, _)| *arg == name) {
+                Some((_, value)) => result.push_str(&value.to_string()),
+                None => result.push_str(&rest[start..=start + end]),
+            }
+            rest = &rest[start + end + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    pub fn message(&self, message: &LocaleMessage) -> String {
+        let args = message
+            .args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn Display))
+            .collect::<Vec<_>>();
+        self.format(message.id, &args)
+    }
+}
+
+/// 稍后才翻译的文本，例如网络任务写入、界面显示时才知道语言的错误信息
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocaleMessage {
+    pub id: &'static str,
+    args: Vec<(&'static str, String)>,
+}
+
+impl LocaleMessage {
+    pub fn new(id: &'static str) -> Self {
+        LocaleMessage { id, args: Vec::new() }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+}
+
+/// 中文界面使用的字体，没有找到字体文件时为 `None`
+#[derive(Resource, Default)]
+struct LocaleFont {
+    cjk: Option<Handle<Font>>,
+}
+
+pub fn locale_plugin(app: &mut App) {
+    app.insert_resource(Locale::new(Language::from_env()))
+        .init_resource::<LocaleFont>()
+        .add_systems(Startup, load_cjk_font)
+        .add_systems(Update, (check_cjk_font, apply_locale_font).chain());
+}
+
+/// 默认字体没有中文字形，需要在 assets 中放一个中文字体，没有时中文界面改用英文
+fn load_cjk_font(asset_server: Res<AssetServer>, mut locale: ResMut<Locale>, mut font: ResMut<LocaleFont>) {
+    let path = env::var("PONG_CJK_FONT").unwrap_or_else(|_| DEFAULT_CJK_FONT.to_string());
+    if Path::new("assets").join(&path).exists() {
+        font.cjk = Some(asset_server.load(path));
+    } else {
+        locale.cjk_font = false;
+        if locale.language.needs_cjk_font() {
+            warn!("⚠️ 没有找到中文字体 assets/{}，中文界面改用英文", path);
+        }
+    }
+}
+
+/// 字体文件损坏或格式不支持时同样回退到英文
+fn check_cjk_font(asset_server: Res<AssetServer>, mut locale: ResMut<Locale>, mut font: ResMut<LocaleFont>) {
+    let Some(handle) = &font.cjk else {
+        return;
+    };
+    if let Some(LoadState::Failed(err)) = asset_server.get_load_state(handle) {
+        warn!("⚠️ 中文字体加载失败，中文界面改用英文: {}", err);
+        font.cjk = None;
+        locale.cjk_font = false;
+    }
+}
+
+/// 切换语言时替换所有文本的字体，新生成的文本也使用当前语言的字体
+fn apply_locale_font(
+    locale: Res<Locale>,
+    font: Res<LocaleFont>,
+    mut texts: Query<(Ref<Text>, &mut TextFont)>,
+) {
+    let handle = match (locale.language, &font.cjk) {
+        (Language::Chinese, Some(cjk)) => cjk.clone(),
+        _ => Handle::default(),
+    };
+    for (text, mut text_font) in texts.iter_mut() {
+        if (locale.is_changed() || text.is_added()) && text_font.font != handle {
+            text_font.font = handle.clone();
+        }
+    }
+}
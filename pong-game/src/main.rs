@@ -3,15 +3,23 @@ use bevy::prelude::*;
 pub mod game;
 pub mod menu;
 pub mod components;
+pub mod locale;
 
-fn despawn_screen<T: Component>(to_despawn: Query<Entity, With<T>>, mut commands: Commands) {
-    for entity in &to_despawn {
-        commands.entity(entity).despawn();
+/// 移除带有标记的实体和它们的子节点，父节点也带标记时由父节点一起移除
+fn despawn_screen<T: Component>(
+    to_despawn: Query<(Entity, Option<&Parent>), With<T>>,
+    marked: Query<(), With<T>>,
+    mut commands: Commands,
+) {
+    for (entity, parent) in &to_despawn {
+        if parent.is_none_or(|parent| !marked.contains(parent.get())) {
+            commands.entity(entity).despawn_recursive();
+        }
     }
 }
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
-enum GameState {
+pub enum GameState {
     #[default]
     Menu,
     GameEntering,
@@ -20,16 +28,34 @@ enum GameState {
     GamePracticeEntering,
     GamePracticeIniting,
     GamePracticeRunning,
+    GameOnlineEntering,
+    GameOnlineIniting,
+    GameOnlineRunning,
+    Spectating,
 }
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins)
         .init_state::<GameState>()
         .add_plugins((
+            components::components_plugin,
             menu::menu_plugin,
             game::game_plugin,
+            locale::locale_plugin,
+            game::input::racket_input_plugin,
+            game::scoring::scoring_plugin,
+            game::pause::pause_plugin,
+            game::stats::stats_plugin,
+            game::tournament::tournament_plugin,
+            game::camera::camera_rig_plugin,
+            game::audio::audio_plugin,
+            game::effects::effects_plugin,
             game::practice::game_practice_plugin,
-        ))
-        .run();
+            game::rooms::game_rooms_plugin,
+            game::online::game_online_plugin,
+        ));
+    #[cfg(feature = "debug-overlay")]
+    app.add_plugins(game::debug::debug_plugin);
+    app.run();
 }
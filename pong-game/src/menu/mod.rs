@@ -1,158 +1,754 @@
+use std::cmp::Reverse;
+
 use bevy::{
     app::AppExit,
+    ecs::system::{EntityCommands, SystemParam},
     prelude::*,
 };
 
 use super::{GameState, despawn_screen};
+use crate::components::Popup;
+use crate::components::button::{ButtonActions, ButtonKind, SelectedOption, button};
+use crate::components::dropdown::{Dropdown, dropdown};
+use crate::components::modal::{CloseModal, spawn_modal};
+use crate::components::qr::qr_code;
+use crate::components::slider::{Slider, spawn_slider};
+use crate::components::theme::{TextSize, UiTheme, option_row, row_label, screen_column};
+use crate::components::toggle::{Toggle, toggle};
+use crate::game::LocalVersus;
+use crate::game::online::net::OnlineRole;
+use crate::game::audio::SoundSettings;
+use crate::game::camera::viewport::{SplitLayout, SplitScreen};
+use crate::game::effects::{Effect, EffectSettings};
+use crate::game::input::ai::AiDifficulty;
+use crate::game::input::{ControlScheme, PlayerControls};
+use crate::game::stats::{self, MatchMode, StatsStore};
+use crate::game::utils::{ControllerQrCode, RoomRegistry, ServerStatusText};
+use crate::locale::{Language, Locale};
+
+pub mod navigation;
+mod tournament;
 
+use navigation::MenuInput;
+
+/// 菜单页面，子页面通过 [`MenuState::parent`] 返回上一级
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
 enum MenuState {
     #[default]
     Disabled,
     Main,
+    ModeSelect,
+    Difficulty,
+    Settings,
+    Stats,
+    Credits,
+    /// 锦标赛报名表或对阵表
+    Tournament,
+}
+
+impl MenuState {
+    const SCREENS: [MenuState; 7] = [
+        MenuState::Main,
+        MenuState::ModeSelect,
+        MenuState::Difficulty,
+        MenuState::Settings,
+        MenuState::Stats,
+        MenuState::Credits,
+        MenuState::Tournament,
+    ];
+
+    /// 返回键进入的上一级页面，主菜单没有上一级
+    fn parent(&self) -> Option<MenuState> {
+        match self {
+            MenuState::Disabled | MenuState::Main => None,
+            MenuState::Difficulty | MenuState::Tournament => Some(MenuState::ModeSelect),
+            MenuState::ModeSelect | MenuState::Settings | MenuState::Stats | MenuState::Credits => {
+                Some(MenuState::Main)
+            }
+        }
+    }
 }
 
 #[derive(Component)]
 enum MenuButtonAction {
     Play,
+    /// 弹出确认对话框
     Quit,
+    ConfirmQuit,
+    /// 两名玩家都使用 Settings 页面中选择的控制方式
+    LocalMatch,
+    VersusAi,
+    /// 玩家 1、3 对玩家 2、4，各自使用 Settings 页面中选择的控制方式
+    Doubles,
+    Tournament,
+    /// 玩家 2 由电脑控制，使用选择的难度
+    Difficulty(AiDifficulty),
     Practice,
+    HostOnline,
+    JoinOnline,
+    Rooms,
+    Settings,
+    Stats,
+    Credits,
+    Back,
 }
 
+/// 所有菜单页面上的实体，离开页面时一起清除
+#[derive(Component)]
+struct OnMenuScreen;
+
+/// Stats 页面中每个列表显示的条数
+const STATS_LIST_LEN: usize = 6;
+
+/// Settings 页面中某名玩家的控制方式
+#[derive(Component, Clone, Copy, PartialEq)]
+struct ControlSchemeOption {
+    player: usize,
+}
+
+/// Settings 页面中的分屏方式
+#[derive(Component)]
+struct SplitLayoutOption;
+
+/// Settings 页面中的音量滑块
+#[derive(Component)]
+struct VolumeOption;
+
+/// 音量滑块右边的数值
+#[derive(Component)]
+struct VolumeLabel;
+
+/// Settings 页面中某个视觉效果的开关
+#[derive(Component, Clone, Copy, PartialEq)]
+struct EffectOption(Effect);
+
+/// Settings 页面中的界面语言
 #[derive(Component)]
-struct OnMainMenuScreen;
+struct LanguageOption;
 
 pub fn menu_plugin(app: &mut App) {
     app.init_state::<MenuState>()
+        .add_plugins((navigation::navigation_plugin, tournament::tournament_menu_plugin))
         .add_systems(OnEnter(GameState::Menu), menu_setup)
         .add_systems(OnEnter(MenuState::Main), main_menu_setup)
-        .add_systems(OnExit(MenuState::Main), despawn_screen::<OnMainMenuScreen>)
+        .add_systems(OnEnter(MenuState::ModeSelect), mode_select_menu_setup)
+        .add_systems(OnEnter(MenuState::Difficulty), difficulty_menu_setup)
+        .add_systems(OnEnter(MenuState::Settings), settings_menu_setup)
+        .add_systems(OnEnter(MenuState::Stats), stats_menu_setup)
+        .add_systems(OnEnter(MenuState::Credits), credits_menu_setup)
+        .add_systems(
+            Update,
+            (menu_action, menu_back, refresh_menu_text).run_if(in_state(GameState::Menu)),
+        )
         .add_systems(
             Update,
-            (button_system, menu_action).run_if(in_state(GameState::Menu)),
+            (
+                control_scheme_dropdown,
+                split_layout_dropdown,
+                volume_slider,
+                effect_toggle,
+                language_dropdown,
+            )
+                .run_if(in_state(MenuState::Settings)),
         );
+    for state in MenuState::SCREENS {
+        app.add_systems(OnExit(state), despawn_screen::<OnMenuScreen>);
+    }
 }
 
-fn menu_setup(mut menu_state: ResMut<NextState<MenuState>>) {
-    menu_state.set(MenuState::Main);
+/// 锦标赛中的比赛结束后回到对阵表，其他情况回到主菜单
+fn menu_setup(versus: Option<Res<LocalVersus>>, mut menu_state: ResMut<NextState<MenuState>>) {
+    if versus.is_some_and(|versus| versus.tournament.is_some()) {
+        menu_state.set(MenuState::Tournament);
+    } else {
+        menu_state.set(MenuState::Main);
+    }
 }
 
 #[derive(Component)]
 struct MenuCamera;
 
-fn main_menu_setup(mut commands: Commands) {
-    commands.spawn((Camera2d, MenuCamera, OnMainMenuScreen));
-    let button_node = Node {
-        // width: Val::Px(100.0),
-        // height: Val::Px(100.0),
-        margin: UiRect::all(Val::Px(10.0)),
-        align_items: AlignItems::Center,
-        justify_content: JustifyContent::Center,
+/// 生成页面的相机和全屏的纵向布局，标题下面是 `content` 生成的内容
+fn spawn_menu_screen(
+    commands: &mut Commands,
+    theme: &UiTheme,
+    title: String,
+    title_size: TextSize,
+    row_gap: f32,
+    content: impl FnOnce(&mut ChildBuilder),
+) {
+    commands.spawn((Camera2d, MenuCamera, OnMenuScreen));
+    commands
+        .spawn((screen_column(row_gap), OnMenuScreen))
+        .with_children(|parent| {
+            parent.spawn((theme.text(title, title_size), OnMenuScreen));
+            content(parent);
+        });
+}
+
+/// 菜单列表中的一个按钮
+fn spawn_menu_button<'a>(
+    parent: &'a mut ChildBuilder,
+    theme: &UiTheme,
+    label: String,
+    action: MenuButtonAction,
+) -> EntityCommands<'a> {
+    parent.spawn((button(theme, label, ButtonKind::Menu), action, OnMenuScreen))
+}
+
+/// 主菜单，下方是控制器地址和扫码连接的二维码
+fn main_menu_setup(mut commands: Commands, theme: Res<UiTheme>, locale: Res<Locale>) {
+    let title = locale.text("menu-title");
+    spawn_menu_screen(&mut commands, &theme, title, TextSize::Hero, 30.0, |parent| {
+        spawn_menu_button(parent, &theme, locale.text("menu-play"), MenuButtonAction::Play);
+        spawn_menu_button(parent, &theme, locale.text("menu-settings"), MenuButtonAction::Settings);
+        spawn_menu_button(parent, &theme, locale.text("menu-stats"), MenuButtonAction::Stats);
+        spawn_menu_button(parent, &theme, locale.text("menu-credits"), MenuButtonAction::Credits);
+        spawn_menu_button(parent, &theme, locale.text("menu-exit"), MenuButtonAction::Quit);
+        parent.spawn((
+            theme.text(String::new(), TextSize::Caption),
+            ServerStatusText,
+            OnMenuScreen,
+        ));
+        parent.spawn((qr_code(String::new(), 160.0), ControllerQrCode, OnMenuScreen));
+    });
+}
+
+/// 模式选择：本地对战、对战电脑、双打、锦标赛、练习、联机和观战
+fn mode_select_menu_setup(
+    mut commands: Commands,
+    rooms: Res<RoomRegistry>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let has_rooms = !rooms.0.lock().unwrap().is_empty();
+    let title = locale.text("menu-mode-select");
+    spawn_menu_screen(&mut commands, &theme, title, TextSize::Title, 20.0, |parent| {
+        spawn_menu_button(parent, &theme, locale.text("menu-local-match"), MenuButtonAction::LocalMatch);
+        spawn_menu_button(parent, &theme, locale.text("menu-versus-ai"), MenuButtonAction::VersusAi);
+        spawn_menu_button(parent, &theme, locale.text("menu-doubles"), MenuButtonAction::Doubles);
+        spawn_menu_button(parent, &theme, locale.text("menu-tournament"), MenuButtonAction::Tournament);
+        spawn_menu_button(parent, &theme, locale.text("menu-practice"), MenuButtonAction::Practice);
+        spawn_menu_button(parent, &theme, locale.text("menu-host-online"), MenuButtonAction::HostOnline);
+        spawn_menu_button(parent, &theme, locale.text("menu-join-online"), MenuButtonAction::JoinOnline);
+        if has_rooms {
+            spawn_menu_button(parent, &theme, locale.text("menu-rooms"), MenuButtonAction::Rooms);
+        }
+        spawn_menu_button(parent, &theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
+}
+
+/// 电脑对手的难度，上次选择的难度带有 [`SelectedOption`]
+fn difficulty_menu_setup(
+    mut commands: Commands,
+    controls: Res<PlayerControls>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let title = locale.text("menu-difficulty");
+    spawn_menu_screen(&mut commands, &theme, title, TextSize::Title, 20.0, |parent| {
+        for difficulty in AiDifficulty::ALL {
+            let mut entity = spawn_menu_button(
+                parent,
+                &theme,
+                locale.text(difficulty.locale_key()),
+                MenuButtonAction::Difficulty(difficulty),
+            );
+            if difficulty == controls.difficulty {
+                entity.insert(SelectedOption);
+            }
+        }
+        spawn_menu_button(parent, &theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
+}
+
+fn credits_menu_setup(mut commands: Commands, theme: Res<UiTheme>, locale: Res<Locale>) {
+    let title = locale.text("menu-credits");
+    spawn_menu_screen(&mut commands, &theme, title, TextSize::Title, 20.0, |parent| {
+        parent.spawn((
+            theme.text(locale.text("credits-body"), TextSize::Body),
+            TextLayout::new_with_justify(JustifyText::Center),
+            OnMenuScreen,
+        ));
+        spawn_menu_button(parent, &theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
+}
+
+/// 下拉框中当前值的位置
+fn option_index<T: PartialEq>(options: &[T], current: T) -> usize {
+    options.iter().position(|option| *option == current).unwrap_or(0)
+}
+
+/// 设置页：控制方式、分屏和语言用下拉框，音量用滑块，视觉效果用开关
+fn settings_menu_setup(
+    mut commands: Commands,
+    controls: Res<PlayerControls>,
+    split: Res<SplitScreen>,
+    sound: Res<SoundSettings>,
+    effects: Res<EffectSettings>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let schemes = ControlScheme::ALL
+        .iter()
+        .map(|scheme| locale.text(scheme.locale_key()))
+        .collect::<Vec<_>>();
+    let layouts = SplitLayout::ALL
+        .iter()
+        .map(|layout| locale.text(layout.locale_key()))
+        .collect::<Vec<_>>();
+    let languages = Language::ALL
+        .iter()
+        .map(|language| locale.language_name(*language).to_string())
+        .collect::<Vec<_>>();
+
+    let title = locale.text("menu-settings");
+    // 四名玩家的控制方式各占一行，行距小一些
+    spawn_menu_screen(&mut commands, &theme, title, TextSize::Title, 12.0, |parent| {
+        for (player, scheme) in controls.schemes.iter().enumerate() {
+            parent
+                .spawn((option_row(), OnMenuScreen))
+                .with_children(|parent| {
+                    parent.spawn((
+                        row_label(&theme, locale.format("controls-player", &[("number", &(player + 1))])),
+                        OnMenuScreen,
+                    ));
+                    parent.spawn((
+                        dropdown(&theme, schemes.clone(), option_index(&ControlScheme::ALL, *scheme)),
+                        ControlSchemeOption { player },
+                        OnMenuScreen,
+                    ));
+                });
+        }
+        parent
+            .spawn((option_row(), OnMenuScreen))
+            .with_children(|parent| {
+                parent.spawn((row_label(&theme, locale.text("controls-split")), OnMenuScreen));
+                parent.spawn((
+                    dropdown(&theme, layouts, option_index(&SplitLayout::ALL, split.layout)),
+                    SplitLayoutOption,
+                    OnMenuScreen,
+                ));
+            });
+        parent
+            .spawn((option_row(), OnMenuScreen))
+            .with_children(|parent| {
+                parent.spawn((row_label(&theme, locale.text("controls-volume")), OnMenuScreen));
+                spawn_slider(
+                    parent,
+                    &theme,
+                    Slider::new(sound.volume, 0.0, 1.0, 0.05),
+                    (VolumeOption, OnMenuScreen),
+                );
+                parent.spawn((
+                    theme.text(SoundSettings::label(sound.volume, &locale), TextSize::Option),
+                    VolumeLabel,
+                    OnMenuScreen,
+                ));
+            });
+        parent
+            .spawn((option_row(), OnMenuScreen))
+            .with_children(|parent| {
+                parent.spawn((row_label(&theme, locale.text("controls-effects")), OnMenuScreen));
+                for effect in Effect::ALL {
+                    parent.spawn((
+                        toggle(&theme, locale.text(effect.locale_key()), effects.enabled(effect)),
+                        EffectOption(effect),
+                        OnMenuScreen,
+                    ));
+                }
+            });
+        parent
+            .spawn((option_row(), OnMenuScreen))
+            .with_children(|parent| {
+                parent.spawn((row_label(&theme, locale.text("controls-language")), OnMenuScreen));
+                parent.spawn((
+                    dropdown(&theme, languages, option_index(&Language::ALL, locale.language)),
+                    LanguageOption,
+                    OnMenuScreen,
+                ));
+            });
+        spawn_menu_button(parent, &theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
+}
 
-        ..default()
+/// 距今多久，例如 "5m ago"
+fn age_label(locale: &Locale, timestamp: u64, now: u64) -> String {
+    let seconds = now.saturating_sub(timestamp);
+    let (id, count) = match seconds {
+        0..60 => return locale.text("age-just-now"),
+        60..3600 => ("age-minutes", seconds / 60),
+        3600..86400 => ("age-hours", seconds / 3600),
+        _ => ("age-days", seconds / 86400),
     };
-    let button_text = TextFont {
-        font_size: 32.0,
-        ..default()
+    locale.format(id, &[("count", &count)])
+}
+
+/// 战绩页：各档案胜率、最近的比赛和练习进度
+fn stats_menu_setup(
+    mut commands: Commands,
+    store: Res<StatsStore>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    let db = &store.db;
+    let now = stats::now();
+
+    let mut profiles = db.profiles.iter().collect::<Vec<_>>();
+    profiles.sort_by_key(|profile| Reverse(profile.last_seen));
+    let profile_lines = profiles
+        .iter()
+        .take(STATS_LIST_LEN)
+        .map(|profile| {
+            let (played, won) = db.record_of(&profile.id);
+            let rate = if played > 0 {
+                format!("{:.0}%", won as f32 / played as f32 * 100.0)
+            } else {
+                "-".to_string()
+            };
+            locale.format(
+                "stats-profile",
+                &[
+                    ("player", &profile.label(&locale)),
+                    ("won", &won),
+                    ("lost", &(played - won)),
+                    ("rate", &rate),
+                    ("age", &age_label(&locale, profile.last_seen, now)),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let match_lines = db
+        .matches
+        .iter()
+        .rev()
+        .take(STATS_LIST_LEN)
+        .map(|record| {
+            let games = record
+                .game_scores
+                .iter()
+                .map(|game| format!("{}-{}", game[0], game[1]))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let result = match record.winner {
+                Some(winner) => locale.format(
+                    "stats-match-won",
+                    &[("player", &db.profile_label(&record.players[winner], &locale))],
+                ),
+                None => locale.text("stats-match-unfinished"),
+            };
+            let mode = match record.mode {
+                MatchMode::Local => locale.text("stats-mode-local"),
+                MatchMode::Online => locale.text("stats-mode-online"),
+            };
+            locale.format(
+                "stats-match",
+                &[
+                    ("mode", &mode),
+                    ("left", &db.profile_label(&record.players[0], &locale)),
+                    ("right", &db.profile_label(&record.players[1], &locale)),
+                    ("left-games", &record.games[0]),
+                    ("right-games", &record.games[1]),
+                    ("games", &games),
+                    ("result", &result),
+                    ("rally", &record.stats.longest_rally),
+                    ("speed", &format!("{:.0}", record.stats.fastest_shot * 3.6)),
+                    ("age", &age_label(&locale, record.finished_at, now)),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let practice_lines = db
+        .practice
+        .iter()
+        .rev()
+        .take(STATS_LIST_LEN)
+        .map(|record| {
+            locale.format(
+                "stats-practice-session",
+                &[
+                    ("shots", &record.shots),
+                    ("duration", &format!("{:.0}", record.duration)),
+                    ("average", &format!("{:.0}", record.average_speed * 3.6)),
+                    ("best", &format!("{:.0}", record.fastest_shot * 3.6)),
+                    ("age", &age_label(&locale, record.started_at, now)),
+                ],
+            )
+        })
+        .collect::<Vec<_>>();
+    // 最近几次练习和更早的练习的平均球速对比
+    let practice_trend = {
+        let split = db.practice.len().saturating_sub(STATS_LIST_LEN);
+        let average = |records: &[stats::PracticeRecord]| {
+            (!records.is_empty()).then(|| {
+                records.iter().map(|record| record.average_speed).sum::<f32>()
+                    / records.len() as f32
+            })
+        };
+        match (average(&db.practice[..split]), average(&db.practice[split..])) {
+            (Some(before), Some(recent)) => Some(locale.format(
+                "stats-practice-trend",
+                &[
+                    ("recent", &format!("{:.0}", recent * 3.6)),
+                    ("change", &format!("{:+.0}", (recent - before) * 3.6)),
+                ],
+            )),
+            _ => None,
+        }
     };
 
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                flex_direction: FlexDirection::Column,
-                row_gap: Val::Px(30.0),
-                ..default()
-            },
-            OnMainMenuScreen,
-        ))
-        .with_children(|parent| {
+    let title = locale.text("menu-stats");
+    spawn_menu_screen(&mut commands, &theme, title, TextSize::Title, 6.0, |parent| {
+        let sections = [
+            ("stats-players", profile_lines, "stats-no-matches"),
+            ("stats-recent-matches", match_lines, "stats-no-matches"),
+            ("stats-practice", practice_lines, "stats-no-practice"),
+        ];
+        for (title, lines, empty) in sections {
             parent.spawn((
-                Text::new("Pong Game"),
-                TextFont {
-                    font_size: 67.0,
+                theme.text(locale.text(title), TextSize::Button),
+                Node {
+                    margin: UiRect::top(Val::Px(14.0)),
                     ..default()
                 },
-                OnMainMenuScreen
-            ));
-            parent.spawn((
-                Text::new("Start Game"),
-                button_text.clone(),
-                Button,
-                button_node.clone(),
-                MenuButtonAction::Play,
-                OnMainMenuScreen
-            ));
-            parent.spawn((
-                Text::new("Practice Mode"),
-                button_text.clone(),
-                Button,
-                button_node.clone(),
-                MenuButtonAction::Practice,
-                OnMainMenuScreen
-            ));
-            parent.spawn((
-                Text::new("Exit"),
-                button_text.clone(),
-                Button,
-                button_node.clone(),
-                MenuButtonAction::Quit,
-                OnMainMenuScreen
+                OnMenuScreen,
             ));
-        });
+            if lines.is_empty() {
+                parent.spawn((theme.text(locale.text(empty), TextSize::Caption), OnMenuScreen));
+            }
+            for text in lines {
+                parent.spawn((theme.text(text, TextSize::Caption), OnMenuScreen));
+            }
+        }
+        if let Some(trend) = practice_trend {
+            parent.spawn((theme.text(trend, TextSize::Caption), OnMenuScreen));
+        }
+        spawn_menu_button(parent, &theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
 }
 
-#[derive(Component)]
-struct SelectedOption;
-
-const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
-const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
-const HOVERED_PRESSED_BUTTON: Color = Color::srgb(0.25, 0.65, 0.25);
-const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.75, 0.35);
-
-fn button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, Option<&SelectedOption>),
-        (Changed<Interaction>, With<Button>),
-    >,
+/// 下拉框生成时也算作修改，只在选择与当前设置不同时写入
+fn control_scheme_dropdown(
+    dropdowns: Query<(&Dropdown, &ControlSchemeOption), Changed<Dropdown>>,
+    mut controls: ResMut<PlayerControls>,
+) {
+    for (dropdown, option) in &dropdowns {
+        let scheme = ControlScheme::ALL[dropdown.selected];
+        if controls.schemes[option.player] != scheme {
+            controls.schemes[option.player] = scheme;
+        }
+    }
+}
+
+fn split_layout_dropdown(
+    dropdowns: Query<&Dropdown, (Changed<Dropdown>, With<SplitLayoutOption>)>,
+    mut split: ResMut<SplitScreen>,
+) {
+    for dropdown in &dropdowns {
+        let layout = SplitLayout::ALL[dropdown.selected];
+        if split.layout != layout {
+            split.layout = layout;
+        }
+    }
+}
+
+/// 拖动滑块时更新音量和右边的数值
+fn volume_slider(
+    sliders: Query<&Slider, (Changed<Slider>, With<VolumeOption>)>,
+    mut labels: Query<&mut Text, With<VolumeLabel>>,
+    mut sound: ResMut<SoundSettings>,
+    locale: Res<Locale>,
+) {
+    for slider in &sliders {
+        if sound.volume == slider.value {
+            continue;
+        }
+        sound.volume = slider.value;
+        for mut label in labels.iter_mut() {
+            label.0 = SoundSettings::label(slider.value, &locale);
+        }
+    }
+}
+
+/// 效果开关各自独立，选中表示开启
+fn effect_toggle(
+    toggles: Query<(&Toggle, &EffectOption), Changed<Toggle>>,
+    mut effects: ResMut<EffectSettings>,
 ) {
-    for (interaction, mut background_color, selected) in &mut interaction_query {
-        *background_color = match (*interaction, selected) {
-            (Interaction::Pressed, _) | (Interaction::None, Some(_)) => PRESSED_BUTTON.into(),
-            (Interaction::Hovered, Some(_)) => HOVERED_PRESSED_BUTTON.into(),
-            (Interaction::Hovered, None) => HOVERED_BUTTON.into(),
-            (Interaction::None, None) => NORMAL_BUTTON.into(),
+    for (toggle, option) in &toggles {
+        if effects.enabled(option.0) != toggle.0 {
+            effects.set(option.0, toggle.0);
         }
     }
 }
 
+fn language_dropdown(
+    dropdowns: Query<&Dropdown, (Changed<Dropdown>, With<LanguageOption>)>,
+    mut locale: ResMut<Locale>,
+) {
+    for dropdown in &dropdowns {
+        let language = Language::ALL[dropdown.selected];
+        if locale.language != language {
+            locale.language = language;
+        }
+    }
+}
+
+/// 切换语言或中文字体加载失败后重新进入当前页面，用新语言重新生成文本
+fn refresh_menu_text(
+    locale: Res<Locale>,
+    menu_state: Res<State<MenuState>>,
+    mut next_menu_state: ResMut<NextState<MenuState>>,
+) {
+    if locale.is_changed() && !locale.is_added() && *menu_state.get() != MenuState::Disabled {
+        next_menu_state.set(*menu_state.get());
+    }
+}
+
+/// 当前菜单页面，以及按钮要切换到的页面和游戏状态
+#[derive(SystemParam)]
+struct MenuStates<'w> {
+    current: Res<'w, State<MenuState>>,
+    menu: ResMut<'w, NextState<MenuState>>,
+    game: ResMut<'w, NextState<GameState>>,
+}
+
 fn menu_action(
-    interaction_query: Query<
-        (&Interaction, &MenuButtonAction),
-        (Changed<Interaction>, With<Button>),
-    >,
+    interaction_query: ButtonActions<MenuButtonAction>,
+    mut commands: Commands,
     mut app_exit_events: EventWriter<AppExit>,
-    mut menu_state: ResMut<NextState<MenuState>>,
-    mut game_state: ResMut<NextState<GameState>>,
+    mut controls: ResMut<PlayerControls>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+    mut states: MenuStates,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
                 MenuButtonAction::Quit => {
+                    spawn_modal(
+                        &mut commands,
+                        &theme,
+                        locale.text("quit-title"),
+                        locale.text("quit-message"),
+                        OnMenuScreen,
+                        |parent| {
+                            parent.spawn((
+                                button(&theme, locale.text("quit-confirm"), ButtonKind::Menu),
+                                MenuButtonAction::ConfirmQuit,
+                            ));
+                            parent.spawn((
+                                button(&theme, locale.text("quit-cancel"), ButtonKind::Menu),
+                                CloseModal,
+                            ));
+                        },
+                    );
+                }
+                MenuButtonAction::ConfirmQuit => {
                     app_exit_events.send(AppExit::Success);
                 }
                 MenuButtonAction::Play => {
-                    game_state.set(GameState::GameEntering);
-                    menu_state.set(MenuState::Disabled);
+                    states.menu.set(MenuState::ModeSelect);
+                }
+                MenuButtonAction::LocalMatch => {
+                    commands.insert_resource(LocalVersus {
+                        names: [1, 2].map(|number| locale.format("controls-player", &[("number", &number)])),
+                        tournament: None,
+                        doubles: false,
+                        ai_opponent: None,
+                    });
+                    states.game.set(GameState::GameEntering);
+                    states.menu.set(MenuState::Disabled);
+                }
+                MenuButtonAction::VersusAi => {
+                    states.menu.set(MenuState::Difficulty);
+                }
+                MenuButtonAction::Doubles => {
+                    let player = |number: usize| locale.format("controls-player", &[("number", &number)]);
+                    let names = [[1, 3], [2, 4]].map(|[first, second]| {
+                        locale.format(
+                            "doubles-team",
+                            &[("first", &player(first)), ("second", &player(second))],
+                        )
+                    });
+                    info!("🏓 本机双打: {} vs {}", names[0], names[1]);
+                    commands.insert_resource(LocalVersus {
+                        names,
+                        tournament: None,
+                        doubles: true,
+                        ai_opponent: None,
+                    });
+                    states.game.set(GameState::GameEntering);
+                    states.menu.set(MenuState::Disabled);
+                }
+                MenuButtonAction::Tournament => {
+                    states.menu.set(MenuState::Tournament);
+                }
+                MenuButtonAction::Difficulty(difficulty) => {
+                    controls.difficulty = *difficulty;
+                    info!("🤖 对战电脑，难度 {:?}", difficulty);
+                    commands.insert_resource(LocalVersus {
+                        names: [
+                            locale.format("controls-player", &[("number", &1)]),
+                            locale.text("scheme-ai"),
+                        ],
+                        tournament: None,
+                        doubles: false,
+                        ai_opponent: Some(*difficulty),
+                    });
+                    states.game.set(GameState::GameEntering);
+                    states.menu.set(MenuState::Disabled);
                 }
                 MenuButtonAction::Practice => {
-                    game_state.set(GameState::GamePracticeEntering);
-                    menu_state.set(MenuState::Disabled);
+                    states.game.set(GameState::GamePracticeEntering);
+                    states.menu.set(MenuState::Disabled);
+                }
+                MenuButtonAction::HostOnline => {
+                    commands.insert_resource(OnlineRole::host_from_env());
+                    states.game.set(GameState::GameOnlineEntering);
+                    states.menu.set(MenuState::Disabled);
+                }
+                MenuButtonAction::JoinOnline => {
+                    commands.insert_resource(OnlineRole::client_from_env());
+                    states.game.set(GameState::GameOnlineEntering);
+                    states.menu.set(MenuState::Disabled);
+                }
+                MenuButtonAction::Rooms => {
+                    states.game.set(GameState::Spectating);
+                    states.menu.set(MenuState::Disabled);
+                }
+                MenuButtonAction::Settings => {
+                    states.menu.set(MenuState::Settings);
+                }
+                MenuButtonAction::Stats => {
+                    states.menu.set(MenuState::Stats);
+                }
+                MenuButtonAction::Credits => {
+                    states.menu.set(MenuState::Credits);
+                }
+                MenuButtonAction::Back => {
+                    if let Some(parent) = states.current.get().parent() {
+                        states.menu.set(parent);
+                    }
                 }
             }
         }
     }
 }
+
+/// 键盘、手柄或手机的返回键回到上一级页面，有弹出层时先关闭弹出层
+fn menu_back(
+    mut inputs: EventReader<MenuInput>,
+    popups: Query<(), With<Popup>>,
+    current_menu: Res<State<MenuState>>,
+    mut menu_state: ResMut<NextState<MenuState>>,
+) {
+    if inputs.read().any(|input| *input == MenuInput::Back)
+        && popups.is_empty()
+        && let Some(parent) = current_menu.get().parent()
+    {
+        menu_state.set(parent);
+    }
+}
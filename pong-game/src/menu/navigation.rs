@@ -0,0 +1,302 @@
+use bevy::input::gamepad::{Gamepad, GamepadButton};
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use bevy::utils::HashSet;
+
+use crate::GameState;
+use crate::components::button::{Focused, SelectedOption};
+use crate::components::slider::Slider;
+use crate::components::text_input::Editing;
+use crate::game::pause::PauseState;
+use crate::game::scoring::{MatchPhase, MatchScore};
+
+/// 摇杆推过该值算一次方向输入，回到 `STICK_RELEASE` 以内后才能再次触发
+const STICK_PRESS: f32 = 0.6;
+const STICK_RELEASE: f32 = 0.3;
+/// 选择下一个焦点时横向偏移的权重，越大越倾向于同一行 / 同一列的按钮
+const ACROSS_WEIGHT: f32 = 2.0;
+
+/// 菜单导航指令，来自键盘、手柄或手机控制器
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Back,
+}
+
+impl MenuInput {
+    /// 解析手机控制器 `nav:<指令>` 消息中的指令
+    pub fn parse(command: &str) -> Option<Self> {
+        match command.trim() {
+            "up" => Some(MenuInput::Up),
+            "down" => Some(MenuInput::Down),
+            "left" => Some(MenuInput::Left),
+            "right" => Some(MenuInput::Right),
+            "select" => Some(MenuInput::Select),
+            "back" => Some(MenuInput::Back),
+            _ => None,
+        }
+    }
+
+    /// 界面坐标中的方向（y 轴向下）
+    fn direction(&self) -> Option<Vec2> {
+        match self {
+            MenuInput::Up => Some(Vec2::NEG_Y),
+            MenuInput::Down => Some(Vec2::Y),
+            MenuInput::Left => Some(Vec2::NEG_X),
+            MenuInput::Right => Some(Vec2::X),
+            MenuInput::Select | MenuInput::Back => None,
+        }
+    }
+}
+
+/// 由导航按下的按钮，下一帧恢复为 `Interaction::None`
+#[derive(Component)]
+struct VirtualPress;
+
+/// 导航在 `UiSystem::Focus` 之后设置 `Interaction`，
+/// Update 中处理按钮的系统与鼠标点击一样在同一帧看到 `Pressed`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MenuNavigationSet;
+
+pub fn navigation_plugin(app: &mut App) {
+    app.add_event::<MenuInput>()
+        .configure_sets(PreUpdate, MenuNavigationSet.after(UiSystem::Focus))
+        .add_systems(
+            PreUpdate,
+            (
+                release_virtual_press,
+                (
+                    (keyboard_menu_input, gamepad_menu_input),
+                    (move_focus, press_focused),
+                )
+                    .chain()
+                    .run_if(navigation_active),
+            )
+                .chain()
+                .in_set(MenuNavigationSet),
+        );
+}
+
+/// 主菜单、暂停菜单和比赛总结页可以导航，比赛进行中方向键留给球拍
+fn navigation_active(
+    game_state: Res<State<GameState>>,
+    pause_state: Option<Res<State<PauseState>>>,
+    score: Option<Res<MatchScore>>,
+) -> bool {
+    *game_state.get() == GameState::Menu
+        || pause_state.is_some_and(|state| *state.get() != PauseState::Playing)
+        || score.is_some_and(|score| matches!(score.phase, MatchPhase::MatchOver { .. }))
+}
+
+fn release_virtual_press(
+    mut commands: Commands,
+    mut pressed: Query<(Entity, &mut Interaction), With<VirtualPress>>,
+) {
+    for (entity, mut interaction) in pressed.iter_mut() {
+        interaction.set_if_neq(Interaction::None);
+        commands.entity(entity).remove::<VirtualPress>();
+    }
+}
+
+/// 方向键移动焦点，Enter / 空格确认，Backspace 返回；
+/// Esc 在比赛中由暂停菜单处理，只在主菜单中作为返回。文本框编辑时键盘留给文本框
+fn keyboard_menu_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    game_state: Res<State<GameState>>,
+    editing: Query<(), With<Editing>>,
+    mut inputs: EventWriter<MenuInput>,
+) {
+    if !editing.is_empty() {
+        return;
+    }
+    let bindings = [
+        (KeyCode::ArrowUp, MenuInput::Up),
+        (KeyCode::ArrowDown, MenuInput::Down),
+        (KeyCode::ArrowLeft, MenuInput::Left),
+        (KeyCode::ArrowRight, MenuInput::Right),
+        (KeyCode::Enter, MenuInput::Select),
+        (KeyCode::NumpadEnter, MenuInput::Select),
+        (KeyCode::Space, MenuInput::Select),
+        (KeyCode::Backspace, MenuInput::Back),
+    ];
+    for (key, input) in bindings {
+        if keys.just_pressed(key) {
+            inputs.send(input);
+        }
+    }
+    if *game_state.get() == GameState::Menu && keys.just_pressed(KeyCode::Escape) {
+        inputs.send(MenuInput::Back);
+    }
+}
+
+/// 十字键或左摇杆移动焦点，A 确认，B 返回
+fn gamepad_menu_input(
+    gamepads: Query<(Entity, &Gamepad)>,
+    mut held_sticks: Local<HashSet<Entity>>,
+    mut inputs: EventWriter<MenuInput>,
+) {
+    let bindings = [
+        (GamepadButton::DPadUp, MenuInput::Up),
+        (GamepadButton::DPadDown, MenuInput::Down),
+        (GamepadButton::DPadLeft, MenuInput::Left),
+        (GamepadButton::DPadRight, MenuInput::Right),
+        (GamepadButton::South, MenuInput::Select),
+        (GamepadButton::East, MenuInput::Back),
+    ];
+    for (entity, gamepad) in gamepads.iter() {
+        for (button, input) in bindings {
+            if gamepad.just_pressed(button) {
+                inputs.send(input);
+            }
+        }
+
+        let stick = gamepad.left_stick();
+        if stick.length() < STICK_RELEASE {
+            held_sticks.remove(&entity);
+        } else if stick.abs().max_element() > STICK_PRESS && held_sticks.insert(entity) {
+            inputs.send(if stick.x.abs() > stick.y.abs() {
+                if stick.x > 0.0 { MenuInput::Right } else { MenuInput::Left }
+            } else if stick.y > 0.0 {
+                MenuInput::Up
+            } else {
+                MenuInput::Down
+            });
+        }
+    }
+}
+
+/// 可以获得焦点的按钮：可见，并且位于最上层（暂停菜单盖住比赛中的按钮）
+fn focus_candidates(
+    buttons: &Query<(Entity, &GlobalTransform, &InheritedVisibility, Has<SelectedOption>), With<Button>>,
+    parents: &Query<&Parent>,
+    layers: &Query<&GlobalZIndex>,
+) -> Vec<(Entity, Vec2, bool)> {
+    let layer = |entity: Entity| {
+        std::iter::once(entity)
+            .chain(parents.iter_ancestors(entity))
+            .find_map(|ancestor| layers.get(ancestor).ok())
+            .map_or(0, |z| z.0)
+    };
+    let visible = buttons
+        .iter()
+        .filter(|(_, _, visibility, _)| visibility.get())
+        .map(|(entity, transform, _, selected)| {
+            (entity, transform.translation().truncate(), selected, layer(entity))
+        })
+        .collect::<Vec<_>>();
+    let top = visible.iter().map(|(_, _, _, layer)| *layer).max().unwrap_or(0);
+    visible
+        .into_iter()
+        .filter(|(_, _, _, layer)| *layer == top)
+        .map(|(entity, position, selected, _)| (entity, position, selected))
+        .collect()
+}
+
+/// 还没有焦点时从选中的选项开始，否则从最上方的按钮开始
+fn initial_focus(candidates: &[(Entity, Vec2, bool)]) -> Option<Entity> {
+    candidates
+        .iter()
+        .find(|(_, _, selected)| *selected)
+        .or_else(|| {
+            candidates
+                .iter()
+                .min_by(|(_, a, _), (_, b, _)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+        })
+        .map(|(entity, _, _)| *entity)
+}
+
+fn set_focus(commands: &mut Commands, focused: &Query<Entity, With<Focused>>, target: Entity) {
+    for entity in focused.iter() {
+        if entity != target {
+            commands.entity(entity).remove::<Focused>();
+        }
+    }
+    commands.entity(target).insert(Focused);
+}
+
+/// 按方向选择最近的按钮，横向偏移越大越不优先。滑块上的左右键由滑块自己处理
+fn move_focus(
+    mut commands: Commands,
+    mut inputs: EventReader<MenuInput>,
+    buttons: Query<(Entity, &GlobalTransform, &InheritedVisibility, Has<SelectedOption>), With<Button>>,
+    parents: Query<&Parent>,
+    layers: Query<&GlobalZIndex>,
+    focused: Query<Entity, With<Focused>>,
+    sliders: Query<(), With<Slider>>,
+) {
+    let directions = inputs
+        .read()
+        .filter_map(|input| input.direction())
+        .collect::<Vec<_>>();
+    if directions.is_empty() {
+        return;
+    }
+    let candidates = focus_candidates(&buttons, &parents, &layers);
+    let current = candidates
+        .iter()
+        .find(|(entity, _, _)| focused.contains(*entity))
+        .map(|(entity, position, _)| (*entity, *position));
+    let Some((mut entity, mut position)) = current else {
+        if let Some(target) = initial_focus(&candidates) {
+            set_focus(&mut commands, &focused, target);
+        }
+        return;
+    };
+
+    for direction in directions {
+        if direction.y == 0.0 && sliders.contains(entity) {
+            continue;
+        }
+        let next = candidates
+            .iter()
+            .filter(|(other, _, _)| *other != entity)
+            .filter_map(|(other, other_position, _)| {
+                let offset = *other_position - position;
+                let along = offset.dot(direction);
+                (along > 1.0).then(|| {
+                    let across = (offset - direction * along).length();
+                    (*other, *other_position, along + across * ACROSS_WEIGHT)
+                })
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+        if let Some((other, other_position, _)) = next {
+            entity = other;
+            position = other_position;
+        }
+    }
+    set_focus(&mut commands, &focused, entity);
+}
+
+/// 确认时按下焦点所在的按钮，处理按钮的系统不需要区分鼠标和导航
+fn press_focused(
+    mut commands: Commands,
+    mut inputs: EventReader<MenuInput>,
+    buttons: Query<(Entity, &GlobalTransform, &InheritedVisibility, Has<SelectedOption>), With<Button>>,
+    parents: Query<&Parent>,
+    layers: Query<&GlobalZIndex>,
+    focused: Query<Entity, With<Focused>>,
+) {
+    if !inputs.read().any(|input| *input == MenuInput::Select) {
+        return;
+    }
+    let candidates = focus_candidates(&buttons, &parents, &layers);
+    match candidates
+        .iter()
+        .find(|(entity, _, _)| focused.contains(*entity))
+    {
+        Some((entity, _, _)) => {
+            commands
+                .entity(*entity)
+                .insert((Interaction::Pressed, VirtualPress));
+        }
+        None => {
+            if let Some(target) = initial_focus(&candidates) {
+                set_focus(&mut commands, &focused, target);
+            }
+        }
+    }
+}
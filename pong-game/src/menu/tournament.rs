@@ -0,0 +1,536 @@
+use bevy::prelude::*;
+
+use super::{
+    MenuButtonAction, MenuState, MenuStates, OnMenuScreen, spawn_menu_button, spawn_menu_screen,
+};
+use crate::GameState;
+use crate::components::button::{ButtonActions, ButtonKind, button};
+use crate::components::dropdown::{Dropdown, dropdown};
+use crate::components::modal::{CloseModal, spawn_modal};
+use crate::components::text_input::{TextInput, text_input};
+use crate::components::theme::{TextSize, UiTheme, option_row, row_label};
+use crate::game::LocalVersus;
+use crate::game::tournament::{
+    BracketMatch, MAX_PLAYERS, Tournament, TournamentFormat, TournamentStore,
+};
+use crate::locale::Locale;
+
+/// 名字的最大长度
+const NAME_MAX_LEN: usize = 16;
+/// 循环赛页面显示的最近结果条数
+const RECENT_RESULTS: usize = 6;
+
+/// 新锦标赛的报名表，开始后保留，下次创建时沿用上次的名字
+#[derive(Resource)]
+struct TournamentDraft {
+    names: Vec<String>,
+    format: TournamentFormat,
+}
+
+impl Default for TournamentDraft {
+    fn default() -> Self {
+        TournamentDraft {
+            names: vec![String::new(); 4],
+            format: TournamentFormat::default(),
+        }
+    }
+}
+
+#[derive(Component)]
+enum TournamentAction {
+    AddPlayer,
+    RemovePlayer(usize),
+    Start,
+    /// 开始下一场比赛
+    Play,
+    /// 弹出确认对话框
+    Abandon,
+    ConfirmAbandon,
+    /// 结束后清除锦标赛，回到报名页
+    Finish,
+}
+
+/// 报名表中第几名玩家的名字
+#[derive(Component)]
+struct DraftName(usize);
+
+#[derive(Component)]
+struct DraftFormat;
+
+pub(super) fn tournament_menu_plugin(app: &mut App) {
+    app.init_resource::<TournamentDraft>()
+        .add_systems(OnEnter(MenuState::Tournament), tournament_menu_setup)
+        .add_systems(
+            Update,
+            (draft_name_input, draft_format_dropdown, tournament_action)
+                .run_if(in_state(MenuState::Tournament)),
+        );
+}
+
+/// 没有进行中的锦标赛时显示报名表，否则显示对阵表
+fn tournament_menu_setup(
+    mut commands: Commands,
+    store: Res<TournamentStore>,
+    draft: Res<TournamentDraft>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+) {
+    match &store.tournament {
+        Some(tournament) => spawn_bracket(&mut commands, &theme, &locale, tournament),
+        None => spawn_registration(&mut commands, &theme, &locale, &draft),
+    }
+}
+
+fn placeholder_name(locale: &Locale, index: usize) -> String {
+    locale.format("tournament-player-placeholder", &[("number", &(index + 1))])
+}
+
+fn spawn_registration(
+    commands: &mut Commands,
+    theme: &UiTheme,
+    locale: &Locale,
+    draft: &TournamentDraft,
+) {
+    let formats = TournamentFormat::ALL
+        .iter()
+        .map(|format| locale.text(format.locale_key()))
+        .collect::<Vec<_>>();
+    let selected = TournamentFormat::ALL
+        .iter()
+        .position(|format| *format == draft.format)
+        .unwrap_or(0);
+
+    let title = locale.text("menu-tournament");
+    spawn_menu_screen(commands, theme, title, TextSize::Title, 10.0, |parent| {
+        parent
+            .spawn((option_row(), OnMenuScreen))
+            .with_children(|parent| {
+                parent.spawn((row_label(theme, locale.text("tournament-format")), OnMenuScreen));
+                parent.spawn((dropdown(theme, formats, selected), DraftFormat, OnMenuScreen));
+            });
+        // 名字较多时排成两列
+        parent
+            .spawn((
+                Node {
+                    max_width: Val::Px(900.0),
+                    flex_wrap: FlexWrap::Wrap,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                OnMenuScreen,
+            ))
+            .with_children(|parent| {
+                for (index, name) in draft.names.iter().enumerate() {
+                    parent
+                        .spawn((option_row(), OnMenuScreen))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                text_input(
+                                    theme,
+                                    name.clone(),
+                                    placeholder_name(locale, index),
+                                    NAME_MAX_LEN,
+                                ),
+                                DraftName(index),
+                                OnMenuScreen,
+                            ));
+                            if draft.names.len() > 2 {
+                                parent.spawn((
+                                    button(theme, locale.text("tournament-remove"), ButtonKind::Option),
+                                    TournamentAction::RemovePlayer(index),
+                                    OnMenuScreen,
+                                ));
+                            }
+                        });
+                }
+            });
+        parent
+            .spawn((option_row(), OnMenuScreen))
+            .with_children(|parent| {
+                if draft.names.len() < MAX_PLAYERS {
+                    parent.spawn((
+                        button(theme, locale.text("tournament-add-player"), ButtonKind::Menu),
+                        TournamentAction::AddPlayer,
+                        OnMenuScreen,
+                    ));
+                }
+                parent.spawn((
+                    button(theme, locale.text("tournament-start"), ButtonKind::Menu),
+                    TournamentAction::Start,
+                    OnMenuScreen,
+                ));
+            });
+        spawn_menu_button(parent, theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
+}
+
+/// 对阵中一方的名字，淘汰赛首轮的空位是轮空，之后的空位是待定
+fn slot_label(locale: &Locale, tournament: &Tournament, round: usize, player: Option<usize>) -> String {
+    match tournament.name(player) {
+        Some(name) => name.to_string(),
+        None if round == 0 => locale.text("tournament-bye"),
+        None => locale.text("tournament-tbd"),
+    }
+}
+
+fn round_label(locale: &Locale, tournament: &Tournament, round: usize) -> String {
+    let remaining = tournament.rounds.len() - round;
+    match (tournament.format, remaining) {
+        (TournamentFormat::SingleElimination, 1) => locale.text("tournament-final"),
+        (TournamentFormat::SingleElimination, 2) => locale.text("tournament-semifinal"),
+        _ => locale.format("tournament-round", &[("number", &(round + 1))]),
+    }
+}
+
+fn result_line(locale: &Locale, tournament: &Tournament, bracket: &BracketMatch) -> String {
+    locale.format(
+        "tournament-result",
+        &[
+            ("left", &slot_label(locale, tournament, 1, bracket.players[0])),
+            ("right", &slot_label(locale, tournament, 1, bracket.players[1])),
+            ("left-games", &bracket.games[0]),
+            ("right-games", &bracket.games[1]),
+        ],
+    )
+}
+
+/// 淘汰赛每一轮一列；循环赛显示积分榜和最近的结果
+fn spawn_bracket(commands: &mut Commands, theme: &UiTheme, locale: &Locale, tournament: &Tournament) {
+    let next = tournament.next_match();
+    let champion = tournament.champion();
+
+    let title = locale.text(tournament.format.locale_key());
+    spawn_menu_screen(commands, theme, title, TextSize::Title, 10.0, |parent| {
+        parent.spawn((
+            theme.text(
+                locale.format(
+                    "tournament-progress",
+                    &[("played", &tournament.played()), ("total", &tournament.total())],
+                ),
+                TextSize::Caption,
+            ),
+            OnMenuScreen,
+        ));
+        parent
+            .spawn((
+                Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(16.0),
+                    ..default()
+                },
+                OnMenuScreen,
+            ))
+            .with_children(|parent| match tournament.format {
+                TournamentFormat::SingleElimination => {
+                    for (round, matches) in tournament.rounds.iter().enumerate() {
+                        spawn_round_column(parent, theme, locale, tournament, round, matches, next);
+                    }
+                }
+                TournamentFormat::RoundRobin => {
+                    spawn_standings(parent, theme, locale, tournament);
+                }
+            });
+
+        match (next, champion) {
+            (Some((round, index)), _) => {
+                let bracket = &tournament.rounds[round][index];
+                parent.spawn((
+                    theme.text(
+                        locale.format(
+                            "tournament-next-match",
+                            &[
+                                ("left", &slot_label(locale, tournament, round, bracket.players[0])),
+                                ("right", &slot_label(locale, tournament, round, bracket.players[1])),
+                            ],
+                        ),
+                        TextSize::Button,
+                    ),
+                    OnMenuScreen,
+                ));
+                parent
+                    .spawn((option_row(), OnMenuScreen))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            button(theme, locale.text("tournament-play"), ButtonKind::Menu),
+                            TournamentAction::Play,
+                            OnMenuScreen,
+                        ));
+                        parent.spawn((
+                            button(theme, locale.text("tournament-abandon"), ButtonKind::Menu),
+                            TournamentAction::Abandon,
+                            OnMenuScreen,
+                        ));
+                    });
+            }
+            (None, champion) => {
+                if let Some(champion) = champion {
+                    parent
+                        .spawn((
+                            theme.text(
+                                locale.format(
+                                    "tournament-champion",
+                                    &[("player", &tournament.players[champion])],
+                                ),
+                                TextSize::Banner,
+                            ),
+                            OnMenuScreen,
+                        ))
+                        .insert(TextColor(theme.accent));
+                }
+                parent.spawn((
+                    button(theme, locale.text("tournament-new"), ButtonKind::Menu),
+                    TournamentAction::Finish,
+                    OnMenuScreen,
+                ));
+            }
+        }
+        spawn_menu_button(parent, theme, locale.text("menu-back"), MenuButtonAction::Back);
+    });
+}
+
+/// 淘汰赛的一轮，胜者用强调色显示，下一场比赛带边框
+fn spawn_round_column(
+    parent: &mut ChildBuilder,
+    theme: &UiTheme,
+    locale: &Locale,
+    tournament: &Tournament,
+    round: usize,
+    matches: &[BracketMatch],
+    next: Option<(usize, usize)>,
+) {
+    parent
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::SpaceAround,
+                align_self: AlignSelf::Stretch,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            OnMenuScreen,
+        ))
+        .with_children(|column| {
+            column.spawn((
+                theme.text(round_label(locale, tournament, round), TextSize::Caption),
+                OnMenuScreen,
+            ));
+            for (index, bracket) in matches.iter().enumerate() {
+                let is_next = next == Some((round, index));
+                column
+                    .spawn((
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            min_width: Val::Px(160.0),
+                            padding: UiRect::axes(Val::Px(8.0), Val::Px(4.0)),
+                            border: UiRect::all(Val::Px(if is_next { 2.0 } else { 0.0 })),
+                            ..default()
+                        },
+                        BackgroundColor(theme.panel),
+                        BorderColor(theme.accent),
+                        OnMenuScreen,
+                    ))
+                    .with_children(|panel| {
+                        for slot in 0..2 {
+                            let mut label = slot_label(locale, tournament, round, bracket.players[slot]);
+                            if bracket.winner.is_some() && bracket.players.iter().all(Option::is_some) {
+                                label = format!("{}  {}", label, bracket.games[slot]);
+                            }
+                            let color = if bracket.winner == Some(slot) {
+                                theme.accent
+                            } else {
+                                theme.text
+                            };
+                            panel
+                                .spawn((theme.text(label, TextSize::Small), OnMenuScreen))
+                                .insert(TextColor(color));
+                        }
+                    });
+            }
+        });
+}
+
+/// 循环赛积分榜和最近的结果
+fn spawn_standings(parent: &mut ChildBuilder, theme: &UiTheme, locale: &Locale, tournament: &Tournament) {
+    let panel = Node {
+        flex_direction: FlexDirection::Column,
+        align_self: AlignSelf::FlexStart,
+        row_gap: Val::Px(4.0),
+        padding: UiRect::all(Val::Px(8.0)),
+        ..default()
+    };
+    parent
+        .spawn((panel.clone(), BackgroundColor(theme.panel), OnMenuScreen))
+        .with_children(|column| {
+            column.spawn((theme.text(locale.text("tournament-standings"), TextSize::Caption), OnMenuScreen));
+            for (rank, standing) in tournament.standings().iter().enumerate() {
+                column.spawn((
+                    theme.text(
+                        locale.format(
+                            "tournament-standing",
+                            &[
+                                ("rank", &(rank + 1)),
+                                ("player", &tournament.players[standing.player]),
+                                ("won", &standing.won),
+                                ("lost", &standing.lost),
+                                ("diff", &format!("{:+}", standing.game_diff)),
+                            ],
+                        ),
+                        TextSize::Small,
+                    ),
+                    OnMenuScreen,
+                ));
+            }
+        });
+    let results = tournament
+        .rounds
+        .iter()
+        .flatten()
+        .filter(|bracket| bracket.winner.is_some())
+        .collect::<Vec<_>>();
+    parent
+        .spawn((panel, BackgroundColor(theme.panel), OnMenuScreen))
+        .with_children(|column| {
+            column.spawn((theme.text(locale.text("tournament-recent"), TextSize::Caption), OnMenuScreen));
+            if results.is_empty() {
+                column.spawn((theme.text(locale.text("tournament-no-results"), TextSize::Small), OnMenuScreen));
+            }
+            for bracket in results.iter().rev().take(RECENT_RESULTS) {
+                column.spawn((
+                    theme.text(result_line(locale, tournament, bracket), TextSize::Small),
+                    OnMenuScreen,
+                ));
+            }
+        });
+}
+
+/// 输入时同步到报名表，增删玩家重新生成页面时不会丢失
+fn draft_name_input(
+    inputs: Query<(&TextInput, &DraftName), Changed<TextInput>>,
+    mut draft: ResMut<TournamentDraft>,
+) {
+    for (input, DraftName(index)) in &inputs {
+        if draft.names.get(*index).is_some_and(|name| *name != input.value) {
+            draft.names[*index] = input.value.clone();
+        }
+    }
+}
+
+fn draft_format_dropdown(
+    dropdowns: Query<&Dropdown, (Changed<Dropdown>, With<DraftFormat>)>,
+    mut draft: ResMut<TournamentDraft>,
+) {
+    for dropdown in &dropdowns {
+        let format = TournamentFormat::ALL[dropdown.selected];
+        if draft.format != format {
+            draft.format = format;
+        }
+    }
+}
+
+/// 报名表中的名字，空名字使用默认名，重名的加上序号
+fn entrant_names(locale: &Locale, draft: &TournamentDraft) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(draft.names.len());
+    for (index, name) in draft.names.iter().enumerate() {
+        let name = name.trim();
+        let base = if name.is_empty() {
+            placeholder_name(locale, index)
+        } else {
+            name.to_string()
+        };
+        let mut unique = base.clone();
+        let mut suffix = 2;
+        while names.contains(&unique) {
+            unique = format!("{} ({})", base, suffix);
+            suffix += 1;
+        }
+        names.push(unique);
+    }
+    names
+}
+
+fn tournament_action(
+    interaction_query: ButtonActions<TournamentAction>,
+    mut commands: Commands,
+    mut draft: ResMut<TournamentDraft>,
+    mut store: ResMut<TournamentStore>,
+    theme: Res<UiTheme>,
+    locale: Res<Locale>,
+    mut states: MenuStates,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match action {
+            TournamentAction::AddPlayer => {
+                if draft.names.len() < MAX_PLAYERS {
+                    draft.names.push(String::new());
+                    states.menu.set(MenuState::Tournament);
+                }
+            }
+            TournamentAction::RemovePlayer(index) => {
+                if draft.names.len() > 2 && *index < draft.names.len() {
+                    draft.names.remove(*index);
+                    states.menu.set(MenuState::Tournament);
+                }
+            }
+            TournamentAction::Start => {
+                let tournament = Tournament::new(draft.format, entrant_names(&locale, &draft));
+                info!(
+                    "🏆 锦标赛开始: {:?}，{} 名玩家，{} 场比赛",
+                    tournament.format,
+                    tournament.players.len(),
+                    tournament.total()
+                );
+                store.set(Some(tournament));
+                states.menu.set(MenuState::Tournament);
+            }
+            TournamentAction::Play => {
+                let Some(tournament) = &store.tournament else {
+                    continue;
+                };
+                let Some((round, index)) = tournament.next_match() else {
+                    continue;
+                };
+                let bracket = &tournament.rounds[round][index];
+                let names = bracket
+                    .players
+                    .map(|player| tournament.name(player).unwrap_or_default().to_string());
+                info!("🏆 锦标赛比赛: {} vs {}", names[0], names[1]);
+                commands.insert_resource(LocalVersus {
+                    names,
+                    tournament: Some((round, index)),
+                    doubles: false,
+                    ai_opponent: None,
+                });
+                states.game.set(GameState::GameEntering);
+                states.menu.set(MenuState::Disabled);
+            }
+            TournamentAction::Abandon => {
+                spawn_modal(
+                    &mut commands,
+                    &theme,
+                    locale.text("tournament-abandon-title"),
+                    locale.text("tournament-abandon-message"),
+                    OnMenuScreen,
+                    |parent| {
+                        parent.spawn((
+                            button(&theme, locale.text("tournament-abandon"), ButtonKind::Menu),
+                            TournamentAction::ConfirmAbandon,
+                        ));
+                        parent.spawn((
+                            button(&theme, locale.text("quit-cancel"), ButtonKind::Menu),
+                            CloseModal,
+                        ));
+                    },
+                );
+            }
+            TournamentAction::ConfirmAbandon | TournamentAction::Finish => {
+                info!("🏆 清除锦标赛");
+                store.set(None);
+                states.menu.set(MenuState::Tournament);
+            }
+        }
+    }
+}